@@ -1,10 +1,13 @@
 //! Build script that extracts resolved dependency metadata from `Cargo.lock`
 //! and exposes it as compile-time environment variables.
 //!
-//! It resolves the ere-guests download source from the workspace lockfile.
+//! It resolves the ere-guests download source from the workspace lockfile and records the git
+//! commit the workspace was built from.
 
 use std::env;
 use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
 
 /// Repository URL used by Cargo for ere-guests git dependencies.
 const ERE_GUESTS_REPO: &str = "https://github.com/eth-act/ere-guests";
@@ -49,6 +52,59 @@ fn main() {
             println!("cargo:rustc-env=ERE_GUESTS_DOWNLOAD_VALUE=unknown");
         }
     }
+
+    emit_git_commit(&workspace_dir);
+}
+
+/// Exposes the workspace git commit as `ERE_HOSTS_GIT_COMMIT`.
+///
+/// An `ERE_HOSTS_GIT_COMMIT` environment variable takes precedence, for builds without a `.git`
+/// directory such as Docker images.
+fn emit_git_commit(workspace_dir: &str) {
+    println!("cargo:rerun-if-env-changed=ERE_HOSTS_GIT_COMMIT");
+    let git_dir = Path::new(workspace_dir).join(".git");
+    for path in git_commit_watch_paths(&git_dir) {
+        println!("cargo:rerun-if-changed={}", path.display());
+    }
+
+    let commit = env::var("ERE_HOSTS_GIT_COMMIT")
+        .ok()
+        .filter(|commit| !commit.is_empty())
+        .or_else(|| {
+            let output = Command::new("git")
+                .args(["rev-parse", "HEAD"])
+                .current_dir(workspace_dir)
+                .output()
+                .ok()?;
+            output
+                .status
+                .success()
+                .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+        })
+        .unwrap_or_else(|| "unknown".to_string());
+    println!("cargo:rustc-env=ERE_HOSTS_GIT_COMMIT={commit}");
+}
+
+/// Returns the files that change when the commit of `HEAD` changes.
+///
+/// These are `HEAD` itself, the ref it points to and `packed-refs`. A ref that is only packed has
+/// no loose file yet, so its directory is watched to notice the file being created. Missing paths
+/// are left out, as Cargo reruns the build script on every build for them.
+fn git_commit_watch_paths(git_dir: &Path) -> Vec<PathBuf> {
+    let head_path = git_dir.join("HEAD");
+    let mut paths = vec![head_path.clone(), git_dir.join("packed-refs")];
+    if let Some(reference) = fs::read_to_string(&head_path)
+        .ok()
+        .and_then(|head| head.strip_prefix("ref:").map(|r| r.trim().to_string()))
+    {
+        let ref_path = git_dir.join(reference);
+        match ref_path.parent().filter(|_| !ref_path.exists()) {
+            Some(ref_dir) => paths.push(ref_dir.to_path_buf()),
+            None => paths.push(ref_path),
+        }
+    }
+    paths.retain(|path| path.exists());
+    paths
 }
 
 /// Extracts the git tag from a Cargo.lock source string.
@@ -0,0 +1,38 @@
+//! One-shot `docker run` commands in the Ere server images
+//!
+//! Used to run tooling shipped in a zkVM's server image, e.g. `ziskemu` or `r0vm`, on files in a
+//! host directory mounted at `/data`.
+
+use ere_dockerized::zkVMKind;
+use std::env;
+use std::path::Path;
+use std::process::Command;
+
+/// ERE commit hash extracted from `Cargo.lock` at build time.
+const DEFAULT_ERE_TAG: &str = ere_dockerized::DOCKER_IMAGE_TAG;
+
+/// Returns a `docker run` command that invokes `entrypoint` in the Ere server image of `kind`,
+/// with `data_dir` mounted at `/data` and `env` set in the container.
+pub(crate) fn server_image_command(
+    kind: zkVMKind,
+    data_dir: &Path,
+    entrypoint: &str,
+    env: &[(&str, &str)],
+) -> Command {
+    let registry_prefix = env::var("ERE_IMAGE_REGISTRY")
+        .map(|r| format!("{r}/"))
+        .unwrap_or_default();
+    let docker_image = format!(
+        "{registry_prefix}ere-server-{}:{DEFAULT_ERE_TAG}",
+        kind.as_str()
+    );
+    let volume_mount = format!("{}:/data", data_dir.display());
+
+    let mut command = Command::new("docker");
+    command.args(["run", "--rm", "-v", &volume_mount]);
+    for (name, value) in env {
+        command.arg("-e").arg(format!("{name}={value}"));
+    }
+    command.args(["--entrypoint", entrypoint, &docker_image]);
+    command
+}
@@ -0,0 +1,334 @@
+//! Self-contained SVG flamegraphs and speedscope profiles from folded stacks
+//!
+//! Frames are laid out bottom-up with the root at the bottom and children sorted by name, as in
+//! Brendan Gregg's `flamegraph.pl`. Every frame carries a `<title>` with its cycles, so the SVG
+//! needs no script to be read in a browser.
+
+use anyhow::{Context, Result};
+use serde_json::json;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use crate::profiling::FoldedStacks;
+
+const IMAGE_WIDTH: f64 = 1200.0;
+const FRAME_HEIGHT: f64 = 16.0;
+const PADDING: f64 = 10.0;
+const TITLE_HEIGHT: f64 = 40.0;
+/// Width available to the frames.
+const FRAMES_WIDTH: f64 = IMAGE_WIDTH - 2.0 * PADDING;
+/// Height of the image without the frames.
+const MARGIN_HEIGHT: f64 = TITLE_HEIGHT + 2.0 * PADDING;
+const FONT_SIZE: f64 = 12.0;
+/// Approximate width of one character at [`FONT_SIZE`].
+const CHAR_WIDTH: f64 = FONT_SIZE * 0.59;
+/// Frames narrower than this are not drawn.
+const MIN_FRAME_WIDTH: f64 = 0.1;
+
+/// Frame of the merged call tree, with the cycles of the candidate and, for diffs, the baseline.
+#[derive(Debug, Default)]
+struct Frame {
+    value: u64,
+    baseline: u64,
+    children: BTreeMap<String, Self>,
+}
+
+impl Frame {
+    /// Adds `weight` cycles to this frame and the frames of `stack` below it.
+    fn add(&mut self, stack: &[String], weight: u64, baseline: bool) {
+        if baseline {
+            self.baseline += weight;
+        } else {
+            self.value += weight;
+        }
+        if let Some((name, rest)) = stack.split_first() {
+            self.children
+                .entry(name.clone())
+                .or_default()
+                .add(rest, weight, baseline);
+        }
+    }
+
+    fn depth(&self) -> usize {
+        self.children
+            .values()
+            .map(|child| child.depth() + 1)
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+fn tree(candidate: &FoldedStacks, baseline: Option<&FoldedStacks>) -> Frame {
+    let mut root = Frame::default();
+    for (stack, weight) in candidate.iter() {
+        root.add(stack, weight, false);
+    }
+    for (stack, weight) in baseline.iter().flat_map(|baseline| baseline.iter()) {
+        root.add(stack, weight, true);
+    }
+    root
+}
+
+/// Renders `folded` as an SVG flamegraph titled `title`.
+pub fn render_svg(folded: &FoldedStacks, title: &str) -> String {
+    render(&tree(folded, None), title, false)
+}
+
+/// Renders a differential flamegraph of `candidate` against `baseline`.
+///
+/// Frame widths are the candidate's cycles. Frames that grew are red and frames that shrank are
+/// blue, with the color saturating at a change of 100%. Frames only in the baseline are not drawn.
+pub fn render_diff_svg(baseline: &FoldedStacks, candidate: &FoldedStacks, title: &str) -> String {
+    render(&tree(candidate, Some(baseline)), title, true)
+}
+
+fn render(root: &Frame, title: &str, diff: bool) -> String {
+    let depth = root.depth() + 1;
+    let height = (depth as f64).mul_add(FRAME_HEIGHT, MARGIN_HEIGHT);
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        r##"<?xml version="1.0" standalone="no"?>
+<svg version="1.1" width="{IMAGE_WIDTH}" height="{height}" viewBox="0 0 {IMAGE_WIDTH} {height}" xmlns="http://www.w3.org/2000/svg">
+<style>text {{ font-family: Verdana, sans-serif; font-size: {FONT_SIZE}px; fill: #000; }} .title {{ font-size: 17px; }}</style>
+<rect x="0" y="0" width="100%" height="100%" fill="#f8f8f8"/>
+<text class="title" x="{}" y="24" text-anchor="middle">{}</text>"##,
+        IMAGE_WIDTH / 2.0,
+        escape_xml(title)
+    );
+
+    if root.value == 0 {
+        let _ = writeln!(
+            out,
+            r#"<text x="{}" y="{}" text-anchor="middle">No cycles recorded</text>"#,
+            IMAGE_WIDTH / 2.0,
+            TITLE_HEIGHT + PADDING
+        );
+    } else {
+        let layout = Layout {
+            scale: FRAMES_WIDTH / root.value as f64,
+            bottom: height - PADDING,
+            total: root.value,
+            diff,
+        };
+        layout.draw(&mut out, "all", root, PADDING, 0);
+    }
+
+    out.push_str("</svg>\n");
+    out
+}
+
+struct Layout {
+    scale: f64,
+    bottom: f64,
+    total: u64,
+    diff: bool,
+}
+
+impl Layout {
+    fn draw(&self, out: &mut String, name: &str, frame: &Frame, x: f64, depth: usize) {
+        let width = frame.value as f64 * self.scale;
+        if width < MIN_FRAME_WIDTH {
+            return;
+        }
+        let y = ((depth + 1) as f64).mul_add(-FRAME_HEIGHT, self.bottom);
+        let percent = frame.value as f64 / self.total as f64 * 100.0;
+        let tooltip = if self.diff {
+            format!(
+                "{name} ({} → {} cycles, {})",
+                frame.baseline,
+                frame.value,
+                format_change(frame.baseline, frame.value)
+            )
+        } else {
+            format!("{name} ({} cycles, {percent:.2}%)", frame.value)
+        };
+        let (r, g, b) = if self.diff {
+            diff_color(frame.baseline, frame.value)
+        } else {
+            hot_color(name)
+        };
+
+        let _ = writeln!(
+            out,
+            r#"<g><title>{}</title><rect x="{x:.2}" y="{y:.2}" width="{width:.2}" height="{}" fill="rgb({r},{g},{b})" rx="2" ry="2"/>{}</g>"#,
+            escape_xml(&tooltip),
+            FRAME_HEIGHT - 1.0,
+            label(name, x, y, width)
+        );
+
+        let mut child_x = x;
+        for (child_name, child) in &frame.children {
+            self.draw(out, child_name, child, child_x, depth + 1);
+            child_x += child.value as f64 * self.scale;
+        }
+    }
+}
+
+/// Returns the frame label, truncated with `..` to fit `width`, or nothing if no text fits.
+fn label(name: &str, x: f64, y: f64, width: f64) -> String {
+    let fits = ((width - 6.0) / CHAR_WIDTH).floor();
+    if fits < 3.0 {
+        return String::new();
+    }
+    let fits = fits as usize;
+    let text = if name.chars().count() <= fits {
+        name.to_string()
+    } else {
+        let truncated: String = name.chars().take(fits - 2).collect();
+        format!("{truncated}..")
+    };
+    format!(
+        r#"<text x="{:.2}" y="{:.2}">{}</text>"#,
+        x + 3.0,
+        y + FRAME_HEIGHT - 4.5,
+        escape_xml(&text)
+    )
+}
+
+fn format_change(baseline: u64, candidate: u64) -> String {
+    if baseline == 0 {
+        return "new".to_string();
+    }
+    let change = (candidate as f64 - baseline as f64) / baseline as f64 * 100.0;
+    format!("{change:+.2}%")
+}
+
+/// Red for frames that grew, blue for frames that shrank, white for unchanged ones.
+fn diff_color(baseline: u64, candidate: u64) -> (u8, u8, u8) {
+    let change = if baseline == 0 {
+        1.0
+    } else {
+        ((candidate as f64 - baseline as f64) / baseline as f64).clamp(-1.0, 1.0)
+    };
+    let fade = change.abs().mul_add(-200.0, 255.0).round() as u8;
+    if change >= 0.0 {
+        (255, fade, fade)
+    } else {
+        (fade, fade, 255)
+    }
+}
+
+/// Stable warm color derived from the frame name.
+fn hot_color(name: &str) -> (u8, u8, u8) {
+    // FNV-1a, so colors are the same across runs and platforms.
+    let hash = name.bytes().fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    });
+    let component = |shift: u32, range: u64| ((hash >> shift) & 0xff) * range / 255;
+    (
+        (205 + component(0, 50)) as u8,
+        component(8, 230) as u8,
+        component(16, 55) as u8,
+    )
+}
+
+fn escape_xml(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Renders `folded` as a speedscope sampled profile named `name`.
+///
+/// Each stack becomes one sample weighted by its cycles. See
+/// <https://github.com/jlfwong/speedscope/wiki/Importing-from-custom-sources>.
+pub fn render_speedscope(folded: &FoldedStacks, name: &str) -> Result<String> {
+    let mut frame_indices: BTreeMap<&str, usize> = BTreeMap::new();
+    let mut frames = Vec::new();
+    let mut samples = Vec::new();
+    let mut weights = Vec::new();
+    for (stack, weight) in folded.iter() {
+        let sample: Vec<usize> = stack
+            .iter()
+            .map(|frame| {
+                *frame_indices.entry(frame.as_str()).or_insert_with(|| {
+                    frames.push(json!({ "name": frame }));
+                    frames.len() - 1
+                })
+            })
+            .collect();
+        samples.push(sample);
+        weights.push(weight);
+    }
+
+    let profile = json!({
+        "$schema": "https://www.speedscope.app/file-format-schema.json",
+        "name": name,
+        "exporter": "ere-hosts",
+        "activeProfileIndex": 0,
+        "shared": { "frames": frames },
+        "profiles": [{
+            "type": "sampled",
+            "name": name,
+            "unit": "none",
+            "startValue": 0,
+            "endValue": folded.total(),
+            "samples": samples,
+            "weights": weights,
+        }],
+    });
+    serde_json::to_string(&profile).context("Failed to serialize speedscope profile")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn folded(lines: &str) -> FoldedStacks {
+        FoldedStacks::parse(lines).unwrap()
+    }
+
+    #[test]
+    fn lays_out_nested_frames_by_cycles() {
+        let tree = tree(&folded("stf 20\nstf;exec 60\nvalidation 20\n"), None);
+        assert_eq!(tree.value, 100);
+        assert_eq!(tree.children["stf"].value, 80);
+        assert_eq!(tree.children["stf"].children["exec"].value, 60);
+        assert_eq!(tree.depth(), 2);
+
+        let svg = render_svg(&folded("stf 20\nstf;exec 60\nvalidation 20\n"), "a <b>");
+        assert!(svg.starts_with("<?xml"));
+        assert!(svg.contains("a &lt;b&gt;"));
+        assert!(svg.contains("<title>stf (80 cycles, 80.00%)</title>"));
+        assert!(svg.contains("<title>exec (60 cycles, 60.00%)</title>"));
+        assert!(svg.trim_end().ends_with("</svg>"));
+
+        assert!(render_svg(&FoldedStacks::new(), "empty").contains("No cycles recorded"));
+    }
+
+    #[test]
+    fn diff_colors_follow_cycle_changes() {
+        let svg = render_diff_svg(
+            &folded("stf 100\nvalidation 50\nremoved 10\n"),
+            &folded("stf 150\nvalidation 25\nnew 5\n"),
+            "diff",
+        );
+        assert!(svg.contains("<title>stf (100 → 150 cycles, +50.00%)</title>"));
+        assert!(svg.contains("<title>new (0 → 5 cycles, new)</title>"));
+        assert!(!svg.contains("removed"));
+
+        assert_eq!(diff_color(100, 100), (255, 255, 255));
+        assert_eq!(diff_color(100, 200), (255, 55, 55));
+        assert_eq!(diff_color(100, 50), (155, 155, 255));
+    }
+
+    #[test]
+    fn speedscope_profiles_share_frames() -> Result<()> {
+        let json = render_speedscope(&folded("stf;exec 60\nstf 20\n"), "block_1")?;
+        let value: serde_json::Value = serde_json::from_str(&json)?;
+
+        assert_eq!(
+            value["shared"]["frames"],
+            json!([{ "name": "stf" }, { "name": "exec" }])
+        );
+        let profile = &value["profiles"][0];
+        assert_eq!(profile["samples"], json!([[0], [0, 1]]));
+        assert_eq!(profile["weights"], json!([20, 60]));
+        assert_eq!(profile["endValue"], 80);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,283 @@
+//! On-disk cache of compiled guest programs
+//!
+//! Artifacts are stored once under their SHA-256 in `blobs/`, and `index/<source>/<guest>.json`
+//! maps a guest program of a given source to the digests of its ELF, verifying key and profiling
+//! ELF. Every read re-hashes the blob, so a corrupted or edited cache entry is never used.
+
+use anyhow::{bail, Context, Result};
+use ere_guests_downloader::CompiledGuest;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
+
+use crate::manifest::sha256_hex;
+
+/// Name of the checksum manifest looked up at an artifact base URL.
+pub const CHECKSUM_MANIFEST_FILE_NAME: &str = "SHA256SUMS";
+
+/// Directory of the guest cache inside the user cache directory.
+const CACHE_SUBDIR: &str = "zkevm-benchmark-workload/guests";
+
+/// Content-addressed cache of compiled guest programs.
+#[derive(Debug, Clone)]
+pub struct GuestCache {
+    root: PathBuf,
+    offline: bool,
+    require_checksums: bool,
+}
+
+/// Digests of the artifacts of one cached guest program.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct CachedGuest {
+    elf: String,
+    program_vk: String,
+    profiling_elf: Option<String>,
+}
+
+impl GuestCache {
+    /// Creates a cache rooted at `root`. When `offline` is set, guests missing from the cache are
+    /// an error instead of being downloaded.
+    pub const fn new(root: PathBuf, offline: bool) -> Self {
+        Self {
+            root,
+            offline,
+            require_checksums: false,
+        }
+    }
+
+    /// Sets whether guest artifacts without a published checksum are an error instead of being
+    /// used with a warning.
+    pub const fn with_required_checksums(mut self, require_checksums: bool) -> Self {
+        self.require_checksums = require_checksums;
+        self
+    }
+
+    /// Returns the default cache directory, `$XDG_CACHE_HOME/zkevm-benchmark-workload/guests`
+    /// falling back to `~/.cache`.
+    pub fn default_dir() -> Option<PathBuf> {
+        env::var_os("XDG_CACHE_HOME")
+            .map(PathBuf::from)
+            .filter(|dir| dir.is_absolute())
+            .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache")))
+            .map(|dir| dir.join(CACHE_SUBDIR))
+    }
+
+    /// Returns the cache directory.
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Returns whether only cached guests may be used.
+    pub const fn is_offline(&self) -> bool {
+        self.offline
+    }
+
+    /// Returns whether every guest artifact must match a published checksum.
+    pub const fn requires_checksums(&self) -> bool {
+        self.require_checksums
+    }
+
+    /// Loads `guest_name` cached for `source_key`.
+    ///
+    /// Returns `None` when the guest is not cached or one of its blobs fails the integrity check.
+    pub(crate) fn load(&self, source_key: &str, guest_name: &str) -> Result<Option<CompiledGuest>> {
+        let index_path = self.index_path(source_key, guest_name);
+        let Ok(bytes) = fs::read(&index_path) else {
+            return Ok(None);
+        };
+        let entry: CachedGuest = serde_json::from_slice(&bytes)
+            .with_context(|| format!("Failed to parse cache index {}", index_path.display()))?;
+
+        let elf = self.read_blob(&entry.elf)?;
+        let program_vk = self.read_blob(&entry.program_vk)?;
+        let profiling_elf = match &entry.profiling_elf {
+            Some(digest) => self.read_blob(digest)?.map(Some),
+            None => Some(None),
+        };
+        let (Some(elf), Some(program_vk), Some(profiling_elf)) = (elf, program_vk, profiling_elf)
+        else {
+            warn!("Discarding corrupted cache entry for {guest_name} ({source_key})");
+            return Ok(None);
+        };
+
+        info!(
+            "Using cached guest program {guest_name} from {}",
+            self.root.display()
+        );
+        Ok(Some(CompiledGuest {
+            elf,
+            program_vk,
+            profiling_elf,
+        }))
+    }
+
+    /// Stores `guest` as `guest_name` for `source_key`.
+    pub(crate) fn store(
+        &self,
+        source_key: &str,
+        guest_name: &str,
+        guest: &CompiledGuest,
+    ) -> Result<()> {
+        let entry = CachedGuest {
+            elf: self.write_blob(&guest.elf)?,
+            program_vk: self.write_blob(&guest.program_vk)?,
+            profiling_elf: guest
+                .profiling_elf
+                .as_deref()
+                .map(|elf| self.write_blob(elf))
+                .transpose()?,
+        };
+        let index_path = self.index_path(source_key, guest_name);
+        let json = serde_json::to_vec_pretty(&entry).context("Failed to serialize cache index")?;
+        write_atomically(&index_path, &json)
+    }
+
+    fn index_path(&self, source_key: &str, guest_name: &str) -> PathBuf {
+        self.root
+            .join("index")
+            .join(source_key)
+            .join(format!("{guest_name}.json"))
+    }
+
+    fn blob_path(&self, digest: &str) -> PathBuf {
+        self.root.join("blobs").join(digest)
+    }
+
+    /// Reads the blob stored under `digest`, returning `None` if it is missing or its content no
+    /// longer hashes to `digest`.
+    fn read_blob(&self, digest: &str) -> Result<Option<Vec<u8>>> {
+        let path = self.blob_path(digest);
+        let Ok(bytes) = fs::read(&path) else {
+            return Ok(None);
+        };
+        if sha256_hex(&bytes) != digest {
+            warn!("Cached blob {} does not match its digest", path.display());
+            fs::remove_file(&path)
+                .with_context(|| format!("Failed to remove corrupted blob {}", path.display()))?;
+            return Ok(None);
+        }
+        Ok(Some(bytes))
+    }
+
+    fn write_blob(&self, bytes: &[u8]) -> Result<String> {
+        let digest = sha256_hex(bytes);
+        let path = self.blob_path(&digest);
+        if !path.exists() {
+            write_atomically(&path, bytes)?;
+        }
+        Ok(digest)
+    }
+}
+
+/// Writes `bytes` to a temporary sibling of `path` and renames it into place.
+fn write_atomically(path: &Path, bytes: &[u8]) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create directory: {}", parent.display()))?;
+    }
+    let tmp_path = path.with_extension(format!("tmp.{}", std::process::id()));
+    fs::write(&tmp_path, bytes)
+        .with_context(|| format!("Failed to write {}", tmp_path.display()))?;
+    fs::rename(&tmp_path, path)
+        .with_context(|| format!("Failed to move {} into place", tmp_path.display()))
+}
+
+/// Parses a `sha256sum` style checksum list into a map from file name to digest.
+pub(crate) fn parse_checksums(text: &str) -> BTreeMap<String, String> {
+    text.lines()
+        .filter_map(|line| {
+            let (digest, file_name) = line.trim().split_once(char::is_whitespace)?;
+            let file_name = file_name.trim_start().trim_start_matches('*');
+            Some((file_name.to_string(), digest.to_ascii_lowercase()))
+        })
+        .collect()
+}
+
+/// Parses a `.sha256` sidecar, which holds the digest optionally followed by the file name.
+pub(crate) fn parse_sidecar(text: &str) -> Option<String> {
+    text.split_whitespace().next().map(str::to_ascii_lowercase)
+}
+
+/// Checks that `bytes` hash to `expected`.
+///
+/// A missing digest is an error when `required` is set and a warning otherwise.
+pub(crate) fn verify_digest(
+    file_name: &str,
+    bytes: &[u8],
+    expected: Option<&str>,
+    required: bool,
+) -> Result<()> {
+    let Some(expected) = expected else {
+        if required {
+            bail!("Checksums are required but none is published for {file_name}");
+        }
+        warn!("No checksum published for {file_name}, skipping integrity check");
+        return Ok(());
+    };
+    let actual = sha256_hex(bytes);
+    if actual != expected {
+        bail!("Checksum mismatch for {file_name}: expected sha256 {expected}, got {actual}");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn guest() -> CompiledGuest {
+        CompiledGuest {
+            elf: b"elf".to_vec(),
+            program_vk: b"vk".to_vec(),
+            profiling_elf: None,
+        }
+    }
+
+    #[test]
+    fn stores_and_loads_guests_by_source() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let cache = GuestCache::new(dir.path().to_path_buf(), false);
+
+        cache.store("tag-v1", "guest-zisk", &guest())?;
+
+        let loaded = cache.load("tag-v1", "guest-zisk")?.expect("cached guest");
+        assert_eq!(loaded.elf, b"elf");
+        assert_eq!(loaded.program_vk, b"vk");
+        assert!(loaded.profiling_elf.is_none());
+        assert!(cache.load("tag-v2", "guest-zisk")?.is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn corrupted_blobs_are_discarded() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let cache = GuestCache::new(dir.path().to_path_buf(), false);
+        cache.store("tag-v1", "guest-zisk", &guest())?;
+
+        fs::write(cache.blob_path(&sha256_hex(b"elf")), b"tampered")?;
+
+        assert!(cache.load("tag-v1", "guest-zisk")?.is_none());
+        assert!(!cache.blob_path(&sha256_hex(b"elf")).exists());
+
+        Ok(())
+    }
+
+    #[test]
+    fn parses_checksum_files() {
+        let sums = parse_checksums("ABCD  guest.elf\nef01 *guest.vk\n\n");
+        assert_eq!(sums.get("guest.elf").map(String::as_str), Some("abcd"));
+        assert_eq!(sums.get("guest.vk").map(String::as_str), Some("ef01"));
+
+        assert_eq!(parse_sidecar("abcd  guest.elf\n").as_deref(), Some("abcd"));
+        assert_eq!(parse_sidecar("\n"), None);
+
+        assert!(verify_digest("guest.elf", b"elf", Some(&sha256_hex(b"elf")), true).is_ok());
+        assert!(verify_digest("guest.elf", b"elf", Some("abcd"), false).is_err());
+        assert!(verify_digest("guest.elf", b"elf", None, false).is_ok());
+        assert!(verify_digest("guest.elf", b"elf", None, true).is_err());
+    }
+}
@@ -0,0 +1,273 @@
+//! Append-only event journal of benchmark runs
+//!
+//! Every zkVM output directory holds an `events.jsonl` timeline next to the run manifest. Each
+//! line is one [`JournalEntry`]: when a run starts, when a fixture starts, is skipped, has its
+//! input prepared, starts and finishes executing, proving or verifying, crashes, and finishes.
+//! Timestamps come from a monotonic clock started with the run, so orchestration tooling can
+//! split the time spent in the zkVM from queueing and overhead time. Runs append to the journal
+//! of earlier runs, each starting with a `run_started` event.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Instant;
+use tracing::warn;
+use zkevm_metrics::chrono::{DateTime, TimeDelta, Utc};
+use zkevm_metrics::CrashInfo;
+
+use crate::manifest::FixtureOutcome;
+use crate::runner::{benchmark_output_dir, benchmark_zkvm_name, RunConfig, ZkVMInstance};
+
+/// File name of the event journal inside a zkVM output directory.
+pub const EVENTS_FILE_NAME: &str = "events.jsonl";
+
+/// One line of the event journal.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JournalEntry {
+    /// Microseconds since the `run_started` event of this run, from a monotonic clock.
+    pub monotonic_us: u64,
+    /// Wall clock time the event was recorded at.
+    pub timestamp: DateTime<Utc>,
+    /// Fixture the event belongs to, absent for run events.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fixture: Option<String>,
+    /// The event.
+    #[serde(flatten)]
+    pub event: RunEvent,
+}
+
+/// Event recorded in the journal, tagged by `event`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum RunEvent {
+    /// A runner process started writing to this output directory.
+    RunStarted {
+        /// zkVM and SDK version, for example `sp1-v5.2.0`.
+        zkvm: String,
+        /// Benchmark action, `execute`, `prove` or `verify`.
+        action: String,
+        /// Id of the runner process.
+        pid: u32,
+    },
+    /// The fixture was picked up.
+    FixtureStarted,
+    /// The guest input of the fixture was serialized.
+    InputPrepared {
+        /// Size of the serialized guest input in bytes.
+        input_bytes: usize,
+    },
+    /// The fixture was not run.
+    Skipped {
+        /// Why the recorded result was kept.
+        reason: String,
+    },
+    /// Execution started, including warm-up iterations.
+    ExecuteStarted,
+    /// Execution finished.
+    ExecuteFinished {
+        /// Outcome of the execution.
+        outcome: FixtureOutcome,
+    },
+    /// Proving started, including warm-up iterations.
+    ProveStarted,
+    /// Proving finished.
+    ProveFinished {
+        /// Outcome of proving.
+        outcome: FixtureOutcome,
+    },
+    /// Standalone verification started, including warm-up iterations.
+    VerifyStarted,
+    /// Standalone verification finished.
+    VerifyFinished {
+        /// Outcome of the verification.
+        outcome: FixtureOutcome,
+    },
+    /// The workload crashed.
+    Crashed {
+        /// Crash reason recorded in the metrics file.
+        reason: String,
+    },
+    /// The metrics file of the fixture was written.
+    FixtureFinished {
+        /// Outcome recorded for the fixture.
+        outcome: FixtureOutcome,
+    },
+}
+
+impl RunEvent {
+    /// The crash event of a crashed workload.
+    pub fn crashed(crash: &CrashInfo) -> Self {
+        Self::Crashed {
+            reason: crash.reason.clone(),
+        }
+    }
+}
+
+/// Event journal of one zkVM output directory, shared by the fixtures of a run.
+#[derive(Debug)]
+pub struct EventJournal {
+    path: PathBuf,
+    file: Mutex<File>,
+    started: Instant,
+}
+
+impl EventJournal {
+    /// Opens the journal of the output directory `zkvm` writes to under `config` and records
+    /// the start of the run.
+    pub fn open(zkvm: &ZkVMInstance, config: &RunConfig) -> Result<Self> {
+        let journal = Self::open_in(&benchmark_output_dir(zkvm, config))?;
+        journal.append(
+            None,
+            RunEvent::RunStarted {
+                zkvm: benchmark_zkvm_name(zkvm),
+                action: config.action.as_str().to_string(),
+                pid: std::process::id(),
+            },
+        );
+        Ok(journal)
+    }
+
+    /// Opens the journal in `output_dir` for appending, creating it if needed.
+    pub(crate) fn open_in(output_dir: &Path) -> Result<Self> {
+        fs::create_dir_all(output_dir)
+            .with_context(|| format!("Failed to create directory {}", output_dir.display()))?;
+        let path = output_dir.join(EVENTS_FILE_NAME);
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .with_context(|| format!("Failed to open event journal {}", path.display()))?;
+        Ok(Self {
+            path,
+            file: Mutex::new(file),
+            started: Instant::now(),
+        })
+    }
+
+    /// Records `event` of `fixture`.
+    ///
+    /// The journal is informational, so a failed write is logged instead of failing the run.
+    pub fn record(&self, fixture: &str, event: RunEvent) {
+        self.append(Some(fixture), event);
+    }
+
+    /// Records `event` of `fixture` as having happened at `at`, for events that are only known
+    /// to be worth recording later.
+    ///
+    /// The entry is appended now, so its line can follow entries with later timestamps.
+    pub fn record_at(&self, fixture: &str, event: RunEvent, at: Instant) {
+        self.append_at(Some(fixture), event, at);
+    }
+
+    fn append(&self, fixture: Option<&str>, event: RunEvent) {
+        self.append_at(fixture, event, Instant::now());
+    }
+
+    fn append_at(&self, fixture: Option<&str>, event: RunEvent, at: Instant) {
+        let since_start = at.saturating_duration_since(self.started);
+        let timestamp = TimeDelta::from_std(at.elapsed())
+            .ok()
+            .and_then(|ago| Utc::now().checked_sub_signed(ago))
+            .unwrap_or_else(Utc::now);
+        let entry = JournalEntry {
+            monotonic_us: u64::try_from(since_start.as_micros()).unwrap_or(u64::MAX),
+            timestamp,
+            fixture: fixture.map(str::to_string),
+            event,
+        };
+        let result = serde_json::to_string(&entry)
+            .map_err(anyhow::Error::from)
+            .and_then(|mut line| {
+                line.push('\n');
+                // A single write keeps lines of concurrent fixtures intact.
+                let mut file = self.file.lock().expect("event journal lock poisoned");
+                file.write_all(line.as_bytes())?;
+                Ok(())
+            });
+        if let Err(err) = result {
+            warn!(
+                "Failed to append to event journal {}: {err}",
+                self.path.display()
+            );
+        }
+    }
+
+    /// Reads every entry of the journal in `output_dir`, returning none if there is no journal.
+    pub fn read(output_dir: &Path) -> Result<Vec<JournalEntry>> {
+        let path = output_dir.join(EVENTS_FILE_NAME);
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read event journal {}", path.display()))?;
+        content
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .enumerate()
+            .map(|(index, line)| {
+                serde_json::from_str(line).with_context(|| {
+                    format!(
+                        "Failed to parse line {} of event journal {}",
+                        index + 1,
+                        path.display()
+                    )
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn appends_fixture_timelines_across_runs() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let journal = EventJournal::open_in(dir.path())?;
+        journal.record("block", RunEvent::FixtureStarted);
+        journal.record("block", RunEvent::InputPrepared { input_bytes: 3 });
+        journal.record("block", RunEvent::ProveStarted);
+        journal.record(
+            "block",
+            RunEvent::ProveFinished {
+                outcome: FixtureOutcome::Crashed,
+            },
+        );
+        journal.record(
+            "block",
+            RunEvent::Crashed {
+                reason: "out of memory".to_string(),
+            },
+        );
+        drop(journal);
+        EventJournal::open_in(dir.path())?.record(
+            "other",
+            RunEvent::Skipped {
+                reason: "up to date in run manifest".to_string(),
+            },
+        );
+
+        let entries = EventJournal::read(dir.path())?;
+        assert_eq!(entries.len(), 6);
+        assert!(entries[..5]
+            .windows(2)
+            .all(|pair| pair[0].monotonic_us <= pair[1].monotonic_us));
+        assert_eq!(entries[1].fixture.as_deref(), Some("block"));
+        assert_eq!(entries[1].event, RunEvent::InputPrepared { input_bytes: 3 });
+        assert_eq!(
+            entries[5].event,
+            RunEvent::Skipped {
+                reason: "up to date in run manifest".to_string()
+            }
+        );
+
+        let content = fs::read_to_string(dir.path().join(EVENTS_FILE_NAME))?;
+        let line = content.lines().nth(3).unwrap();
+        assert!(line.contains(r#""fixture":"block","event":"prove_finished","outcome":"crashed""#));
+        Ok(())
+    }
+}
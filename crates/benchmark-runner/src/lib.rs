@@ -2,10 +2,23 @@
 
 #![cfg_attr(not(test), warn(unused_crate_dependencies))]
 
+pub mod guest_cache;
 pub mod guest_programs;
+pub mod journal;
+pub mod manifest;
 
+pub mod remote;
+mod resources;
+
+mod docker;
+pub mod flamegraph;
+pub mod profiling;
+pub mod progress;
+pub mod risc0_pprof;
+pub mod sp1_trace;
 pub mod stateless_validator;
-pub mod zisk_profiling;
+pub mod zisk_profile;
+mod ziskemu;
 
 pub mod runner;
 pub mod verification;
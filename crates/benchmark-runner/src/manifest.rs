@@ -0,0 +1,508 @@
+//! Run manifest used to resume interrupted or outdated benchmark runs
+//!
+//! Every zkVM output directory holds a `run-manifest.jsonl` that records, per fixture, the
+//! settings the result was produced with and its outcome. A fixture is only skipped on a rerun
+//! when its recorded settings match the current run exactly.
+//!
+//! Each recorded fixture appends one line, so recording stays cheap in folders with many
+//! fixtures. The last line of a fixture wins, and the file is compacted to one line per fixture
+//! when it is opened for a run.
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tracing::{info, warn};
+use zkevm_metrics::{ExecutionMetrics, ProvingMetrics, VerificationMetrics};
+
+use crate::runner::{benchmark_output_dir, RunConfig, ZkVMInstance};
+
+/// File name of the run manifest inside a zkVM output directory.
+pub const RUN_MANIFEST_FILE_NAME: &str = "run-manifest.jsonl";
+
+/// Settings that determine whether an existing fixture result is still valid.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RunFingerprint {
+    /// SHA-256 of the guest ELF, hex encoded.
+    pub elf_sha256: String,
+    /// Program verifying key, hex encoded.
+    pub program_vk: String,
+    /// zkVM SDK version.
+    pub sdk_version: String,
+    /// Benchmark action, `execute`, `prove` or `verify`.
+    pub action: String,
+    /// Timeout applied to each execute or prove request, if any.
+    pub timeout: Option<Duration>,
+}
+
+impl RunFingerprint {
+    /// Builds the fingerprint of running `zkvm` with `config`.
+    pub fn new(zkvm: &ZkVMInstance, config: &RunConfig) -> Self {
+        Self {
+            elf_sha256: zkvm.elf_sha256().to_string(),
+            program_vk: zkvm.program_vk_hex(),
+            sdk_version: zkvm.sdk_version().to_string(),
+            action: config.action.as_str().to_string(),
+            timeout: config.timeout,
+        }
+    }
+
+    /// Returns the name of the first setting that differs from `other`.
+    fn first_difference(&self, other: &Self) -> Option<&'static str> {
+        if self.elf_sha256 != other.elf_sha256 {
+            Some("guest ELF")
+        } else if self.program_vk != other.program_vk {
+            Some("program vk")
+        } else if self.sdk_version != other.sdk_version {
+            Some("zkVM SDK version")
+        } else if self.action != other.action {
+            Some("action")
+        } else if self.timeout != other.timeout {
+            Some("timeout")
+        } else {
+            None
+        }
+    }
+}
+
+/// Outcome of a recorded fixture run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FixtureOutcome {
+    /// The workload completed and its public output matched the fixture.
+    Success,
+    /// The workload completed but its public output did not match the fixture.
+    OutputMismatch,
+    /// The workload crashed.
+    Crashed,
+}
+
+impl FixtureOutcome {
+    /// Classifies execution metrics.
+    pub const fn of_execution(metrics: &ExecutionMetrics) -> Self {
+        match metrics {
+            ExecutionMetrics::Success { output_matched, .. } => Self::completed(*output_matched),
+            ExecutionMetrics::Crashed(_) => Self::Crashed,
+        }
+    }
+
+    /// Classifies proving metrics.
+    pub const fn of_proving(metrics: &ProvingMetrics) -> Self {
+        match metrics {
+            ProvingMetrics::Success { output_matched, .. } => Self::completed(*output_matched),
+            ProvingMetrics::Crashed(_) => Self::Crashed,
+        }
+    }
+
+    /// Classifies standalone verification metrics.
+    pub const fn of_verification(metrics: &VerificationMetrics) -> Self {
+        match metrics {
+            VerificationMetrics::Success { .. } => Self::Success,
+            VerificationMetrics::Crashed(_) => Self::Crashed,
+        }
+    }
+
+    const fn completed(output_matched: bool) -> Self {
+        if output_matched {
+            Self::Success
+        } else {
+            Self::OutputMismatch
+        }
+    }
+}
+
+/// Manifest entry of a single fixture.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ManifestEntry {
+    /// Settings the result was produced with.
+    pub fingerprint: RunFingerprint,
+    /// SHA-256 of the serialized guest input, or of the proof for verification runs, hex
+    /// encoded.
+    pub input_sha256: String,
+    /// Outcome of the run.
+    pub outcome: FixtureOutcome,
+}
+
+/// Line of the manifest file, the entry of one fixture.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct ManifestRecord {
+    fixture: String,
+    #[serde(flatten)]
+    entry: ManifestEntry,
+}
+
+/// Per-fixture record of the results in a zkVM output directory.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RunManifest {
+    /// Entries keyed by fixture name.
+    pub fixtures: BTreeMap<String, ManifestEntry>,
+}
+
+impl RunManifest {
+    /// Loads the manifest of `output_dir`, returning an empty manifest if there is none.
+    ///
+    /// A fixture recorded several times keeps its last entry. A last line cut off by an
+    /// interrupted write is ignored.
+    pub fn load(output_dir: &Path) -> Result<Self> {
+        Ok(Self::read(output_dir)?.0)
+    }
+
+    /// Loads the manifest of `output_dir` together with the number of lines it was read from.
+    fn read(output_dir: &Path) -> Result<(Self, usize)> {
+        let path = output_dir.join(RUN_MANIFEST_FILE_NAME);
+        if !path.exists() {
+            return Ok((Self::default(), 0));
+        }
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read run manifest {}", path.display()))?;
+
+        let mut manifest = Self::default();
+        let lines: Vec<&str> = content.lines().collect();
+        for (index, line) in lines.iter().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            match serde_json::from_str::<ManifestRecord>(line) {
+                Ok(record) => {
+                    manifest.fixtures.insert(record.fixture, record.entry);
+                }
+                Err(err) if index + 1 == lines.len() && !content.ends_with('\n') => {
+                    warn!(
+                        "Ignoring incomplete last line of run manifest {}: {err}",
+                        path.display()
+                    );
+                }
+                Err(err) => bail!(
+                    "Failed to parse line {} of run manifest {}: {err}",
+                    index + 1,
+                    path.display()
+                ),
+            }
+        }
+        Ok((manifest, lines.len()))
+    }
+
+    /// Writes the manifest to `output_dir` with one line per fixture, replacing any previous
+    /// manifest atomically.
+    pub fn save(&self, output_dir: &Path) -> Result<()> {
+        fs::create_dir_all(output_dir)
+            .with_context(|| format!("Failed to create directory {}", output_dir.display()))?;
+        let path = output_dir.join(RUN_MANIFEST_FILE_NAME);
+        let tmp_path = path.with_extension("jsonl.tmp");
+        let mut content = String::new();
+        for (fixture, entry) in &self.fixtures {
+            content.push_str(&record_line(fixture, entry)?);
+        }
+        fs::write(&tmp_path, content)
+            .with_context(|| format!("Failed to write run manifest {}", tmp_path.display()))?;
+        fs::rename(&tmp_path, &path)
+            .with_context(|| format!("Failed to replace run manifest {}", path.display()))
+    }
+
+    /// Appends the entry of `fixture` to the manifest file in `output_dir`.
+    fn append(output_dir: &Path, fixture: &str, entry: &ManifestEntry) -> Result<()> {
+        fs::create_dir_all(output_dir)
+            .with_context(|| format!("Failed to create directory {}", output_dir.display()))?;
+        let path = output_dir.join(RUN_MANIFEST_FILE_NAME);
+        let line = record_line(fixture, entry)?;
+        // A single write keeps the line intact next to other appends.
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .and_then(|mut file| file.write_all(line.as_bytes()))
+            .with_context(|| format!("Failed to append to run manifest {}", path.display()))
+    }
+}
+
+fn record_line(fixture: &str, entry: &ManifestEntry) -> Result<String> {
+    let record = ManifestRecord {
+        fixture: fixture.to_string(),
+        entry: entry.clone(),
+    };
+    let mut line = serde_json::to_string(&record)?;
+    line.push('\n');
+    Ok(line)
+}
+
+/// Controls which previously recorded fixtures are run again.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RerunPolicy {
+    /// Rerun every fixture regardless of the manifest.
+    pub force: bool,
+    /// Rerun fixtures whose recorded run crashed.
+    pub crashed: bool,
+    /// Rerun fixtures whose recorded public output did not match.
+    pub mismatched: bool,
+}
+
+/// Run manifest of one zkVM output directory, shared between fixture selection and result
+/// recording.
+#[derive(Debug, Clone)]
+pub struct RunManifestStore {
+    output_dir: PathBuf,
+    fingerprint: RunFingerprint,
+    policy: RerunPolicy,
+    manifest: Arc<Mutex<RunManifest>>,
+}
+
+impl RunManifestStore {
+    /// Opens the manifest of the output directory `zkvm` writes to under `config`, compacting
+    /// it to one line per fixture first.
+    pub fn open(zkvm: &ZkVMInstance, config: &RunConfig) -> Result<Self> {
+        let output_dir = benchmark_output_dir(zkvm, config);
+        Ok(Self::new(
+            compact(&output_dir)?,
+            output_dir,
+            RunFingerprint::new(zkvm, config),
+            config.rerun,
+        ))
+    }
+
+    /// Creates a store for `output_dir` from an already loaded manifest.
+    pub(crate) fn new(
+        manifest: RunManifest,
+        output_dir: PathBuf,
+        fingerprint: RunFingerprint,
+        policy: RerunPolicy,
+    ) -> Self {
+        Self {
+            output_dir,
+            fingerprint,
+            policy,
+            manifest: Arc::new(Mutex::new(manifest)),
+        }
+    }
+
+    /// Returns why `fixture_name` has to run with `input`, or `None` if its recorded result
+    /// can be kept.
+    pub fn rerun_reason(&self, fixture_name: &str, input: &[u8]) -> Option<String> {
+        if self.policy.force {
+            return Some("--force-rerun".to_string());
+        }
+        if !self.output_exists(fixture_name) {
+            return Some("no metrics output".to_string());
+        }
+
+        let manifest = self.manifest.lock().expect("run manifest lock poisoned");
+        let Some(entry) = manifest.fixtures.get(fixture_name) else {
+            return Some("no run manifest entry".to_string());
+        };
+        if let Some(setting) = entry.fingerprint.first_difference(&self.fingerprint) {
+            return Some(format!("{setting} changed"));
+        }
+        if entry.input_sha256 != sha256_hex(input) {
+            return Some("fixture input changed".to_string());
+        }
+        match entry.outcome {
+            FixtureOutcome::Crashed if self.policy.crashed => {
+                Some("previous run crashed".to_string())
+            }
+            FixtureOutcome::OutputMismatch if self.policy.mismatched => {
+                Some("previous output mismatched".to_string())
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns whether `fixture_name` has to run, logging when it is skipped.
+    pub fn should_run(&self, fixture_name: &str, input: &[u8]) -> bool {
+        match self.rerun_reason(fixture_name, input) {
+            Some(reason) => {
+                if self.output_exists(fixture_name) {
+                    info!("Rerunning {fixture_name} ({reason})");
+                }
+                true
+            }
+            None => {
+                info!("Skipping {fixture_name} (up to date in run manifest)");
+                false
+            }
+        }
+    }
+
+    fn output_exists(&self, fixture_name: &str) -> bool {
+        self.output_dir
+            .join(format!("{fixture_name}.json"))
+            .exists()
+    }
+
+    /// Records the outcome of running `fixture_name` with `input` and appends it to the
+    /// manifest file.
+    pub fn record(&self, fixture_name: &str, input: &[u8], outcome: FixtureOutcome) -> Result<()> {
+        let entry = ManifestEntry {
+            fingerprint: self.fingerprint.clone(),
+            input_sha256: sha256_hex(input),
+            outcome,
+        };
+        let mut manifest = self.manifest.lock().expect("run manifest lock poisoned");
+        RunManifest::append(&self.output_dir, fixture_name, &entry)?;
+        manifest.fixtures.insert(fixture_name.to_string(), entry);
+        Ok(())
+    }
+}
+
+/// Loads the manifest of `output_dir` and rewrites it with one line per fixture if it holds
+/// superseded or incomplete lines.
+fn compact(output_dir: &Path) -> Result<RunManifest> {
+    let (manifest, lines) = RunManifest::read(output_dir)?;
+    if lines > manifest.fixtures.len() {
+        manifest.save(output_dir)?;
+    }
+    Ok(manifest)
+}
+
+/// Returns the hex encoded SHA-256 of `bytes`.
+pub(crate) fn sha256_hex(bytes: &[u8]) -> String {
+    hex::encode(Sha256::digest(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fingerprint() -> RunFingerprint {
+        RunFingerprint {
+            elf_sha256: "aa".to_string(),
+            program_vk: "bb".to_string(),
+            sdk_version: "1.0.0".to_string(),
+            action: "prove".to_string(),
+            timeout: Some(Duration::from_secs(60)),
+        }
+    }
+
+    fn store(
+        dir: &Path,
+        fingerprint: RunFingerprint,
+        policy: RerunPolicy,
+    ) -> Result<RunManifestStore> {
+        Ok(RunManifestStore::new(
+            RunManifest::load(dir)?,
+            dir.to_path_buf(),
+            fingerprint,
+            policy,
+        ))
+    }
+
+    #[test]
+    fn skips_only_matching_recorded_fixtures() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let recorder = store(dir.path(), fingerprint(), RerunPolicy::default())?;
+        assert_eq!(
+            recorder.rerun_reason("block", b"input").as_deref(),
+            Some("no metrics output")
+        );
+
+        fs::write(dir.path().join("block.json"), "{}")?;
+        assert_eq!(
+            recorder.rerun_reason("block", b"input").as_deref(),
+            Some("no run manifest entry")
+        );
+
+        recorder.record("block", b"input", FixtureOutcome::Success)?;
+        let reloaded = store(dir.path(), fingerprint(), RerunPolicy::default())?;
+        assert_eq!(reloaded.rerun_reason("block", b"input"), None);
+        assert_eq!(
+            reloaded.rerun_reason("block", b"other input").as_deref(),
+            Some("fixture input changed")
+        );
+
+        let longer_timeout = RunFingerprint {
+            timeout: Some(Duration::from_secs(120)),
+            ..fingerprint()
+        };
+        let changed = store(dir.path(), longer_timeout, RerunPolicy::default())?;
+        assert_eq!(
+            changed.rerun_reason("block", b"input").as_deref(),
+            Some("timeout changed")
+        );
+
+        let other_elf = RunFingerprint {
+            elf_sha256: "cc".to_string(),
+            ..fingerprint()
+        };
+        let changed = store(dir.path(), other_elf, RerunPolicy::default())?;
+        assert_eq!(
+            changed.rerun_reason("block", b"input").as_deref(),
+            Some("guest ELF changed")
+        );
+
+        let forced = RerunPolicy {
+            force: true,
+            ..RerunPolicy::default()
+        };
+        assert!(store(dir.path(), fingerprint(), forced)?.should_run("block", b"input"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn reruns_crashed_and_mismatched_only_when_requested() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        fs::write(dir.path().join("crashed.json"), "{}")?;
+        fs::write(dir.path().join("mismatched.json"), "{}")?;
+        let recorder = store(dir.path(), fingerprint(), RerunPolicy::default())?;
+        recorder.record("crashed", b"a", FixtureOutcome::Crashed)?;
+        recorder.record("mismatched", b"b", FixtureOutcome::OutputMismatch)?;
+
+        assert!(!recorder.should_run("crashed", b"a"));
+        assert!(!recorder.should_run("mismatched", b"b"));
+
+        let crashed = RerunPolicy {
+            crashed: true,
+            ..RerunPolicy::default()
+        };
+        let recorder = store(dir.path(), fingerprint(), crashed)?;
+        assert!(recorder.should_run("crashed", b"a"));
+        assert!(!recorder.should_run("mismatched", b"b"));
+
+        let mismatched = RerunPolicy {
+            mismatched: true,
+            ..RerunPolicy::default()
+        };
+        let recorder = store(dir.path(), fingerprint(), mismatched)?;
+        assert!(!recorder.should_run("crashed", b"a"));
+        assert!(recorder.should_run("mismatched", b"b"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn records_are_appended_and_compacted_on_open() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join(RUN_MANIFEST_FILE_NAME);
+        let recorder = store(dir.path(), fingerprint(), RerunPolicy::default())?;
+        recorder.record("a", b"a", FixtureOutcome::Crashed)?;
+        recorder.record("b", b"b", FixtureOutcome::Success)?;
+        recorder.record("a", b"a", FixtureOutcome::Success)?;
+        assert_eq!(fs::read_to_string(&path)?.lines().count(), 3);
+
+        // An interrupted append leaves a partial last line behind.
+        let mut file = OpenOptions::new().append(true).open(&path)?;
+        file.write_all(br#"{"fixture":"c","finger"#)?;
+
+        let manifest = compact(dir.path())?;
+        assert_eq!(manifest.fixtures.len(), 2);
+        assert_eq!(manifest.fixtures["a"].outcome, FixtureOutcome::Success);
+        assert_eq!(fs::read_to_string(&path)?.lines().count(), 2);
+        assert_eq!(RunManifest::load(dir.path())?, manifest);
+
+        fs::write(&path, "not json\n")?;
+        assert!(RunManifest::load(dir.path()).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn sha256_hex_matches_known_digest() {
+        assert_eq!(
+            sha256_hex(b"abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+}
@@ -0,0 +1,934 @@
+//! Guest cycle profiling for benchmark runs
+//!
+//! Each zkVM has a [`Profiler`] that runs the backend's profiling tooling in its Ere Docker
+//! image. Every profiler emits cycles per stack of profiling scopes in the folded stack format
+//! (`outer;inner <cycles>`), so profiles of different zkVMs can be read and compared the same way.
+//! Backends with a richer native format also keep their raw output next to it.
+
+use anyhow::{bail, Context, Result};
+use ere_dockerized::{zkVMKind, Input};
+use std::collections::BTreeMap;
+use std::fmt::{self, Write};
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use tracing::info;
+use zkevm_metrics::{BenchmarkRun, ExecutionMetrics, ExecutionProfile};
+
+use crate::docker::server_image_command;
+use crate::runner::ZkVMInstance;
+use crate::zisk_profile::ZiskProfile;
+use crate::ziskemu::{write_emulator_inputs, ziskemu_command};
+use crate::{flamegraph, risc0_pprof, sp1_trace};
+
+/// Frame for the cycles of an execution outside of any profiling scope.
+pub const UNATTRIBUTED_FRAME: &str = "[unattributed]";
+
+/// Configuration for guest profiling.
+#[derive(Debug, Clone)]
+pub struct ProfileConfig {
+    /// Output folder for profile results
+    pub output_folder: PathBuf,
+    /// Whether zkVMs without a native profiler are profiled from their cycle regions with
+    /// [`RegionCyclesProfiler`]. When disabled, profiling them fails.
+    pub region_fallback: bool,
+}
+
+impl ProfileConfig {
+    /// Creates a new `ProfileConfig` without the region cycles fallback.
+    pub const fn new(output_folder: PathBuf) -> Self {
+        Self {
+            output_folder,
+            region_fallback: false,
+        }
+    }
+
+    /// Sets whether zkVMs without a native profiler fall back to [`RegionCyclesProfiler`].
+    pub const fn with_region_fallback(mut self, region_fallback: bool) -> Self {
+        self.region_fallback = region_fallback;
+        self
+    }
+}
+
+/// Outcome of a profiling attempt.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProfileOutcome {
+    /// Profiling succeeded and a `.folded` artifact was written, with the parsed costs when the
+    /// backend reports them.
+    Success(Option<ExecutionProfile>),
+    /// Profiling failed and an `.error.txt` sidecar was written when possible.
+    Failed(String),
+}
+
+/// Cycles attributed to stacks of profiling scopes, outermost scope first.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FoldedStacks {
+    stacks: BTreeMap<Vec<String>, u64>,
+}
+
+impl FoldedStacks {
+    /// Creates an empty profile.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `weight` cycles to `stack`.
+    ///
+    /// Frames are sanitized so they survive the folded format: `;` becomes `:` and whitespace
+    /// becomes `_`. Zero weights are ignored.
+    pub fn add<S: AsRef<str>>(&mut self, stack: &[S], weight: u64) {
+        if weight == 0 || stack.is_empty() {
+            return;
+        }
+        let stack = stack
+            .iter()
+            .map(|frame| sanitize_frame(frame.as_ref()))
+            .collect();
+        *self.stacks.entry(stack).or_default() += weight;
+    }
+
+    /// Iterates over the stacks and their cycles, sorted by stack.
+    pub fn iter(&self) -> impl Iterator<Item = (&[String], u64)> {
+        self.stacks
+            .iter()
+            .map(|(stack, weight)| (stack.as_slice(), *weight))
+    }
+
+    /// Returns whether no cycles were recorded.
+    pub fn is_empty(&self) -> bool {
+        self.stacks.is_empty()
+    }
+
+    /// Returns the cycles summed over all stacks.
+    pub fn total(&self) -> u64 {
+        self.stacks.values().sum()
+    }
+
+    /// Parses the folded stack format, one `frame;frame <weight>` line per stack.
+    ///
+    /// Blank lines are skipped. Fails on lines without a numeric weight.
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut folded = Self::new();
+        for (index, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            let (stack, weight) = line
+                .rsplit_once(' ')
+                .with_context(|| format!("Line {} has no weight: {line}", index + 1))?;
+            let weight = weight
+                .parse()
+                .with_context(|| format!("Line {} has an invalid weight: {line}", index + 1))?;
+            let frames: Vec<&str> = stack.split(';').collect();
+            folded.add(&frames, weight);
+        }
+        Ok(folded)
+    }
+
+    /// Builds a profile from the cycle regions of an execution, attributing the cycles outside of
+    /// all regions to [`UNATTRIBUTED_FRAME`].
+    ///
+    /// Regions nest by name: `stf/execute_tx` is a region inside `stf`. The cycles of a region
+    /// include those of its inner regions, so they are subtracted from the enclosing frame and
+    /// every cycle is counted once. An enclosing region that is not reported itself spans just
+    /// its inner regions. Fails when the inner regions of a region, or the outermost regions,
+    /// report more cycles than their enclosing frame.
+    pub fn from_region_cycles<'a>(
+        total_cycles: u64,
+        regions: impl IntoIterator<Item = (&'a str, u64)>,
+    ) -> Result<Self> {
+        // Cycles of every region including its inner regions, `None` for unreported ones.
+        let mut inclusive: BTreeMap<Vec<&str>, Option<u64>> = BTreeMap::new();
+        for (region, cycles) in regions {
+            let stack: Vec<&str> = region.split('/').collect();
+            for depth in 1..stack.len() {
+                inclusive.entry(stack[..depth].to_vec()).or_default();
+            }
+            let entry = inclusive.entry(stack).or_default();
+            *entry = Some(entry.unwrap_or_default() + cycles);
+        }
+
+        // Cycles of the regions directly inside each region, keyed by the enclosing stack. Inner
+        // stacks sort after their enclosing stack, so walking backwards visits them first.
+        let mut nested: BTreeMap<Vec<&str>, u64> = BTreeMap::new();
+        let mut folded = Self::new();
+        for (stack, cycles) in inclusive.iter().rev() {
+            let inner = nested.get(stack).copied().unwrap_or_default();
+            let cycles = cycles.unwrap_or(inner);
+            *nested.entry(stack[..stack.len() - 1].to_vec()).or_default() += cycles;
+            let exclusive = cycles.checked_sub(inner).with_context(|| {
+                format!(
+                    "Regions inside '{}' report {inner} cycles, more than its {cycles}",
+                    stack.join("/")
+                )
+            })?;
+            folded.add(stack, exclusive);
+        }
+        let outermost = nested.get(&Vec::new()).copied().unwrap_or_default();
+        let unattributed = total_cycles.checked_sub(outermost).with_context(|| {
+            format!("Regions report {outermost} cycles, more than the total {total_cycles}")
+        })?;
+        folded.add(&[UNATTRIBUTED_FRAME], unattributed);
+        Ok(folded)
+    }
+
+    /// Reads and parses the folded stack file at `path`.
+    pub fn from_path(path: &Path) -> Result<Self> {
+        let text = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read folded stacks {}", path.display()))?;
+        Self::parse(&text)
+            .with_context(|| format!("Failed to parse folded stacks {}", path.display()))
+    }
+}
+
+impl fmt::Display for FoldedStacks {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (stack, weight) in self.iter() {
+            writeln!(f, "{} {weight}", stack.join(";"))?;
+        }
+        Ok(())
+    }
+}
+
+fn sanitize_frame(frame: &str) -> String {
+    frame
+        .chars()
+        .map(|c| match c {
+            ';' => ':',
+            c if c.is_whitespace() => '_',
+            c => c,
+        })
+        .collect()
+}
+
+/// Loads the cycle profile stored at `path`.
+///
+/// Accepts folded stacks (`.folded`), Zisk profiles (`.prof`), and `BenchmarkRun` metrics files
+/// (`.json`), whose execution `region_cycles` are read as with [`RegionCyclesProfiler`].
+pub fn load_folded_stacks(path: &Path) -> Result<FoldedStacks> {
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("prof") => Ok(ZiskProfile::from_path(path)?.folded_stacks()),
+        Some("json") => {
+            let run = BenchmarkRun::<serde_json::Value>::from_path(path)
+                .with_context(|| format!("Failed to read metrics file {}", path.display()))?;
+            let Some(ExecutionMetrics::Success {
+                total_num_cycles,
+                region_cycles,
+                ..
+            }) = &run.execution
+            else {
+                bail!(
+                    "{} has no successful execution with cycle regions",
+                    path.display()
+                );
+            };
+            FoldedStacks::from_region_cycles(
+                *total_num_cycles,
+                region_cycles
+                    .iter()
+                    .map(|(region, cycles)| (region.as_str(), *cycles)),
+            )
+        }
+        _ => FoldedStacks::from_path(path),
+    }
+}
+
+/// Result of profiling one fixture.
+#[derive(Debug, Clone, Default)]
+pub struct GuestProfile {
+    /// Cycles per stack of profiling scopes.
+    pub folded: FoldedStacks,
+    /// Native output of the backend's profiler and its file extension, kept next to the folded
+    /// stacks.
+    pub raw: Option<(&'static str, Vec<u8>)>,
+    /// Costs recorded in the fixture's `BenchmarkRun`, when the backend reports them.
+    pub execution_profile: Option<ExecutionProfile>,
+}
+
+/// Profiling tooling of one zkVM backend.
+pub trait Profiler: Send + Sync {
+    /// Profiles the guest of `zkvm` on `input`.
+    fn profile(&self, zkvm: &ZkVMInstance, input: &Input) -> Result<GuestProfile>;
+}
+
+/// Returns the profiler for guests of `zkvm`.
+///
+/// Zisk guests are profiled with `ziskemu` and their `cycle-scope` profiling ELF, SP1 guests with
+/// the executor's `TRACE_FILE` sampling and RISC Zero guests with `r0vm`'s pprof output. The other
+/// zkVMs have no native profiler and get [`RegionCyclesProfiler`] only with `region_fallback`.
+pub fn profiler_for(zkvm: zkVMKind, region_fallback: bool) -> Option<Box<dyn Profiler>> {
+    match zkvm {
+        zkVMKind::Zisk => Some(Box::new(ZiskProfiler)),
+        zkVMKind::SP1 => Some(Box::new(Sp1Profiler)),
+        zkVMKind::Risc0 => Some(Box::new(Risc0Profiler)),
+        _ if region_fallback => Some(Box::new(RegionCyclesProfiler)),
+        _ => None,
+    }
+}
+
+/// Profiles Zisk guests with `ziskemu -X -S -D`, which reports the `PROFILE TAGS` scopes.
+#[derive(Debug, Clone, Copy)]
+pub struct ZiskProfiler;
+
+impl Profiler for ZiskProfiler {
+    fn profile(&self, zkvm: &ZkVMInstance, input: &Input) -> Result<GuestProfile> {
+        let elf = zkvm
+            .profiling_elf()
+            .context("Zisk profiling requires the guest's profiling ELF")?;
+        let stdout = profile_with_ziskemu(&elf.0, input.stdin(), run_ziskemu_command)?;
+
+        let profile = ZiskProfile::parse(&String::from_utf8_lossy(&stdout))
+            .context("Failed to parse ziskemu profile")?;
+        Ok(GuestProfile {
+            folded: profile.folded_stacks(),
+            raw: Some(("prof", stdout)),
+            execution_profile: Some(profile.to_execution_profile()),
+        })
+    }
+}
+
+/// Runs `ziskemu` profiling through `command_runner` and returns its stdout.
+fn profile_with_ziskemu<F>(elf: &[u8], stdin: &[u8], command_runner: F) -> Result<Vec<u8>>
+where
+    F: FnOnce(&Path) -> Result<ProfilingCommandOutput>,
+{
+    let temp_dir = tempfile::tempdir().context("Failed to create temp directory for profiling")?;
+    write_emulator_inputs(temp_dir.path(), elf, stdin)?;
+
+    let output = command_runner(temp_dir.path())?;
+    check_command_output(&output)?;
+    Ok(output.stdout)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ProfilingCommandOutput {
+    success: bool,
+    status_code: Option<i32>,
+    stdout: Vec<u8>,
+    stderr: Vec<u8>,
+}
+
+fn check_command_output(output: &ProfilingCommandOutput) -> Result<()> {
+    if !output.success {
+        bail!(
+            "docker command exited with code {}\nstderr: {}",
+            output.status_code.unwrap_or(-1),
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+    Ok(())
+}
+
+fn run_profiling_command(mut command: Command, zkvm: zkVMKind) -> Result<ProfilingCommandOutput> {
+    let output = command
+        .output()
+        .with_context(|| format!("Failed to execute docker command for {zkvm} profiling"))?;
+
+    Ok(ProfilingCommandOutput {
+        success: output.status.success(),
+        status_code: output.status.code(),
+        stdout: output.stdout,
+        stderr: output.stderr,
+    })
+}
+
+fn run_ziskemu_command(temp_path: &Path) -> Result<ProfilingCommandOutput> {
+    let command = ziskemu_command(
+        temp_path,
+        &[
+            "-X",
+            "-S",
+            "-D",
+            "-e",
+            "/data/program.elf",
+            "-i",
+            "/data/input.bin",
+        ],
+    );
+    run_profiling_command(command, zkVMKind::Zisk)
+}
+
+/// Writes `inputs` to a temporary directory, runs the profiler through `command_runner` on it and
+/// returns the contents of the `output` file it wrote there.
+fn profile_with_tool<F>(
+    inputs: &[(&str, &[u8])],
+    output: &str,
+    command_runner: F,
+) -> Result<Vec<u8>>
+where
+    F: FnOnce(&Path) -> Result<ProfilingCommandOutput>,
+{
+    let temp_dir = tempfile::tempdir().context("Failed to create temp directory for profiling")?;
+    for (name, contents) in inputs {
+        let path = temp_dir.path().join(name);
+        fs::write(&path, contents)
+            .with_context(|| format!("Failed to write {name} to {}", path.display()))?;
+    }
+
+    check_command_output(&command_runner(temp_dir.path())?)?;
+    let output_path = temp_dir.path().join(output);
+    fs::read(&output_path)
+        .with_context(|| format!("Profiler wrote no {output} to {}", output_path.display()))
+}
+
+/// Cycles between two call stack samples of the SP1 executor.
+const SP1_TRACE_SAMPLE_RATE: u64 = 10;
+
+/// Profiles SP1 guests by executing them with `TRACE_FILE` set, which makes the SP1 executor
+/// sample the guest's call stack every [`SP1_TRACE_SAMPLE_RATE`] cycles.
+///
+/// The guest ELF is executed by `sp1-perf-executor` in the SP1 server image. Its Gecko trace is
+/// kept as `.trace.json`, which the Firefox profiler opens.
+#[derive(Debug, Clone, Copy)]
+pub struct Sp1Profiler;
+
+impl Profiler for Sp1Profiler {
+    fn profile(&self, zkvm: &ZkVMInstance, input: &Input) -> Result<GuestProfile> {
+        let elf = zkvm
+            .profiling_elf()
+            .context("SP1 profiling requires the guest ELF")?;
+        let stdin = sp1_trace::encode_stdin(input.stdin());
+        let trace = profile_with_tool(
+            &[("program.elf", elf.0.as_slice()), ("stdin.bin", &stdin[..])],
+            "trace.json",
+            run_sp1_trace_command,
+        )?;
+
+        let folded = sp1_trace::folded_stacks(&trace, SP1_TRACE_SAMPLE_RATE)
+            .context("Failed to parse SP1 trace")?;
+        Ok(GuestProfile {
+            folded,
+            raw: Some(("trace.json", trace)),
+            execution_profile: None,
+        })
+    }
+}
+
+fn run_sp1_trace_command(temp_path: &Path) -> Result<ProfilingCommandOutput> {
+    let sample_rate = SP1_TRACE_SAMPLE_RATE.to_string();
+    let mut command = server_image_command(
+        zkVMKind::SP1,
+        temp_path,
+        "sp1-perf-executor",
+        &[
+            ("TRACE_FILE", "/data/trace.json"),
+            ("TRACE_SAMPLE_RATE", &sample_rate),
+        ],
+    );
+    command.args([
+        "--program",
+        "/data/program.elf",
+        "--stdin",
+        "/data/stdin.bin",
+    ]);
+    run_profiling_command(command, zkVMKind::SP1)
+}
+
+/// Profiles RISC Zero guests by executing them in `r0vm` with `RISC0_PPROF_OUT` set, which
+/// writes the cycles per call stack as a pprof profile.
+///
+/// The pprof profile is kept as `.pb`, which `go tool pprof` reads.
+#[derive(Debug, Clone, Copy)]
+pub struct Risc0Profiler;
+
+impl Profiler for Risc0Profiler {
+    fn profile(&self, zkvm: &ZkVMInstance, input: &Input) -> Result<GuestProfile> {
+        let elf = zkvm
+            .profiling_elf()
+            .context("RISC Zero profiling requires the guest ELF")?;
+        let profile = profile_with_tool(
+            &[
+                ("program.elf", elf.0.as_slice()),
+                ("input.bin", input.stdin()),
+            ],
+            "profile.pb",
+            run_r0vm_pprof_command,
+        )?;
+
+        let folded =
+            risc0_pprof::folded_stacks(&profile).context("Failed to parse RISC Zero profile")?;
+        Ok(GuestProfile {
+            folded,
+            raw: Some(("pb", profile)),
+            execution_profile: None,
+        })
+    }
+}
+
+fn run_r0vm_pprof_command(temp_path: &Path) -> Result<ProfilingCommandOutput> {
+    let mut command = server_image_command(
+        zkVMKind::Risc0,
+        temp_path,
+        "r0vm",
+        &[("RISC0_PPROF_OUT", "/data/profile.pb")],
+    );
+    command.args([
+        "--elf",
+        "/data/program.elf",
+        "--initial-input",
+        "/data/input.bin",
+    ]);
+    run_profiling_command(command, zkVMKind::Risc0)
+}
+
+/// Fallback profiler attributing cycles to the regions guests report, for zkVMs without a native
+/// profiler.
+///
+/// The guest is executed once in the zkVM's Ere server image and its regions are folded with
+/// [`FoldedStacks::from_region_cycles`]: `/` in a region name nests it inside the region named by
+/// its prefix, and cycles outside of all regions are attributed to [`UNATTRIBUTED_FRAME`]. A guest
+/// without regions yields a single [`UNATTRIBUTED_FRAME`] frame with its total cycles.
+#[derive(Debug, Clone, Copy)]
+pub struct RegionCyclesProfiler;
+
+impl Profiler for RegionCyclesProfiler {
+    fn profile(&self, zkvm: &ZkVMInstance, input: &Input) -> Result<GuestProfile> {
+        let (_, report) = zkvm.execute(input).context("Failed to execute guest")?;
+        Ok(GuestProfile {
+            folded: FoldedStacks::from_region_cycles(
+                report.total_num_cycles,
+                report
+                    .region_cycles
+                    .iter()
+                    .map(|(region, cycles)| (region.as_str(), *cycles)),
+            )?,
+            raw: None,
+            execution_profile: None,
+        })
+    }
+}
+
+/// Files of the profile of one fixture, named `<zkvm>_profile_<fixture>.<ext>`.
+#[derive(Debug)]
+struct ProfileArtifacts {
+    title: String,
+    profile_dir: PathBuf,
+    file_stem: String,
+    folded_path: PathBuf,
+    error_path: PathBuf,
+}
+
+impl ProfileArtifacts {
+    fn new(
+        config: &ProfileConfig,
+        zkvm_name: &str,
+        fixture_name: &str,
+        sub_folder: Option<&str>,
+    ) -> Self {
+        let profile_dir = config.output_folder.join(sub_folder.unwrap_or(""));
+        let file_stem = format!("{zkvm_name}_profile_{fixture_name}");
+        let folded_path = profile_dir.join(format!("{file_stem}.folded"));
+        let error_path = profile_dir.join(format!("{file_stem}.error.txt"));
+        Self {
+            title: format!("{zkvm_name} {fixture_name}"),
+            profile_dir,
+            file_stem,
+            folded_path,
+            error_path,
+        }
+    }
+
+    fn path(&self, extension: &str) -> PathBuf {
+        self.profile_dir
+            .join(format!("{}.{extension}", self.file_stem))
+    }
+}
+
+/// Extensions of the flamegraph and speedscope renderings of the folded stacks.
+const SVG_EXTENSION: &str = "svg";
+const SPEEDSCOPE_EXTENSION: &str = "speedscope.json";
+
+/// Extensions of the profile files removed when a profile of the fixture is rewritten or fails.
+const PROFILE_EXTENSIONS: &[&str] = &[
+    "folded",
+    SVG_EXTENSION,
+    SPEEDSCOPE_EXTENSION,
+    "prof",
+    "trace.json",
+    "pb",
+];
+
+/// Profiles a single fixture with the profiler of its zkVM.
+pub fn run_profiling(
+    config: &ProfileConfig,
+    zkvm: &ZkVMInstance,
+    input: &Input,
+    fixture_name: &str,
+    sub_folder: Option<&str>,
+) -> ProfileOutcome {
+    let artifacts = ProfileArtifacts::new(config, zkvm.name(), fixture_name, sub_folder);
+    info!("Running {} profiling for {}", zkvm.name(), fixture_name);
+
+    let result = profiler_for(zkvm.zkvm_kind(), config.region_fallback)
+        .with_context(|| {
+            format!(
+                "{} has no native profiler, enable the region cycles fallback to profile it",
+                zkvm.name()
+            )
+        })
+        .and_then(|profiler| profiler.profile(zkvm, input))
+        .with_context(|| {
+            format!(
+                "{} profiling failed for fixture '{fixture_name}'",
+                zkvm.name()
+            )
+        });
+    record_profile(&artifacts, fixture_name, result)
+}
+
+fn record_profile(
+    artifacts: &ProfileArtifacts,
+    fixture_name: &str,
+    result: Result<GuestProfile>,
+) -> ProfileOutcome {
+    match result.and_then(|profile| write_profile(artifacts, profile)) {
+        Ok(execution_profile) => ProfileOutcome::Success(execution_profile),
+        Err(err) => record_profiling_failure(artifacts, fixture_name, err),
+    }
+}
+
+fn write_profile(
+    artifacts: &ProfileArtifacts,
+    profile: GuestProfile,
+) -> Result<Option<ExecutionProfile>> {
+    fs::create_dir_all(&artifacts.profile_dir).with_context(|| {
+        format!(
+            "Failed to create profile directory: {}",
+            artifacts.profile_dir.display()
+        )
+    })?;
+
+    remove_profile_files(artifacts)?;
+    if let Some((extension, bytes)) = &profile.raw {
+        let raw_path = artifacts.path(extension);
+        fs::write(&raw_path, bytes)
+            .with_context(|| format!("Failed to write profile to {}", raw_path.display()))?;
+    }
+    let renderings = [
+        (artifacts.folded_path.clone(), profile.folded.to_string()),
+        (
+            artifacts.path(SVG_EXTENSION),
+            flamegraph::render_svg(&profile.folded, &artifacts.title),
+        ),
+        (
+            artifacts.path(SPEEDSCOPE_EXTENSION),
+            flamegraph::render_speedscope(&profile.folded, &artifacts.title)?,
+        ),
+    ];
+    for (path, contents) in renderings {
+        fs::write(&path, contents)
+            .with_context(|| format!("Failed to write profile to {}", path.display()))?;
+    }
+
+    remove_file_if_exists(&artifacts.error_path).with_context(|| {
+        format!(
+            "Failed to remove stale profiling error file {}",
+            artifacts.error_path.display()
+        )
+    })?;
+
+    info!("Saved profile to {}", artifacts.folded_path.display());
+
+    Ok(profile.execution_profile)
+}
+
+/// Removes the profile files of a previous run of the fixture.
+fn remove_profile_files(artifacts: &ProfileArtifacts) -> Result<()> {
+    for path in PROFILE_EXTENSIONS
+        .iter()
+        .map(|extension| artifacts.path(extension))
+    {
+        remove_file_if_exists(&path)
+            .with_context(|| format!("Failed to remove stale profile {}", path.display()))?;
+    }
+    Ok(())
+}
+
+fn record_profiling_failure(
+    artifacts: &ProfileArtifacts,
+    fixture_name: &str,
+    err: anyhow::Error,
+) -> ProfileOutcome {
+    let mut message = format!("{err:#}");
+
+    if let Err(remove_err) = remove_profile_files(artifacts) {
+        let _ = write!(message, "\nAdditionally failed: {remove_err:#}");
+    }
+
+    let sidecar = format!(
+        "fixture: {fixture_name}\ntimestamp_utc: {}\nerror:\n{message}\n",
+        zkevm_metrics::chrono::Utc::now().to_rfc3339()
+    );
+
+    match write_failure_sidecar(artifacts, &sidecar) {
+        Ok(()) => ProfileOutcome::Failed(message),
+        Err(write_err) => ProfileOutcome::Failed(format!(
+            "{message}\nAdditionally failed to write profiling error artifact {}: {}",
+            artifacts.error_path.display(),
+            write_err
+        )),
+    }
+}
+
+fn write_failure_sidecar(artifacts: &ProfileArtifacts, sidecar: &str) -> Result<()> {
+    fs::create_dir_all(&artifacts.profile_dir).with_context(|| {
+        format!(
+            "Failed to create profile directory: {}",
+            artifacts.profile_dir.display()
+        )
+    })?;
+    fs::write(&artifacts.error_path, sidecar).with_context(|| {
+        format!(
+            "Failed to write profiling error file to {}",
+            artifacts.error_path.display()
+        )
+    })?;
+    info!(
+        "Saved profiling error to {}",
+        artifacts.error_path.display()
+    );
+    Ok(())
+}
+
+fn remove_file_if_exists(path: &Path) -> Result<()> {
+    match fs::remove_file(path) {
+        Ok(()) => Ok(()),
+        Err(err) if err.kind() == ErrorKind::NotFound => Ok(()),
+        Err(err) => Err(err.into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::remote::RemoteProver;
+    use ere_dockerized::{
+        EncodedProof, ProgramExecutionReport, ProgramProvingReport, PublicValues,
+    };
+    use zkevm_metrics::GuestSource;
+
+    fn artifacts(dir: &Path) -> ProfileArtifacts {
+        ProfileArtifacts::new(
+            &ProfileConfig::new(dir.to_path_buf()),
+            "zisk",
+            "block_1",
+            Some("reth"),
+        )
+    }
+
+    #[test]
+    fn folded_stacks_round_trip_and_sanitize_frames() -> Result<()> {
+        let mut folded = FoldedStacks::new();
+        folded.add(&["stf", "execute tx"], 30);
+        folded.add(&["stf"], 10);
+        folded.add(&["a;b"], 5);
+        folded.add(&["stf"], 2);
+        folded.add(&["empty"], 0);
+
+        let text = folded.to_string();
+        assert_eq!(text, "a:b 5\nstf 12\nstf;execute_tx 30\n");
+        assert_eq!(FoldedStacks::parse(&text)?, folded);
+        assert_eq!(folded.total(), 47);
+        assert!(FoldedStacks::parse("stf ten\n").is_err());
+
+        Ok(())
+    }
+
+    struct RegionProver;
+
+    impl RemoteProver for RegionProver {
+        fn zkvm_kind(&self) -> zkVMKind {
+            zkVMKind::OpenVM
+        }
+
+        fn sdk_version(&self) -> &str {
+            "1.0.0"
+        }
+
+        fn program_vk(&self) -> Vec<u8> {
+            Vec::new()
+        }
+
+        fn execute(&self, _input: &Input) -> Result<(PublicValues, ProgramExecutionReport)> {
+            let mut report = ProgramExecutionReport {
+                total_num_cycles: 100,
+                ..Default::default()
+            };
+            report.region_cycles.insert("stf".to_string(), 60);
+            report
+                .region_cycles
+                .insert("stf/execute_tx".to_string(), 45);
+            Ok((Vec::new(), report))
+        }
+
+        fn prove(
+            &self,
+            _input: &Input,
+        ) -> Result<(PublicValues, EncodedProof, ProgramProvingReport)> {
+            bail!("region prover does not prove")
+        }
+
+        fn verify(&self, _proof: &EncodedProof) -> Result<PublicValues> {
+            bail!("region prover does not verify")
+        }
+    }
+
+    #[test]
+    fn region_cycles_nest_by_name_and_count_each_cycle_once() -> Result<()> {
+        let folded = FoldedStacks::from_region_cycles(
+            100,
+            [
+                ("stf", 70),
+                ("stf/execute_tx", 50),
+                ("stf/execute_tx/sload", 20),
+                ("verify/root", 10),
+            ],
+        )?;
+        assert_eq!(
+            folded.to_string(),
+            "[unattributed] 20\nstf 20\nstf;execute_tx 30\nstf;execute_tx;sload 20\nverify;root 10\n"
+        );
+        assert_eq!(folded.total(), 100);
+
+        let err = FoldedStacks::from_region_cycles(100, [("stf", 10), ("stf/execute_tx", 20)])
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Regions inside 'stf' report 20 cycles, more than its 10"
+        );
+        assert!(FoldedStacks::from_region_cycles(10, [("stf", 20)]).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn region_cycles_fallback_is_opt_in() -> Result<()> {
+        for kind in [zkVMKind::Zisk, zkVMKind::SP1, zkVMKind::Risc0] {
+            assert!(profiler_for(kind, false).is_some());
+        }
+        assert!(profiler_for(zkVMKind::OpenVM, false).is_none());
+
+        let zkvm = ZkVMInstance::Remote {
+            prover: Box::new(RegionProver),
+            elf_sha256: "aa".to_string(),
+            guest_source: GuestSource::LocalPath {
+                path: "guests".to_string(),
+            },
+            profiling_elf: None,
+        };
+        let profiler = profiler_for(zkvm.zkvm_kind(), true).context("fallback profiler")?;
+        let profile = profiler.profile(&zkvm, &Input::new())?;
+        assert_eq!(
+            profile.folded.to_string(),
+            "[unattributed] 40\nstf 15\nstf;execute_tx 45\n"
+        );
+        assert!(profile.raw.is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn tool_profiles_are_read_from_the_data_dir() -> Result<()> {
+        let output =
+            profile_with_tool(&[("stdin.bin", b"input".as_slice())], "trace.json", |dir| {
+                assert_eq!(fs::read(dir.join("stdin.bin"))?, b"input");
+                fs::write(dir.join("trace.json"), "{}")?;
+                Ok(ProfilingCommandOutput {
+                    success: true,
+                    status_code: Some(0),
+                    stdout: Vec::new(),
+                    stderr: Vec::new(),
+                })
+            })?;
+        assert_eq!(output, b"{}");
+
+        let err = profile_with_tool(&[], "profile.pb", |_| {
+            Ok(ProfilingCommandOutput {
+                success: true,
+                status_code: Some(0),
+                stdout: Vec::new(),
+                stderr: Vec::new(),
+            })
+        })
+        .unwrap_err();
+        assert!(err.to_string().starts_with("Profiler wrote no profile.pb"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn successful_profiles_replace_stale_errors() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let artifacts = artifacts(dir.path());
+        fs::create_dir_all(&artifacts.profile_dir)?;
+        fs::write(&artifacts.error_path, "old failure")?;
+
+        let mut folded = FoldedStacks::new();
+        folded.add(&["stf"], 7);
+        let profile = GuestProfile {
+            folded,
+            raw: Some(("prof", b"raw".to_vec())),
+            execution_profile: Some(ExecutionProfile::default()),
+        };
+
+        let outcome = record_profile(&artifacts, "block_1", Ok(profile));
+        assert_eq!(
+            outcome,
+            ProfileOutcome::Success(Some(ExecutionProfile::default()))
+        );
+        assert_eq!(fs::read_to_string(&artifacts.folded_path)?, "stf 7\n");
+        assert!(fs::read_to_string(artifacts.path("svg"))?.contains("zisk block_1"));
+        assert!(artifacts.path("speedscope.json").exists());
+        assert_eq!(
+            fs::read(dir.path().join("reth/zisk_profile_block_1.prof"))?,
+            b"raw"
+        );
+        assert!(!artifacts.error_path.exists());
+
+        Ok(())
+    }
+
+    #[test]
+    fn failures_write_sidecar_and_remove_stale_profiles() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let artifacts = artifacts(dir.path());
+        fs::create_dir_all(&artifacts.profile_dir)?;
+        fs::write(&artifacts.folded_path, "stf 7\n")?;
+        fs::write(artifacts.path("prof"), "raw")?;
+        fs::write(artifacts.path("svg"), "<svg/>")?;
+
+        let outcome = record_profile(
+            &artifacts,
+            "block_1",
+            Err(anyhow::anyhow!("ziskemu crashed")),
+        );
+        assert_eq!(outcome, ProfileOutcome::Failed("ziskemu crashed".into()));
+        let sidecar = fs::read_to_string(&artifacts.error_path)?;
+        assert!(sidecar.starts_with("fixture: block_1\n"));
+        assert!(sidecar.ends_with("error:\nziskemu crashed\n"));
+        assert!(!artifacts.folded_path.exists());
+        assert!(!artifacts.path("prof").exists());
+        assert!(!artifacts.path("svg").exists());
+
+        Ok(())
+    }
+
+    #[test]
+    fn failed_ziskemu_runs_report_stderr() {
+        let err = profile_with_ziskemu(b"elf", b"input", |_| {
+            Ok(ProfilingCommandOutput {
+                success: false,
+                status_code: Some(3),
+                stdout: Vec::new(),
+                stderr: b"out of memory".to_vec(),
+            })
+        })
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "docker command exited with code 3\nstderr: out of memory"
+        );
+    }
+}
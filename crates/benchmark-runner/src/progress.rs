@@ -0,0 +1,267 @@
+//! Prometheus metrics of benchmark progress
+//!
+//! Long sweeps expose how many fixtures completed, were skipped or crashed, which fixtures are
+//! running, and the distribution of cycles, proving and verification time per zkVM. The
+//! registry is either served over HTTP or written as a textfile after every fixture.
+
+use std::path::PathBuf;
+use std::sync::Arc;
+use tracing::warn;
+use zkevm_metrics::{
+    prometheus::{exponential_buckets, Registry},
+    ExecutionMetrics, ProvingMetrics, VerificationMetrics,
+};
+
+use crate::manifest::FixtureOutcome;
+use crate::runner::{benchmark_zkvm_name, RunConfig, ZkVMInstance};
+
+/// Fixtures by outcome: `success`, `output_mismatch`, `crashed` or `skipped`.
+pub const FIXTURES_TOTAL: &str = "zkevm_benchmark_fixtures_total";
+/// Fixtures currently running, one series per fixture with value 1.
+pub const CURRENT_FIXTURE: &str = "zkevm_benchmark_current_fixture";
+/// Total cycles of successful executions.
+pub const EXECUTION_CYCLES: &str = "zkevm_benchmark_execution_cycles";
+/// Proving time of successful proofs in seconds.
+pub const PROVING_SECONDS: &str = "zkevm_benchmark_proving_seconds";
+/// Verification time of successful standalone verifications in seconds.
+pub const VERIFICATION_SECONDS: &str = "zkevm_benchmark_verification_seconds";
+
+/// Benchmark progress metrics shared by every zkVM of a run.
+#[derive(Debug)]
+pub struct ProgressMetrics {
+    registry: Arc<Registry>,
+    textfile: Option<PathBuf>,
+}
+
+impl ProgressMetrics {
+    /// Registers the progress metrics in `registry`, rewriting `textfile` after every update when
+    /// given.
+    pub fn new(registry: Arc<Registry>, textfile: Option<PathBuf>) -> Self {
+        registry.register_counter(FIXTURES_TOTAL, "Fixtures processed, by outcome");
+        registry.register_gauge(CURRENT_FIXTURE, "Fixtures currently running");
+        registry.register_histogram(
+            EXECUTION_CYCLES,
+            "Total cycles of successful executions",
+            &exponential_buckets(1e6, 4.0, 12),
+        );
+        registry.register_histogram(
+            PROVING_SECONDS,
+            "Proving time of successful proofs in seconds",
+            &exponential_buckets(1.0, 2.0, 15),
+        );
+        registry.register_histogram(
+            VERIFICATION_SECONDS,
+            "Verification time of successful standalone verifications in seconds",
+            &exponential_buckets(0.001, 4.0, 10),
+        );
+        Self { registry, textfile }
+    }
+
+    /// The registry the metrics are recorded in.
+    pub const fn registry(&self) -> &Arc<Registry> {
+        &self.registry
+    }
+
+    /// Marks `fixture` as running until the returned guard is dropped.
+    pub(crate) fn start<'a>(&'a self, run: &'a RunLabels, fixture: &'a str) -> RunningFixture<'a> {
+        self.registry
+            .set_gauge(CURRENT_FIXTURE, &run.with("fixture", fixture), 1.0);
+        self.flush();
+        RunningFixture {
+            metrics: self,
+            run,
+            fixture,
+        }
+    }
+
+    /// Counts a fixture that was not run because its recorded result is up to date.
+    pub(crate) fn skipped(&self, run: &RunLabels) {
+        self.count(run, "skipped");
+        self.flush();
+    }
+
+    fn count(&self, run: &RunLabels, outcome: &str) {
+        self.registry
+            .inc_counter(FIXTURES_TOTAL, &run.with("outcome", outcome));
+    }
+
+    fn flush(&self) {
+        if let Some(path) = &self.textfile {
+            if let Err(err) = self.registry.write_textfile(path) {
+                warn!("Failed to write metrics textfile {}: {err}", path.display());
+            }
+        }
+    }
+}
+
+/// Labels identifying the zkVM and execution client of a run.
+#[derive(Debug, Clone)]
+pub(crate) struct RunLabels {
+    el: String,
+    zkvm: String,
+    action: &'static str,
+}
+
+impl RunLabels {
+    pub(crate) fn new(zkvm: &ZkVMInstance, config: &RunConfig) -> Self {
+        Self {
+            el: config.sub_folder.clone().unwrap_or_default(),
+            zkvm: benchmark_zkvm_name(zkvm),
+            action: config.action.as_str(),
+        }
+    }
+
+    fn labels(&self) -> [(&str, &str); 3] {
+        [
+            ("el", &self.el),
+            ("zkvm", &self.zkvm),
+            ("action", self.action),
+        ]
+    }
+
+    fn with<'a>(&'a self, name: &'a str, value: &'a str) -> [(&'a str, &'a str); 4] {
+        let [el, zkvm, action] = self.labels();
+        [el, zkvm, action, (name, value)]
+    }
+}
+
+/// A fixture marked as running, see [`ProgressMetrics::start`].
+#[derive(Debug)]
+pub(crate) struct RunningFixture<'a> {
+    metrics: &'a ProgressMetrics,
+    run: &'a RunLabels,
+    fixture: &'a str,
+}
+
+impl RunningFixture<'_> {
+    /// Records the outcome and measurements of the finished fixture.
+    pub(crate) fn finish(
+        self,
+        execution: Option<&ExecutionMetrics>,
+        proving: Option<&ProvingMetrics>,
+        verification: Option<&VerificationMetrics>,
+    ) {
+        let registry = &self.metrics.registry;
+        let [el, zkvm, _] = self.run.labels();
+        let per_zkvm = [el, zkvm];
+        let mut outcome = FixtureOutcome::Success;
+        if let Some(execution) = execution {
+            outcome = FixtureOutcome::of_execution(execution);
+            if let ExecutionMetrics::Success {
+                total_num_cycles, ..
+            } = execution
+            {
+                registry.observe(EXECUTION_CYCLES, &per_zkvm, *total_num_cycles as f64);
+            }
+        }
+        if let Some(proving) = proving {
+            outcome = FixtureOutcome::of_proving(proving);
+            if let ProvingMetrics::Success {
+                proving_time_ms, ..
+            } = proving
+            {
+                registry.observe(PROVING_SECONDS, &per_zkvm, *proving_time_ms as f64 / 1e3);
+            }
+        }
+        match verification {
+            Some(VerificationMetrics::Success {
+                verification_time_ms,
+                ..
+            }) => {
+                registry.observe(
+                    VERIFICATION_SECONDS,
+                    &per_zkvm,
+                    *verification_time_ms as f64 / 1e3,
+                );
+            }
+            Some(VerificationMetrics::Crashed(_)) => outcome = FixtureOutcome::Crashed,
+            None => {}
+        }
+        self.metrics.count(self.run, outcome_label(outcome));
+        // Dropping the guard clears the running fixture and flushes.
+    }
+}
+
+impl Drop for RunningFixture<'_> {
+    fn drop(&mut self) {
+        self.metrics
+            .registry
+            .remove(CURRENT_FIXTURE, &self.run.with("fixture", self.fixture));
+        self.metrics.flush();
+    }
+}
+
+const fn outcome_label(outcome: FixtureOutcome) -> &'static str {
+    match outcome {
+        FixtureOutcome::Success => "success",
+        FixtureOutcome::OutputMismatch => "output_mismatch",
+        FixtureOutcome::Crashed => "crashed",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zkevm_metrics::CrashInfo;
+
+    #[test]
+    fn records_fixture_outcomes_and_clears_running_fixtures() {
+        let dir = tempfile::tempdir().unwrap();
+        let textfile = dir.path().join("zkevm.prom");
+        let metrics = ProgressMetrics::new(Arc::new(Registry::new()), Some(textfile.clone()));
+        let run = RunLabels {
+            el: "reth-v1.9.0".into(),
+            zkvm: "sp1-v5.2.0".into(),
+            action: "prove",
+        };
+        let registry = metrics.registry();
+        let outcome = |outcome| registry.value(FIXTURES_TOTAL, &run.with("outcome", outcome));
+
+        let running = metrics.start(&run, "transfers");
+        assert_eq!(
+            registry.value(CURRENT_FIXTURE, &run.with("fixture", "transfers")),
+            Some(1.0)
+        );
+        assert!(std::fs::read_to_string(&textfile)
+            .unwrap()
+            .contains("fixture=\"transfers\"} 1\n"));
+        running.finish(
+            None,
+            Some(&ProvingMetrics::Success {
+                output_matched: true,
+                proof_size: 1_000,
+                proving_time_ms: 90_000,
+                verification_time_ms: 10,
+                statistics: None,
+            }),
+            None,
+        );
+        assert_eq!(
+            registry.value(CURRENT_FIXTURE, &run.with("fixture", "transfers")),
+            None
+        );
+        assert_eq!(outcome("success"), Some(1.0));
+
+        metrics.start(&run, "prover_killer").finish(
+            None,
+            Some(&ProvingMetrics::Crashed(CrashInfo {
+                reason: "out of memory".into(),
+            })),
+            None,
+        );
+        metrics.skipped(&run);
+        // A fixture that fails without a result is no longer reported as running.
+        drop(metrics.start(&run, "broken"));
+        assert_eq!(outcome("crashed"), Some(1.0));
+        assert_eq!(outcome("skipped"), Some(1.0));
+
+        let rendered = std::fs::read_to_string(&textfile).unwrap();
+        assert!(!rendered.contains("fixture=\""));
+        assert!(rendered.contains(
+            "zkevm_benchmark_proving_seconds_bucket{el=\"reth-v1.9.0\",zkvm=\"sp1-v5.2.0\",le=\"128\"} 1\n"
+        ));
+        assert!(rendered.contains(
+            "zkevm_benchmark_proving_seconds_bucket{el=\"reth-v1.9.0\",zkvm=\"sp1-v5.2.0\",le=\"64\"} 0\n"
+        ));
+    }
+}
@@ -0,0 +1,681 @@
+//! Remote prover backends for proving clusters and hosted proving networks
+//!
+//! A [`RemoteProver`] proves, verifies and optionally executes a single guest program on a remote
+//! service. [`ZkVMInstance::Remote`](crate::runner::ZkVMInstance::Remote) dispatches to it, so the
+//! rest of the runner treats remote and dockerized zkVMs alike.
+
+use anyhow::{anyhow, bail, Context, Result};
+use ere_cluster_client_zisk::{ZiskClusterClient, ZiskProof};
+use ere_dockerized::{
+    codec::{Decode, Encode},
+    zkVMKind, zkVMVerifier, DockerizedzkVMConfig, Elf, EncodedProof, Input, ProgramExecutionReport,
+    ProgramProvingReport, ProverResource, PublicValues, RemoteProverConfig,
+};
+use ere_util_tokio::block_on;
+use reqwest::StatusCode;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::time::Duration;
+use tokio::time::Instant;
+
+use crate::ziskemu;
+
+/// Prove timeout used when none is configured.
+const DEFAULT_PROVE_TIMEOUT: Duration = Duration::from_mins(3);
+/// Execute and verify timeout used when none is configured.
+const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_mins(1);
+
+/// A guest program hosted by a remote prover.
+pub trait RemoteProver: Send + Sync {
+    /// Returns the zkVM kind.
+    fn zkvm_kind(&self) -> zkVMKind;
+
+    /// Returns the zkVM SDK version reported by the remote prover.
+    fn sdk_version(&self) -> &str;
+
+    /// Returns the encoded program verifying key.
+    fn program_vk(&self) -> Vec<u8>;
+
+    /// Executes the guest program without proving.
+    ///
+    /// Backends without an execution endpoint keep this default, which reports execution as
+    /// unsupported.
+    fn execute(&self, input: &Input) -> Result<(PublicValues, ProgramExecutionReport)> {
+        let _ = input;
+        bail!(
+            "Remote prover for {} does not support execution",
+            self.zkvm_kind()
+        )
+    }
+
+    /// Generates a proof for the guest program with the given input.
+    fn prove(&self, input: &Input) -> Result<(PublicValues, EncodedProof, ProgramProvingReport)>;
+
+    /// Verifies a proof and returns the public values it commits to.
+    fn verify(&self, proof: &EncodedProof) -> Result<PublicValues>;
+}
+
+/// Per-request timeouts of a remote prover.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RemoteTimeouts {
+    /// Timeout of an execute request
+    pub execute: Duration,
+    /// Timeout of a prove request
+    pub prove: Duration,
+    /// Timeout of a verify request
+    pub verify: Duration,
+}
+
+impl Default for RemoteTimeouts {
+    fn default() -> Self {
+        Self {
+            execute: DEFAULT_REQUEST_TIMEOUT,
+            prove: DEFAULT_PROVE_TIMEOUT,
+            verify: DEFAULT_REQUEST_TIMEOUT,
+        }
+    }
+}
+
+impl From<&DockerizedzkVMConfig> for RemoteTimeouts {
+    fn from(config: &DockerizedzkVMConfig) -> Self {
+        let defaults = Self::default();
+        Self {
+            execute: config.execute_timeout.unwrap_or(defaults.execute),
+            prove: config.prove_timeout.unwrap_or(defaults.prove),
+            verify: config.verify_timeout.unwrap_or(defaults.verify),
+        }
+    }
+}
+
+/// Connects to the remote prover of `resource` serving `zkvm` and registers `elf` with it.
+///
+/// Zisk proving clusters use the Zisk cluster client; every other cluster and all proving networks
+/// use the HTTP protocol of [`HttpRemoteProver`].
+pub async fn connect(
+    zkvm: zkVMKind,
+    resource: &ProverResource,
+    elf: Elf,
+    config: &DockerizedzkVMConfig,
+) -> Result<Box<dyn RemoteProver>> {
+    let timeouts = RemoteTimeouts::from(config);
+    match resource {
+        ProverResource::Cluster(cluster) if zkvm == zkVMKind::Zisk => Ok(Box::new(
+            ZiskClusterProver::connect(cluster, elf, timeouts).await?,
+        )),
+        ProverResource::Cluster(remote) | ProverResource::Network(remote) => Ok(Box::new(
+            HttpRemoteProver::register(zkvm, remote, &elf, timeouts).await?,
+        )),
+        ProverResource::Cpu | ProverResource::Gpu => {
+            bail!("{resource:?} is not a remote prover resource")
+        }
+    }
+}
+
+/// Remote Zisk proving cluster.
+///
+/// The cluster only proves, so guests are executed locally with `ziskemu` in the Zisk server image
+/// and proofs are verified locally.
+pub struct ZiskClusterProver {
+    /// gRPC client connected to the remote Zisk cluster.
+    client: ZiskClusterClient,
+    /// Guest ELF, executed locally with `ziskemu`.
+    elf: Elf,
+    /// Per-request prove timeout.
+    prove_timeout: Duration,
+}
+
+impl ZiskClusterProver {
+    /// Connects to the Zisk cluster and uploads `elf`.
+    pub async fn connect(
+        config: &RemoteProverConfig,
+        elf: Elf,
+        timeouts: RemoteTimeouts,
+    ) -> Result<Self> {
+        let client = ZiskClusterClient::new(config, elf.clone())
+            .await
+            .map_err(|e| anyhow!("Failed to connect to Zisk cluster: {e}"))?;
+        Ok(Self {
+            client,
+            elf,
+            prove_timeout: timeouts.prove,
+        })
+    }
+}
+
+impl std::fmt::Debug for ZiskClusterProver {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ZiskClusterProver")
+            .field("sdk_version", &self.sdk_version())
+            .field("prove_timeout", &self.prove_timeout)
+            .finish()
+    }
+}
+
+impl RemoteProver for ZiskClusterProver {
+    fn zkvm_kind(&self) -> zkVMKind {
+        zkVMKind::Zisk
+    }
+
+    fn sdk_version(&self) -> &str {
+        self.client.verifier().sdk_version()
+    }
+
+    fn program_vk(&self) -> Vec<u8> {
+        self.client
+            .program_vk()
+            .encode_to_vec()
+            .expect("infallible")
+    }
+
+    fn execute(&self, input: &Input) -> Result<(PublicValues, ProgramExecutionReport)> {
+        ziskemu::execute(&self.elf, input.stdin())
+    }
+
+    fn prove(&self, input: &Input) -> Result<(PublicValues, EncodedProof, ProgramProvingReport)> {
+        // The deadline is taken per request, so proofs in flight concurrently each get the full
+        // timeout.
+        let deadline = Instant::now() + self.prove_timeout;
+        let (proof, proving_time) = block_on(self.client.prove(input, deadline))?;
+        let (_, public_values) = proof.program_vk_and_public_values()?;
+        let proof = proof.encode_to_vec()?;
+        Ok((
+            public_values,
+            EncodedProof(proof),
+            ProgramProvingReport::new(proving_time),
+        ))
+    }
+
+    fn verify(&self, proof: &EncodedProof) -> Result<PublicValues> {
+        let proof = ZiskProof::decode_from_slice(&proof.0)?;
+        Ok(self.client.verifier().verify(&proof)?)
+    }
+}
+
+/// Remote prover speaking the JSON-over-HTTP protocol used for hosted proving networks.
+///
+/// Binary fields are hex encoded and every request carries `Authorization: Bearer <api key>` when
+/// an API key is configured. The endpoints, relative to the configured endpoint, are:
+///
+/// - `POST /v1/programs` registers the ELF and returns the program id, verifying key and SDK
+///   version.
+/// - `POST /v1/programs/{id}/prove` proves an input.
+/// - `POST /v1/programs/{id}/verify` verifies a proof.
+/// - `POST /v1/programs/{id}/execute` executes an input. Services without execution support answer
+///   `404` or `501`, which [`RemoteProver::execute`] reports as unsupported.
+pub struct HttpRemoteProver {
+    client: reqwest::Client,
+    endpoint: String,
+    api_key: Option<String>,
+    zkvm: zkVMKind,
+    program_id: String,
+    program_vk: Vec<u8>,
+    sdk_version: String,
+    timeouts: RemoteTimeouts,
+}
+
+#[derive(Serialize)]
+struct RegisterRequest<'a> {
+    zkvm: &'a str,
+    elf: String,
+}
+
+#[derive(Deserialize)]
+struct RegisterResponse {
+    program_id: String,
+    program_vk: String,
+    sdk_version: String,
+}
+
+#[derive(Serialize)]
+struct InputRequest {
+    input: String,
+}
+
+#[derive(Serialize)]
+struct ProofRequest {
+    proof: String,
+}
+
+#[derive(Deserialize)]
+struct ExecuteResponse {
+    public_values: String,
+    total_num_cycles: u64,
+    execution_duration_ms: u64,
+}
+
+#[derive(Deserialize)]
+struct ProveResponse {
+    public_values: String,
+    proof: String,
+    proving_time_ms: u64,
+}
+
+#[derive(Deserialize)]
+struct VerifyResponse {
+    public_values: String,
+}
+
+impl HttpRemoteProver {
+    /// Registers `elf` with the remote prover at `config.endpoint`.
+    pub async fn register(
+        zkvm: zkVMKind,
+        config: &RemoteProverConfig,
+        elf: &[u8],
+        timeouts: RemoteTimeouts,
+    ) -> Result<Self> {
+        let mut prover = Self {
+            client: reqwest::Client::new(),
+            endpoint: config.endpoint.trim_end_matches('/').to_string(),
+            api_key: config.api_key.clone(),
+            zkvm,
+            program_id: String::new(),
+            program_vk: Vec::new(),
+            sdk_version: String::new(),
+            timeouts,
+        };
+
+        let request = RegisterRequest {
+            zkvm: zkvm.as_str(),
+            elf: hex::encode(elf),
+        };
+        let response: RegisterResponse = prover
+            .post("v1/programs", &request, timeouts.prove)
+            .await?
+            .with_context(|| {
+                format!("Remote prover at {} rejected the program", prover.endpoint)
+            })?;
+
+        prover.program_vk = decode_hex("program_vk", &response.program_vk)?;
+        prover.program_id = response.program_id;
+        prover.sdk_version = response.sdk_version;
+        Ok(prover)
+    }
+
+    /// Returns the id assigned to the guest program by the remote prover.
+    pub fn program_id(&self) -> &str {
+        &self.program_id
+    }
+
+    /// Posts `body` to `path` and decodes the JSON response.
+    ///
+    /// Returns `Ok(None)` when the service does not implement the endpoint.
+    async fn post<B: Serialize, R: DeserializeOwned>(
+        &self,
+        path: &str,
+        body: &B,
+        timeout: Duration,
+    ) -> Result<Option<R>> {
+        let url = format!("{}/{path}", self.endpoint);
+        let mut request = self.client.post(&url).json(body).timeout(timeout);
+        if let Some(api_key) = &self.api_key {
+            request = request.bearer_auth(api_key);
+        }
+
+        let response = match request.send().await {
+            Ok(response) => response,
+            Err(err) if err.is_timeout() => {
+                bail!("Request to remote prover timed out after {timeout:?}: {url}")
+            }
+            Err(err) => {
+                return Err(err).with_context(|| format!("Request to remote prover failed: {url}"));
+            }
+        };
+        let status = response.status();
+        if matches!(status, StatusCode::NOT_FOUND | StatusCode::NOT_IMPLEMENTED) {
+            return Ok(None);
+        }
+        if matches!(status, StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN) {
+            bail!(
+                "Remote prover rejected the {} with HTTP {status}: {url}",
+                if self.api_key.is_some() {
+                    "API key"
+                } else {
+                    "request without an API key"
+                }
+            );
+        }
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            bail!("Remote prover returned HTTP {status} for {url}: {body}");
+        }
+        let response = response
+            .json()
+            .await
+            .with_context(|| format!("Failed to decode remote prover response from {url}"))?;
+        Ok(Some(response))
+    }
+
+    fn program_path(&self, action: &str) -> String {
+        format!("v1/programs/{}/{action}", self.program_id)
+    }
+}
+
+impl std::fmt::Debug for HttpRemoteProver {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // The API key is left out so it never ends up in logs.
+        f.debug_struct("HttpRemoteProver")
+            .field("endpoint", &self.endpoint)
+            .field("zkvm", &self.zkvm.as_str())
+            .field("program_id", &self.program_id)
+            .field("sdk_version", &self.sdk_version)
+            .field("timeouts", &self.timeouts)
+            .finish()
+    }
+}
+
+impl RemoteProver for HttpRemoteProver {
+    fn zkvm_kind(&self) -> zkVMKind {
+        self.zkvm
+    }
+
+    fn sdk_version(&self) -> &str {
+        &self.sdk_version
+    }
+
+    fn program_vk(&self) -> Vec<u8> {
+        self.program_vk.clone()
+    }
+
+    fn execute(&self, input: &Input) -> Result<(PublicValues, ProgramExecutionReport)> {
+        let request = InputRequest {
+            input: hex::encode(input.stdin()),
+        };
+        let Some(response) = block_on(self.post::<_, ExecuteResponse>(
+            &self.program_path("execute"),
+            &request,
+            self.timeouts.execute,
+        ))?
+        else {
+            bail!(
+                "Remote prover for {} at {} does not support execution",
+                self.zkvm,
+                self.endpoint
+            );
+        };
+
+        let report = ProgramExecutionReport {
+            total_num_cycles: response.total_num_cycles,
+            execution_duration: Duration::from_millis(response.execution_duration_ms),
+            ..Default::default()
+        };
+        Ok((
+            decode_hex("public_values", &response.public_values)?,
+            report,
+        ))
+    }
+
+    fn prove(&self, input: &Input) -> Result<(PublicValues, EncodedProof, ProgramProvingReport)> {
+        let request = InputRequest {
+            input: hex::encode(input.stdin()),
+        };
+        let response: ProveResponse =
+            block_on(self.post(&self.program_path("prove"), &request, self.timeouts.prove))?
+                .with_context(|| {
+                    format!(
+                        "Remote prover at {} does not support proving",
+                        self.endpoint
+                    )
+                })?;
+
+        Ok((
+            decode_hex("public_values", &response.public_values)?,
+            EncodedProof(decode_hex("proof", &response.proof)?),
+            ProgramProvingReport::new(Duration::from_millis(response.proving_time_ms)),
+        ))
+    }
+
+    fn verify(&self, proof: &EncodedProof) -> Result<PublicValues> {
+        let request = ProofRequest {
+            proof: hex::encode(&proof.0),
+        };
+        let response: VerifyResponse =
+            block_on(self.post(&self.program_path("verify"), &request, self.timeouts.verify))?
+                .with_context(|| {
+                    format!(
+                        "Remote prover at {} does not support verification",
+                        self.endpoint
+                    )
+                })?;
+
+        decode_hex("public_values", &response.public_values)
+    }
+}
+
+fn decode_hex(field: &str, value: &str) -> Result<Vec<u8>> {
+    hex::decode(value.trim_start_matches("0x"))
+        .with_context(|| format!("Remote prover returned invalid hex in `{field}`"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::sync::{Arc, Mutex};
+    use std::thread;
+
+    /// Request path, `Authorization` header and body of a request received by the stub server.
+    type Recorded = Arc<Mutex<Vec<(String, Option<String>, String)>>>;
+
+    /// Behaviour of the stub prover server.
+    #[derive(Default, Clone, Copy)]
+    struct Stub {
+        /// Serve the execute endpoint instead of answering `501`.
+        execute: bool,
+        /// Answer `401` to requests without `Authorization: Bearer <key>`.
+        api_key: Option<&'static str>,
+        /// Delay before answering prove requests.
+        prove_delay: Duration,
+    }
+
+    /// Serves the HTTP prover protocol on a local port, executing nothing.
+    fn spawn_stub_server(stub: Stub) -> (String, Recorded) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let endpoint = format!("http://{}", listener.local_addr().unwrap());
+        let recorded = Recorded::default();
+
+        let requests = Arc::clone(&recorded);
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+                let mut request_line = String::new();
+                reader.read_line(&mut request_line).unwrap();
+                let mut authorization = None;
+                let mut content_length = 0;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    let line = line.trim_end();
+                    if line.is_empty() {
+                        break;
+                    }
+                    let (name, value) = line.split_once(':').unwrap();
+                    match name.to_ascii_lowercase().as_str() {
+                        "authorization" => authorization = Some(value.trim().to_string()),
+                        "content-length" => content_length = value.trim().parse().unwrap(),
+                        _ => {}
+                    }
+                }
+                let mut body = vec![0; content_length];
+                reader.read_exact(&mut body).unwrap();
+
+                let path = request_line.split_whitespace().nth(1).unwrap().to_string();
+                let authorized = stub.api_key.is_none_or(|key| {
+                    authorization.as_deref() == Some(format!("Bearer {key}").as_str())
+                });
+                let (status, response) = match path.as_str() {
+                    _ if !authorized => ("401 Unauthorized", ""),
+                    "/v1/programs" => (
+                        "200 OK",
+                        r#"{"program_id":"p1","program_vk":"0xabcd","sdk_version":"1.2.3"}"#,
+                    ),
+                    "/v1/programs/p1/prove" => {
+                        thread::sleep(stub.prove_delay);
+                        (
+                            "200 OK",
+                            r#"{"public_values":"01","proof":"cafe","proving_time_ms":1500}"#,
+                        )
+                    }
+                    "/v1/programs/p1/verify" => ("200 OK", r#"{"public_values":"01"}"#),
+                    "/v1/programs/p1/execute" if stub.execute => (
+                        "200 OK",
+                        r#"{"public_values":"01","total_num_cycles":42,"execution_duration_ms":7}"#,
+                    ),
+                    "/v1/programs/p1/execute" => ("501 Not Implemented", ""),
+                    _ => ("400 Bad Request", "unknown path"),
+                };
+                requests.lock().unwrap().push((
+                    path,
+                    authorization,
+                    String::from_utf8(body).unwrap(),
+                ));
+                // The client may have given up on a delayed response already.
+                let _ = write!(
+                    stream,
+                    "HTTP/1.1 {status}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{response}",
+                    response.len()
+                );
+            }
+        });
+
+        (endpoint, recorded)
+    }
+
+    fn register(
+        endpoint: &str,
+        api_key: Option<&str>,
+        timeouts: RemoteTimeouts,
+    ) -> Result<HttpRemoteProver> {
+        let config = RemoteProverConfig {
+            endpoint: format!("{endpoint}/"),
+            api_key: api_key.map(str::to_string),
+        };
+        block_on(HttpRemoteProver::register(
+            zkVMKind::SP1,
+            &config,
+            &[0x7f, 0x45],
+            timeouts,
+        ))
+    }
+
+    #[test]
+    fn http_prover_registers_proves_verifies_and_executes() -> Result<()> {
+        let (endpoint, recorded) = spawn_stub_server(Stub {
+            execute: true,
+            api_key: Some("secret"),
+            ..Default::default()
+        });
+        let prover = register(&endpoint, Some("secret"), RemoteTimeouts::default())?;
+
+        assert_eq!(prover.program_id(), "p1");
+        assert_eq!(prover.program_vk(), [0xab, 0xcd]);
+        assert_eq!(prover.sdk_version(), "1.2.3");
+
+        let input = Input::new().with_stdin(vec![1, 2]);
+        let (public_values, proof, report) = prover.prove(&input)?;
+        assert_eq!(public_values, [0x01]);
+        assert_eq!(proof.0, [0xca, 0xfe]);
+        assert_eq!(report.proving_time, Duration::from_millis(1500));
+
+        assert_eq!(prover.verify(&proof)?, [0x01]);
+
+        let (public_values, report) = prover.execute(&input)?;
+        assert_eq!(public_values, [0x01]);
+        assert_eq!(report.total_num_cycles, 42);
+        assert_eq!(report.execution_duration, Duration::from_millis(7));
+
+        let recorded = recorded.lock().unwrap();
+        assert_eq!(recorded.len(), 4);
+        assert!(recorded
+            .iter()
+            .all(|(_, auth, _)| auth.as_deref() == Some("Bearer secret")));
+        assert!(recorded[0].2.contains(r#""elf":"7f45""#));
+        assert!(recorded[0].2.contains(r#""zkvm":"sp1""#));
+        assert!(recorded[1].2.contains(r#""input":"0102""#));
+        assert!(recorded[2].2.contains(r#""proof":"cafe""#));
+
+        Ok(())
+    }
+
+    #[test]
+    fn http_prover_reports_missing_execute_support() -> Result<()> {
+        let (endpoint, recorded) = spawn_stub_server(Stub::default());
+        let prover = register(&endpoint, None, RemoteTimeouts::default())?;
+
+        let err = prover.execute(&Input::new()).unwrap_err();
+        assert!(err.to_string().contains("does not support execution"));
+        assert!(recorded
+            .lock()
+            .unwrap()
+            .iter()
+            .all(|(_, auth, _)| auth.is_none()));
+
+        Ok(())
+    }
+
+    #[test]
+    fn http_prover_rejects_missing_or_wrong_api_key() {
+        let (endpoint, _) = spawn_stub_server(Stub {
+            api_key: Some("secret"),
+            ..Default::default()
+        });
+
+        let err = register(&endpoint, None, RemoteTimeouts::default()).unwrap_err();
+        assert!(err.to_string().contains("without an API key"), "{err:#}");
+
+        let err = register(&endpoint, Some("wrong"), RemoteTimeouts::default()).unwrap_err();
+        assert!(err.to_string().contains("rejected the API key"), "{err:#}");
+    }
+
+    #[test]
+    fn http_prover_times_out_slow_proofs() -> Result<()> {
+        let (endpoint, _) = spawn_stub_server(Stub {
+            prove_delay: Duration::from_secs(2),
+            ..Default::default()
+        });
+        let timeouts = RemoteTimeouts {
+            prove: Duration::from_millis(200),
+            ..Default::default()
+        };
+        let prover = register(&endpoint, None, timeouts)?;
+
+        let err = prover.prove(&Input::new()).unwrap_err();
+        assert!(err.to_string().contains("timed out after 200ms"), "{err:#}");
+
+        Ok(())
+    }
+
+    struct ProveOnly;
+
+    impl RemoteProver for ProveOnly {
+        fn zkvm_kind(&self) -> zkVMKind {
+            zkVMKind::SP1
+        }
+
+        fn sdk_version(&self) -> &str {
+            "1.0.0"
+        }
+
+        fn program_vk(&self) -> Vec<u8> {
+            Vec::new()
+        }
+
+        fn prove(
+            &self,
+            _input: &Input,
+        ) -> Result<(PublicValues, EncodedProof, ProgramProvingReport)> {
+            bail!("not used")
+        }
+
+        fn verify(&self, _proof: &EncodedProof) -> Result<PublicValues> {
+            bail!("not used")
+        }
+    }
+
+    #[test]
+    fn execute_is_unsupported_by_default() {
+        let err = ProveOnly.execute(&Input::new()).unwrap_err();
+        assert!(err.to_string().contains("does not support execution"));
+    }
+}
@@ -0,0 +1,364 @@
+//! Resource usage sampling for execute and prove workloads
+//!
+//! While a workload runs, a background thread periodically samples memory and CPU usage of the
+//! zkVM. For dockerized zkVMs the cgroup (v2) of the zkVM server container is sampled, falling
+//! back to the benchmark process tree when the container cannot be found, e.g. when Docker is
+//! not available or the host does not use cgroup v2. Neither probe can tell the usage of
+//! concurrent jobs apart, so the runner only samples while it runs one fixture at a time.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use ere_dockerized::zkVMKind;
+use sysinfo::{Pid, ProcessExt, ProcessRefreshKind, System, SystemExt};
+use tracing::warn;
+use zkevm_metrics::{ResourceSource, ResourceUsage};
+
+use crate::runner::ZkVMInstance;
+
+/// Interval between two resource samples.
+const SAMPLE_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Cgroup v2 directories Docker places container cgroups in, for the systemd and cgroupfs
+/// drivers respectively.
+const CGROUP_DIRS: [&str; 2] = [
+    "/sys/fs/cgroup/system.slice/docker-{id}.scope",
+    "/sys/fs/cgroup/docker/{id}",
+];
+
+/// Samples resource usage of a zkVM on a background thread until finished.
+#[derive(Debug)]
+pub(crate) struct ResourceSampler {
+    running: Option<(Sender<()>, JoinHandle<Option<ResourceUsage>>)>,
+}
+
+impl ResourceSampler {
+    /// Starts sampling the resources used by `zkvm`.
+    ///
+    /// Remote zkVMs are not sampled, [`Self::finish`] returns `None` for them.
+    pub(crate) fn start(zkvm: &ZkVMInstance) -> Self {
+        let Some(probe) = Probe::for_zkvm(zkvm) else {
+            return Self { running: None };
+        };
+
+        let (stop, stopped) = mpsc::channel();
+        let handle = thread::spawn(move || sample_until_stopped(probe, &stopped));
+        Self {
+            running: Some((stop, handle)),
+        }
+    }
+
+    /// Stops sampling and returns the usage observed since [`Self::start`].
+    pub(crate) fn finish(self) -> Option<ResourceUsage> {
+        let (stop, handle) = self.running?;
+        // The sampler also stops when the sender is dropped, so a failed send is fine.
+        let _ = stop.send(());
+        match handle.join() {
+            Ok(usage) => usage,
+            Err(_) => {
+                warn!("Resource sampler thread panicked, no resource usage recorded");
+                None
+            }
+        }
+    }
+}
+
+fn sample_until_stopped(mut probe: Probe, stopped: &mpsc::Receiver<()>) -> Option<ResourceUsage> {
+    let started = Instant::now();
+    let mut summary = SampleSummary::new(probe.sample()?);
+    while stopped.recv_timeout(SAMPLE_INTERVAL) == Err(RecvTimeoutError::Timeout) {
+        if let Some(sample) = probe.sample() {
+            summary.record(sample);
+        }
+    }
+    if let Some(sample) = probe.sample() {
+        summary.record(sample);
+    }
+    Some(summary.finish(probe.source(), started.elapsed()))
+}
+
+/// A single observation of the sampled target.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Sample {
+    /// Resident memory in bytes.
+    memory_bytes: u64,
+    /// Cumulative CPU time consumed by the target.
+    cpu_time: Duration,
+}
+
+/// Running aggregate over the samples of one measurement.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct SampleSummary {
+    first: Sample,
+    last: Sample,
+    peak_memory_bytes: u64,
+    samples: u32,
+}
+
+impl SampleSummary {
+    const fn new(first: Sample) -> Self {
+        Self {
+            first,
+            last: first,
+            peak_memory_bytes: first.memory_bytes,
+            samples: 1,
+        }
+    }
+
+    fn record(&mut self, sample: Sample) {
+        self.last = sample;
+        self.peak_memory_bytes = self.peak_memory_bytes.max(sample.memory_bytes);
+        self.samples += 1;
+    }
+
+    fn finish(self, source: ResourceSource, wall_time: Duration) -> ResourceUsage {
+        ResourceUsage::new(
+            source,
+            self.peak_memory_bytes,
+            wall_time,
+            self.last.cpu_time.saturating_sub(self.first.cpu_time),
+            self.samples,
+        )
+    }
+}
+
+/// Where resource usage is read from.
+enum Probe {
+    /// Cgroup v2 directory of the zkVM server container.
+    Cgroup {
+        path: PathBuf,
+        /// `memory.peak` when sampling started, if the kernel exposes it.
+        initial_peak: Option<u64>,
+    },
+    /// The benchmark process and all of its descendants.
+    ProcessTree {
+        system: Box<System>,
+        root: Pid,
+        cpu_time: Duration,
+        last_refresh: Instant,
+    },
+}
+
+impl Probe {
+    fn for_zkvm(zkvm: &ZkVMInstance) -> Option<Self> {
+        match zkvm {
+            ZkVMInstance::Dockerized { .. } => match find_container_cgroup(zkvm.zkvm_kind()) {
+                ContainerLookup::Found(path) => Some(Self::cgroup(path)),
+                ContainerLookup::NotFound => Self::process_tree(),
+                ContainerLookup::Ambiguous => {
+                    warn!(
+                        "Multiple {} server containers are running, resource usage will not be recorded",
+                        zkvm.zkvm_kind()
+                    );
+                    None
+                }
+            },
+            ZkVMInstance::Remote { .. } => None,
+        }
+    }
+
+    fn cgroup(path: PathBuf) -> Self {
+        let initial_peak = read_u64(&path.join("memory.peak"));
+        Self::Cgroup { path, initial_peak }
+    }
+
+    fn process_tree() -> Option<Self> {
+        let root = sysinfo::get_current_pid().ok()?;
+        Some(Self::ProcessTree {
+            system: Box::new(System::new()),
+            root,
+            cpu_time: Duration::ZERO,
+            last_refresh: Instant::now(),
+        })
+    }
+
+    const fn source(&self) -> ResourceSource {
+        match self {
+            Self::Cgroup { .. } => ResourceSource::DockerContainer,
+            Self::ProcessTree { .. } => ResourceSource::HostProcess,
+        }
+    }
+
+    fn sample(&mut self) -> Option<Sample> {
+        match self {
+            Self::Cgroup { path, initial_peak } => {
+                let current = read_u64(&path.join("memory.current"))?;
+                // `memory.peak` covers the container lifetime, so it only tells us about this
+                // measurement once it grew past the value seen at the start.
+                let peak = read_u64(&path.join("memory.peak"))
+                    .filter(|peak| initial_peak.is_some_and(|initial| *peak > initial))
+                    .unwrap_or_default();
+                let cpu_stat = fs::read_to_string(path.join("cpu.stat")).ok()?;
+                Some(Sample {
+                    memory_bytes: current.max(peak),
+                    cpu_time: Duration::from_micros(parse_cpu_usage_usec(&cpu_stat)?),
+                })
+            }
+            Self::ProcessTree {
+                system,
+                root,
+                cpu_time,
+                last_refresh,
+            } => {
+                system.refresh_processes_specifics(ProcessRefreshKind::new().with_cpu());
+                let elapsed = last_refresh.elapsed();
+                *last_refresh = Instant::now();
+
+                let mut memory_bytes = 0;
+                let mut cpu_percent = 0.0;
+                for pid in process_tree(system.processes(), *root) {
+                    if let Some(process) = system.process(pid) {
+                        memory_bytes += process.memory();
+                        cpu_percent += f64::from(process.cpu_usage());
+                    }
+                }
+                // sysinfo reports utilization since the previous refresh, integrate it into
+                // cumulative CPU time.
+                *cpu_time += elapsed.mul_f64(cpu_percent / 100.0);
+                Some(Sample {
+                    memory_bytes,
+                    cpu_time: *cpu_time,
+                })
+            }
+        }
+    }
+}
+
+/// Returns `root` and all of its descendant processes.
+fn process_tree<P: ProcessExt>(processes: &HashMap<Pid, P>, root: Pid) -> Vec<Pid> {
+    let mut children: HashMap<Pid, Vec<Pid>> = HashMap::new();
+    for (pid, process) in processes {
+        if let Some(parent) = process.parent() {
+            children.entry(parent).or_default().push(*pid);
+        }
+    }
+
+    let mut tree = Vec::new();
+    let mut pending = vec![root];
+    while let Some(pid) = pending.pop() {
+        tree.push(pid);
+        if let Some(descendants) = children.get(&pid) {
+            pending.extend(descendants);
+        }
+    }
+    tree
+}
+
+enum ContainerLookup {
+    Found(PathBuf),
+    NotFound,
+    Ambiguous,
+}
+
+/// Finds the cgroup of the running zkVM server container for `kind`.
+fn find_container_cgroup(kind: zkVMKind) -> ContainerLookup {
+    let Ok(output) = Command::new("docker")
+        .args(["ps", "--no-trunc", "--format", "{{.ID}} {{.Image}}"])
+        .output()
+    else {
+        return ContainerLookup::NotFound;
+    };
+    if !output.status.success() {
+        return ContainerLookup::NotFound;
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let ids: Vec<&str> = stdout
+        .lines()
+        .filter_map(|line| line.split_once(' '))
+        .filter(|(_, image)| is_server_image(image, kind))
+        .map(|(id, _)| id)
+        .collect();
+    match ids.as_slice() {
+        [] => ContainerLookup::NotFound,
+        [id] => CGROUP_DIRS
+            .iter()
+            .map(|dir| PathBuf::from(dir.replace("{id}", id)))
+            .find(|path| path.join("memory.current").exists())
+            .map_or(ContainerLookup::NotFound, ContainerLookup::Found),
+        _ => ContainerLookup::Ambiguous,
+    }
+}
+
+/// Returns whether `image` is an ere server image for `kind`, ignoring registry and tag.
+fn is_server_image(image: &str, kind: zkVMKind) -> bool {
+    let name = image.rsplit('/').next().unwrap_or(image);
+    let name = name.split(':').next().unwrap_or(name);
+    let server = format!("ere-server-{}", kind.as_str());
+    name.strip_prefix(&server)
+        .is_some_and(|suffix| suffix.is_empty() || suffix.starts_with('-'))
+}
+
+fn read_u64(path: &Path) -> Option<u64> {
+    fs::read_to_string(path).ok()?.trim().parse().ok()
+}
+
+/// Parses `usage_usec` from a cgroup v2 `cpu.stat` file.
+fn parse_cpu_usage_usec(cpu_stat: &str) -> Option<u64> {
+    cpu_stat.lines().find_map(|line| {
+        let value = line.strip_prefix("usage_usec ")?;
+        value.trim().parse().ok()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_cgroup_cpu_usage() {
+        let cpu_stat = "usage_usec 1500000\nuser_usec 1000000\nsystem_usec 500000\n";
+        assert_eq!(parse_cpu_usage_usec(cpu_stat), Some(1_500_000));
+        assert_eq!(parse_cpu_usage_usec("user_usec 1\n"), None);
+    }
+
+    #[test]
+    fn matches_server_images_ignoring_registry_and_tag() {
+        assert!(is_server_image("ere-server-sp1:0.1.0", zkVMKind::SP1));
+        assert!(is_server_image(
+            "ghcr.io/eth-act/ere/ere-server-sp1-cuda:abc",
+            zkVMKind::SP1
+        ));
+        assert!(!is_server_image("ere-server-sp1x:0.1.0", zkVMKind::SP1));
+        assert!(!is_server_image("ere-base:0.1.0", zkVMKind::SP1));
+    }
+
+    #[test]
+    fn summary_tracks_peak_memory_and_cpu_delta() {
+        let mut summary = SampleSummary::new(Sample {
+            memory_bytes: 100,
+            cpu_time: Duration::from_secs(10),
+        });
+        summary.record(Sample {
+            memory_bytes: 400,
+            cpu_time: Duration::from_secs(12),
+        });
+        summary.record(Sample {
+            memory_bytes: 200,
+            cpu_time: Duration::from_secs(14),
+        });
+
+        let usage = summary.finish(ResourceSource::DockerContainer, Duration::from_secs(2));
+        assert_eq!(usage.peak_memory_bytes, 400);
+        assert_eq!(usage.cpu_time, Duration::from_secs(4));
+        assert_eq!(usage.average_cpu_percent, 200);
+        assert_eq!(usage.samples, 3);
+    }
+
+    #[test]
+    fn process_tree_includes_descendants_only() {
+        let mut system = System::new();
+        system.refresh_processes();
+        let root = sysinfo::get_current_pid().expect("current pid");
+        let tree = process_tree(system.processes(), root);
+        assert_eq!(tree.first(), Some(&root));
+        assert!(tree
+            .iter()
+            .all(|pid| *pid == root || system.process(*pid).is_some()));
+    }
+}
@@ -0,0 +1,384 @@
+//! Parser for the pprof profiles RISC Zero writes to `RISC0_PPROF_OUT`
+//!
+//! Only the fields needed for folded stacks are decoded from the `Profile` message of
+//! <https://github.com/google/pprof/blob/main/proto/profile.proto>: samples with their location
+//! ids and first value, locations with their lines, functions and the string table. Profiles may
+//! be gzip compressed, as `pprof` tools write them.
+
+use anyhow::{bail, ensure, Context, Result};
+use flate2::read::GzDecoder;
+use std::collections::HashMap;
+use std::io::Read;
+
+use crate::profiling::{FoldedStacks, UNATTRIBUTED_FRAME};
+
+/// `Profile` field numbers.
+const PROFILE_SAMPLE: u64 = 2;
+const PROFILE_LOCATION: u64 = 4;
+const PROFILE_FUNCTION: u64 = 5;
+const PROFILE_STRING_TABLE: u64 = 6;
+/// `Sample` field numbers.
+const SAMPLE_LOCATION_ID: u64 = 1;
+const SAMPLE_VALUE: u64 = 2;
+/// `Location` field numbers.
+const LOCATION_ID: u64 = 1;
+const LOCATION_ADDRESS: u64 = 3;
+const LOCATION_LINE: u64 = 4;
+/// `Line` field numbers.
+const LINE_FUNCTION_ID: u64 = 1;
+/// `Function` field numbers.
+const FUNCTION_ID: u64 = 1;
+const FUNCTION_NAME: u64 = 2;
+
+/// Converts a pprof profile into folded stacks weighted by the first value of every sample, the
+/// cycles in RISC Zero profiles.
+///
+/// Inlined functions become frames of their own below the function they were inlined into.
+/// Samples without locations are attributed to [`UNATTRIBUTED_FRAME`].
+pub fn folded_stacks(profile: &[u8]) -> Result<FoldedStacks> {
+    let decompressed;
+    let profile = if profile.starts_with(&[0x1f, 0x8b]) {
+        let mut bytes = Vec::new();
+        GzDecoder::new(profile)
+            .read_to_end(&mut bytes)
+            .context("Failed to decompress pprof profile")?;
+        decompressed = bytes;
+        decompressed.as_slice()
+    } else {
+        profile
+    };
+
+    let mut strings = Vec::new();
+    let mut samples = Vec::new();
+    let mut locations = HashMap::new();
+    let mut functions = HashMap::new();
+    let mut fields = Fields::new(profile);
+    while let Some((number, value)) = fields.next_field()? {
+        match number {
+            PROFILE_SAMPLE => samples.push(Sample::decode(value.bytes()?)?),
+            PROFILE_LOCATION => {
+                let location = Location::decode(value.bytes()?)?;
+                locations.insert(location.id, location);
+            }
+            PROFILE_FUNCTION => {
+                let (id, name) = decode_function(value.bytes()?)?;
+                functions.insert(id, name);
+            }
+            PROFILE_STRING_TABLE => strings.push(
+                String::from_utf8(value.bytes()?.to_vec())
+                    .context("pprof string table entry is not UTF-8")?,
+            ),
+            _ => {}
+        }
+    }
+
+    let function_name = |id: u64| -> Result<&str> {
+        let index = *functions
+            .get(&id)
+            .with_context(|| format!("pprof location refers to missing function {id}"))?;
+        usize::try_from(index)
+            .ok()
+            .and_then(|index| strings.get(index))
+            .map(String::as_str)
+            .with_context(|| format!("pprof function {id} refers to missing string {index}"))
+    };
+
+    let mut folded = FoldedStacks::new();
+    for sample in samples {
+        let weight = sample.value.first().copied().unwrap_or_default();
+        let weight = u64::try_from(weight)
+            .with_context(|| format!("pprof sample has a negative value {weight}"))?;
+
+        // Locations are listed leaf first, and the lines of a location end with the caller the
+        // preceding lines were inlined into.
+        let mut stack = Vec::new();
+        for id in sample.location_ids.iter().rev() {
+            let location = locations
+                .get(id)
+                .with_context(|| format!("pprof sample refers to missing location {id}"))?;
+            if location.function_ids.is_empty() {
+                stack.push(format!("{:#x}", location.address));
+            }
+            for function_id in location.function_ids.iter().rev() {
+                stack.push(function_name(*function_id)?.to_string());
+            }
+        }
+        if stack.is_empty() {
+            stack.push(UNATTRIBUTED_FRAME.to_string());
+        }
+        folded.add(&stack, weight);
+    }
+    Ok(folded)
+}
+
+#[derive(Debug, Default)]
+struct Sample {
+    location_ids: Vec<u64>,
+    value: Vec<i64>,
+}
+
+impl Sample {
+    fn decode(bytes: &[u8]) -> Result<Self> {
+        let mut sample = Self::default();
+        let mut fields = Fields::new(bytes);
+        while let Some((number, value)) = fields.next_field()? {
+            match number {
+                SAMPLE_LOCATION_ID => value.push_repeated(&mut sample.location_ids)?,
+                SAMPLE_VALUE => {
+                    let mut values = Vec::new();
+                    value.push_repeated(&mut values)?;
+                    // int64 values are encoded as their two's complement.
+                    sample
+                        .value
+                        .extend(values.into_iter().map(|value| value as i64));
+                }
+                _ => {}
+            }
+        }
+        Ok(sample)
+    }
+}
+
+#[derive(Debug, Default)]
+struct Location {
+    id: u64,
+    address: u64,
+    /// Function of each line, innermost inlined function first.
+    function_ids: Vec<u64>,
+}
+
+impl Location {
+    fn decode(bytes: &[u8]) -> Result<Self> {
+        let mut location = Self::default();
+        let mut fields = Fields::new(bytes);
+        while let Some((number, value)) = fields.next_field()? {
+            match number {
+                LOCATION_ID => location.id = value.varint()?,
+                LOCATION_ADDRESS => location.address = value.varint()?,
+                LOCATION_LINE => {
+                    let mut line = Fields::new(value.bytes()?);
+                    while let Some((number, value)) = line.next_field()? {
+                        if number == LINE_FUNCTION_ID {
+                            location.function_ids.push(value.varint()?);
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(location)
+    }
+}
+
+/// Decodes the id and name string index of a `Function`.
+fn decode_function(bytes: &[u8]) -> Result<(u64, u64)> {
+    let (mut id, mut name) = (0, 0);
+    let mut fields = Fields::new(bytes);
+    while let Some((number, value)) = fields.next_field()? {
+        match number {
+            FUNCTION_ID => id = value.varint()?,
+            FUNCTION_NAME => name = value.varint()?,
+            _ => {}
+        }
+    }
+    Ok((id, name))
+}
+
+/// Value of a protobuf field.
+#[derive(Debug, Clone, Copy)]
+enum FieldValue<'a> {
+    Varint(u64),
+    Bytes(&'a [u8]),
+    /// A fixed-width 32 or 64 bit value, which none of the decoded fields use.
+    Fixed,
+}
+
+impl<'a> FieldValue<'a> {
+    fn varint(self) -> Result<u64> {
+        match self {
+            Self::Varint(value) => Ok(value),
+            _ => bail!("pprof field is not a varint"),
+        }
+    }
+
+    fn bytes(self) -> Result<&'a [u8]> {
+        match self {
+            Self::Bytes(bytes) => Ok(bytes),
+            _ => bail!("pprof field is not length delimited"),
+        }
+    }
+
+    /// Appends the values of a repeated varint field, packed or not.
+    fn push_repeated(self, values: &mut Vec<u64>) -> Result<()> {
+        match self {
+            Self::Varint(value) => values.push(value),
+            Self::Bytes(bytes) => {
+                let mut packed = Fields::new(bytes);
+                while !packed.bytes.is_empty() {
+                    values.push(packed.varint()?);
+                }
+            }
+            Self::Fixed => bail!("pprof repeated field is not a varint"),
+        }
+        Ok(())
+    }
+}
+
+/// Reads the fields of an encoded protobuf message.
+struct Fields<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Fields<'a> {
+    const fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes }
+    }
+
+    fn varint(&mut self) -> Result<u64> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let (&byte, rest) = self
+                .bytes
+                .split_first()
+                .context("pprof profile ends inside a varint")?;
+            self.bytes = rest;
+            value |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        bail!("pprof profile has a varint longer than 64 bits")
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        ensure!(len <= self.bytes.len(), "pprof profile ends inside a field");
+        let (taken, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+        Ok(taken)
+    }
+
+    fn next_field(&mut self) -> Result<Option<(u64, FieldValue<'a>)>> {
+        if self.bytes.is_empty() {
+            return Ok(None);
+        }
+        let key = self.varint()?;
+        let value = match key & 0x7 {
+            0 => FieldValue::Varint(self.varint()?),
+            1 => {
+                self.take(8)?;
+                FieldValue::Fixed
+            }
+            2 => {
+                let len = usize::try_from(self.varint()?)
+                    .context("pprof field length does not fit in memory")?;
+                FieldValue::Bytes(self.take(len)?)
+            }
+            5 => {
+                self.take(4)?;
+                FieldValue::Fixed
+            }
+            wire_type => bail!("pprof profile has an unsupported wire type {wire_type}"),
+        };
+        Ok(Some((key >> 3, value)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::{write::GzEncoder, Compression};
+    use std::io::Write;
+
+    fn varint(mut value: u64, out: &mut Vec<u8>) {
+        while value >= 0x80 {
+            out.push((value as u8) | 0x80);
+            value >>= 7;
+        }
+        out.push(value as u8);
+    }
+
+    fn varint_field(number: u64, value: u64, out: &mut Vec<u8>) {
+        varint(number << 3, out);
+        varint(value, out);
+    }
+
+    fn bytes_field(number: u64, bytes: &[u8], out: &mut Vec<u8>) {
+        varint((number << 3) | 2, out);
+        varint(bytes.len() as u64, out);
+        out.extend_from_slice(bytes);
+    }
+
+    fn packed(values: &[u64]) -> Vec<u8> {
+        let mut out = Vec::new();
+        for value in values {
+            varint(*value, &mut out);
+        }
+        out
+    }
+
+    /// A profile of `main` calling `execute`, which has `keccak` inlined at one location.
+    fn profile() -> Vec<u8> {
+        let mut profile = Vec::new();
+        for string in ["", "cycles", "main", "execute", "keccak"] {
+            bytes_field(PROFILE_STRING_TABLE, string.as_bytes(), &mut profile);
+        }
+        for (id, name) in [(1, 2), (2, 3), (3, 4)] {
+            let mut function = Vec::new();
+            varint_field(FUNCTION_ID, id, &mut function);
+            varint_field(FUNCTION_NAME, name, &mut function);
+            bytes_field(PROFILE_FUNCTION, &function, &mut profile);
+        }
+        // Location 1 is in `main`, location 2 in `keccak` inlined into `execute`.
+        for (id, functions) in [(1, vec![1]), (2, vec![3, 2]), (3, vec![])] {
+            let mut location = Vec::new();
+            varint_field(LOCATION_ID, id, &mut location);
+            varint_field(LOCATION_ADDRESS, 0x1000 + id, &mut location);
+            for function in functions {
+                let mut line = Vec::new();
+                varint_field(LINE_FUNCTION_ID, function, &mut line);
+                bytes_field(LOCATION_LINE, &line, &mut location);
+            }
+            bytes_field(PROFILE_LOCATION, &location, &mut profile);
+        }
+        for (locations, cycles) in [(vec![1], 10), (vec![2, 1], 30), (vec![3, 1], 5)] {
+            let mut sample = Vec::new();
+            bytes_field(SAMPLE_LOCATION_ID, &packed(&locations), &mut sample);
+            bytes_field(SAMPLE_VALUE, &packed(&[cycles]), &mut sample);
+            bytes_field(PROFILE_SAMPLE, &sample, &mut profile);
+        }
+        // An unpacked sample without locations.
+        let mut sample = Vec::new();
+        varint_field(SAMPLE_VALUE, 7, &mut sample);
+        bytes_field(PROFILE_SAMPLE, &sample, &mut profile);
+        profile
+    }
+
+    #[test]
+    fn samples_are_folded_from_root_to_inlined_leaf() -> Result<()> {
+        let folded = folded_stacks(&profile())?;
+        assert_eq!(
+            folded.to_string(),
+            "[unattributed] 7\nmain 10\nmain;0x1003 5\nmain;execute;keccak 30\n"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn gzip_compressed_profiles_are_decompressed() -> Result<()> {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&profile())?;
+        let compressed = encoder.finish()?;
+
+        assert_eq!(folded_stacks(&compressed)?, folded_stacks(&profile())?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn truncated_profiles_are_rejected() {
+        let profile = profile();
+        let err = folded_stacks(&profile[..profile.len() - 1]).unwrap_err();
+        assert_eq!(err.to_string(), "pprof profile ends inside a field");
+    }
+}
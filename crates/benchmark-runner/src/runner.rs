@@ -1,34 +1,49 @@
 //! Runner for benchmark tests
 
 use anyhow::{anyhow, bail, Context, Result};
-use ere_cluster_client_zisk::{ZiskClusterClient, ZiskProof};
 use ere_dockerized::{
-    codec::{Decode, Encode},
     zkVMKind, zkVMVerifier, DockerizedzkVM, DockerizedzkVMConfig, Elf, EncodedProof, Input,
-    ProgramExecutionReport, ProgramProvingReport, ProverResource, PublicValues,
+    ProgramExecutionReport, ProgramProvingReport, ProverResource, PublicValues, DOCKER_IMAGE_TAG,
 };
 use ere_guests_downloader::{CompiledGuest, Downloader};
-use ere_util_tokio::block_on;
 use rayon::iter::{ParallelBridge, ParallelIterator};
+use std::collections::BTreeMap;
 use std::fs;
+use std::num::NonZeroUsize;
 use std::path::{Path, PathBuf};
-use std::time::Duration;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 use std::{any::Any, env, panic};
-use tokio::time::Instant;
 use tracing::{info, warn};
 
-use zkevm_metrics::{BenchmarkRun, CrashInfo, ExecutionMetrics, HardwareInfo, ProvingMetrics};
+use zkevm_metrics::{
+    results, BenchmarkRun, CrashInfo, DurationStats, ExecutionMetrics, ExecutionStatistics,
+    GasNormalizedMetrics, GuestSource, HardwareInfo, Provenance, ProvingMetrics, ProvingStatistics,
+    ResourceUsage,
+};
 
+use crate::guest_cache::{
+    parse_checksums, parse_sidecar, verify_digest, GuestCache, CHECKSUM_MANIFEST_FILE_NAME,
+};
 use crate::guest_programs::GuestFixture;
-use crate::zisk_profiling::{run_profiling, ProfileOutcome};
+use crate::journal::{EventJournal, RunEvent};
+use crate::manifest::{sha256_hex, FixtureOutcome, RerunPolicy, RunManifestStore};
+use crate::profiling::{run_profiling, ProfileOutcome};
+use crate::progress::{ProgressMetrics, RunLabels};
+use crate::remote::{self, RemoteProver};
+use crate::resources::ResourceSampler;
 
-pub use crate::zisk_profiling::ProfileConfig;
+pub use crate::profiling::ProfileConfig;
 
 /// How to resolve downloaded guest binaries, derived from the resolved
 /// ere-guests dependency in Cargo.lock at build time.
 const ERE_GUESTS_DOWNLOAD_KIND: &str = env!("ERE_GUESTS_DOWNLOAD_KIND");
 /// Tag or commit SHA matching [`ERE_GUESTS_DOWNLOAD_KIND`].
 const ERE_GUESTS_DOWNLOAD_VALUE: &str = env!("ERE_GUESTS_DOWNLOAD_VALUE");
+/// Release download URL of ere-guests tags, where each release publishes its checksums.
+const ERE_GUESTS_RELEASES_URL: &str = "https://github.com/eth-act/ere-guests/releases/download";
+/// Git commit the workspace was built from, resolved at build time.
+const ERE_HOSTS_GIT_COMMIT: &str = env!("ERE_HOSTS_GIT_COMMIT");
 
 /// Source used to resolve compiled guest programs.
 #[derive(Debug, Clone)]
@@ -42,6 +57,53 @@ pub enum GuestProgramSource {
 }
 
 impl GuestProgramSource {
+    /// Returns the source as recorded in result provenance.
+    pub fn provenance(&self) -> GuestSource {
+        match self {
+            Self::Default => GuestSource::EreGuests {
+                kind: ERE_GUESTS_DOWNLOAD_KIND.to_string(),
+                value: ERE_GUESTS_DOWNLOAD_VALUE.to_string(),
+            },
+            Self::LocalPath(path) => GuestSource::LocalPath {
+                path: fs::canonicalize(path)
+                    .unwrap_or_else(|_| path.clone())
+                    .display()
+                    .to_string(),
+            },
+            Self::ArtifactBaseUrl(url) => GuestSource::ArtifactBaseUrl { url: url.clone() },
+        }
+    }
+
+    /// Returns the key under which guests of this source are cached.
+    fn cache_key(&self) -> String {
+        match self {
+            Self::Default => {
+                format!("ere-guests-{ERE_GUESTS_DOWNLOAD_KIND}-{ERE_GUESTS_DOWNLOAD_VALUE}")
+            }
+            Self::LocalPath(path) => {
+                let digest = sha256_hex(path.to_string_lossy().as_bytes());
+                format!("path-{}", &digest[..16])
+            }
+            Self::ArtifactBaseUrl(url) => {
+                let digest = sha256_hex(url.trim_end_matches('/').as_bytes());
+                format!("url-{}", &digest[..16])
+            }
+        }
+    }
+
+    /// Returns the base URL whose `SHA256SUMS` and `.sha256` sidecars cover downloaded guests.
+    ///
+    /// ere-guests workflow artifacts of a commit publish no checksums.
+    fn checksum_base_url(&self) -> Option<String> {
+        match self {
+            Self::Default if ERE_GUESTS_DOWNLOAD_KIND == "tag" => Some(format!(
+                "{ERE_GUESTS_RELEASES_URL}/{ERE_GUESTS_DOWNLOAD_VALUE}"
+            )),
+            Self::Default | Self::LocalPath(_) => None,
+            Self::ArtifactBaseUrl(url) => Some(url.clone()),
+        }
+    }
+
     /// Returns a stable label for externally supplied guest artifacts.
     pub fn version_label(&self) -> Option<String> {
         match self {
@@ -62,19 +124,24 @@ pub enum ZkVMInstance {
     Dockerized {
         /// zkVM instance
         zkvm: DockerizedzkVM,
-        /// ELF of Zisk guest with feature `cycle-scope` enabled.
-        /// `Some` only if the guest is a Zisk guest.
+        /// SHA-256 of the guest ELF, hex encoded.
+        elf_sha256: String,
+        /// Where the guest program was resolved from.
+        guest_source: GuestSource,
+        /// ELF run by the zkVM's native profiler: the Zisk guest built with feature
+        /// `cycle-scope`, or the guest ELF itself for SP1 and RISC Zero.
         profiling_elf: Option<Elf>,
     },
-    /// Remote Zisk proving cluster client.
-    ZiskClusterClient {
-        /// gRPC client connected to the remote Zisk cluster.
-        client: ZiskClusterClient,
-        /// Per-request prove timeout, propagated from the `DockerizedzkVMConfig`
-        /// prove timeout. Defaults to 3 minutes.
-        prove_timeout: Duration,
-        /// ELF of Zisk guest with feature `cycle-scope` enabled.
-        /// `Some` only if the guest is a Zisk guest.
+    /// Guest program hosted by a remote proving cluster or proving network.
+    Remote {
+        /// Remote prover the guest program is registered with.
+        prover: Box<dyn RemoteProver>,
+        /// SHA-256 of the guest ELF, hex encoded.
+        elf_sha256: String,
+        /// Where the guest program was resolved from.
+        guest_source: GuestSource,
+        /// ELF run by the zkVM's native profiler: the Zisk guest built with feature
+        /// `cycle-scope`, or the guest ELF itself for SP1 and RISC Zero.
         profiling_elf: Option<Elf>,
     },
 }
@@ -84,7 +151,7 @@ impl ZkVMInstance {
     pub fn zkvm_kind(&self) -> zkVMKind {
         match self {
             Self::Dockerized { zkvm, .. } => zkvm.zkvm_kind(),
-            Self::ZiskClusterClient { .. } => zkVMKind::Zisk,
+            Self::Remote { prover, .. } => prover.zkvm_kind(),
         }
     }
 
@@ -92,33 +159,65 @@ impl ZkVMInstance {
     pub fn name(&self) -> &'static str {
         match self {
             Self::Dockerized { zkvm, .. } => zkvm.name(),
-            Self::ZiskClusterClient { client, .. } => client.verifier().name(),
+            Self::Remote { prover, .. } => prover.zkvm_kind().as_str(),
         }
     }
 
     /// Returns the zkVM SDK version.
-    pub fn sdk_version(&self) -> &'static str {
+    pub fn sdk_version(&self) -> &str {
         match self {
             Self::Dockerized { zkvm, .. } => zkvm.sdk_version(),
-            Self::ZiskClusterClient { client, .. } => client.verifier().sdk_version(),
+            Self::Remote { prover, .. } => prover.sdk_version(),
         }
     }
 
-    /// Returns the ELF for Zisk profiling.
+    /// Returns the hex encoded SHA-256 of the guest ELF.
+    pub fn elf_sha256(&self) -> &str {
+        match self {
+            Self::Dockerized { elf_sha256, .. } | Self::Remote { elf_sha256, .. } => elf_sha256,
+        }
+    }
+
+    /// Returns the hex encoded program verifying key.
+    pub fn program_vk_hex(&self) -> String {
+        match self {
+            Self::Dockerized { zkvm, .. } => hex::encode(&zkvm.program_vk().0),
+            Self::Remote { prover, .. } => hex::encode(prover.program_vk()),
+        }
+    }
+
+    /// Returns the guest program and tooling recorded with every result of this instance.
+    pub fn provenance(&self) -> Provenance {
+        let (Self::Dockerized { guest_source, .. } | Self::Remote { guest_source, .. }) = self;
+        Provenance {
+            zkvm: self.name().to_string(),
+            sdk_version: self.sdk_version().to_string(),
+            elf_sha256: self.elf_sha256().to_string(),
+            program_vk: self.program_vk_hex(),
+            guest_source: guest_source.clone(),
+            ere_image_tag: DOCKER_IMAGE_TAG.to_string(),
+            ere_hosts_commit: ERE_HOSTS_GIT_COMMIT.to_string(),
+        }
+    }
+
+    /// Returns the ELF run by the zkVM's native profiler.
     pub const fn profiling_elf(&self) -> Option<&Elf> {
         match self {
-            Self::Dockerized { profiling_elf, .. }
-            | Self::ZiskClusterClient { profiling_elf, .. } => profiling_elf.as_ref(),
+            Self::Dockerized { profiling_elf, .. } | Self::Remote { profiling_elf, .. } => {
+                profiling_elf.as_ref()
+            }
         }
     }
 
     /// Executes the guest program without proving.
+    ///
+    /// Remote instances execute on the remote prover when it supports execution. Zisk cluster
+    /// instances execute locally with `ziskemu` in the Zisk server image, reporting emulator steps
+    /// as cycles.
     pub fn execute(&self, input: &Input) -> Result<(PublicValues, ProgramExecutionReport)> {
         match self {
             Self::Dockerized { zkvm, .. } => zkvm.execute(input),
-            Self::ZiskClusterClient { .. } => {
-                bail!("ZiskClusterClient does not support Action::Execute")
-            }
+            Self::Remote { prover, .. } => prover.execute(input),
         }
     }
 
@@ -129,21 +228,7 @@ impl ZkVMInstance {
     ) -> Result<(PublicValues, EncodedProof, ProgramProvingReport)> {
         match self {
             Self::Dockerized { zkvm, .. } => zkvm.prove(input),
-            Self::ZiskClusterClient {
-                client,
-                prove_timeout,
-                ..
-            } => {
-                let deadline = Instant::now() + *prove_timeout;
-                let (proof, proving_time) = block_on(client.prove(input, deadline))?;
-                let (_, public_values) = proof.program_vk_and_public_values()?;
-                let proof = proof.encode_to_vec()?;
-                Ok((
-                    public_values,
-                    EncodedProof(proof),
-                    ProgramProvingReport::new(proving_time),
-                ))
-            }
+            Self::Remote { prover, .. } => prover.prove(input),
         }
     }
 
@@ -151,10 +236,7 @@ impl ZkVMInstance {
     pub fn verify(&self, proof: &EncodedProof) -> Result<PublicValues> {
         match self {
             Self::Dockerized { zkvm, .. } => zkvm.verify(proof),
-            Self::ZiskClusterClient { client, .. } => {
-                let proof = ZiskProof::decode_from_slice(&proof.0)?;
-                Ok(client.verifier().verify(&proof)?)
-            }
+            Self::Remote { prover, .. } => prover.verify(proof),
         }
     }
 }
@@ -166,21 +248,21 @@ impl std::fmt::Debug for ZkVMInstance {
                 .debug_struct("Dockerized")
                 .field("zkvm", &zkvm.name())
                 .field("sdk_version", &zkvm.sdk_version())
-                .field("program_vk", &hex::encode(&zkvm.program_vk().0))
+                .field("program_vk", &self.program_vk_hex())
                 .finish(),
-            Self::ZiskClusterClient { client, .. } => f
-                .debug_struct("ZiskClusterClient")
-                .field("zkvm", &client.verifier().name())
-                .field("sdk_version", &client.verifier().sdk_version())
-                .field(
-                    "program_vk",
-                    &hex::encode(client.program_vk().encode_to_vec().expect("infallible")),
-                )
+            Self::Remote { prover, .. } => f
+                .debug_struct("Remote")
+                .field("zkvm", &prover.zkvm_kind().as_str())
+                .field("sdk_version", &prover.sdk_version())
+                .field("program_vk", &self.program_vk_hex())
                 .finish(),
         }
     }
 }
 
+/// File in each zkVM output directory recording the provenance of its results.
+pub const RUN_INFO_FILE_NAME: &str = "run.json";
+
 /// Holds the configuration for running benchmarks
 #[derive(Debug, Clone)]
 pub struct RunConfig {
@@ -190,14 +272,67 @@ pub struct RunConfig {
     pub sub_folder: Option<String>,
     /// Action to perform: either proving or executing
     pub action: Action,
-    /// Force rerun benchmarks even if output files already exist
-    pub force_rerun: bool,
+    /// Which fixtures with recorded results are run again
+    pub rerun: RerunPolicy,
+    /// Timeout applied to each execute or prove request, recorded in the run manifest
+    pub timeout: Option<Duration>,
     /// Optional folder to dump input files
     pub dump_inputs_folder: Option<PathBuf>,
-    /// Optional Zisk profiling configuration
-    pub zisk_profile_config: Option<ProfileConfig>,
+    /// Optional guest profiling configuration
+    pub profile_config: Option<ProfileConfig>,
     /// Optional folder to save proof artifacts for later verification
     pub save_proofs_folder: Option<PathBuf>,
+    /// Warmup and measured iterations per fixture
+    pub repetitions: Repetitions,
+    /// Number of fixtures proven concurrently on one zkVM instance
+    pub prove_concurrency: NonZeroUsize,
+    /// Whether to sample resource usage of the measured iterations. Sampled runs execute
+    /// fixtures one at a time and require a `prove_concurrency` of one.
+    pub sample_resources: bool,
+    /// Optional Prometheus metrics recording fixture progress
+    pub progress: Option<Arc<ProgressMetrics>>,
+}
+
+/// Number of warmup and measured iterations run for each fixture.
+///
+/// Warmup results are discarded. When more than one iteration is measured, the scalar metrics
+/// report the median and a statistics section records every sample.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Repetitions {
+    /// Discarded iterations run before measuring
+    pub warmup: u32,
+    /// Measured iterations, at least one
+    pub measured: u32,
+}
+
+impl Default for Repetitions {
+    fn default() -> Self {
+        Self {
+            warmup: 0,
+            measured: 1,
+        }
+    }
+}
+
+impl Repetitions {
+    /// Returns whether the run differs from a single unwarmed measurement, in which case
+    /// statistics are recorded.
+    pub const fn records_statistics(&self) -> bool {
+        self.warmup > 0 || self.measured > 1
+    }
+
+    /// Runs the warmup iterations, logging and discarding failures.
+    pub(crate) fn warm_up<T>(&self, fixture_name: &str, mut op: impl FnMut() -> Result<T>) {
+        for iteration in 1..=self.warmup {
+            info!(
+                "Warmup {}/{} for {} (result will be discarded)",
+                iteration, self.warmup, fixture_name
+            );
+            if let Err(reason) = catch_crash(&mut op) {
+                warn!("Warmup failed for {}: {}", fixture_name, reason);
+            }
+        }
+    }
 }
 
 /// Action specifies whether we should prove or execute
@@ -211,22 +346,67 @@ pub enum Action {
     Verify,
 }
 
+impl Action {
+    /// Returns the lowercase action name.
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::Prove => "prove",
+            Self::Execute => "execute",
+            Self::Verify => "verify",
+        }
+    }
+}
+
 /// Executes benchmarks from a lazy iterator of fixtures.
 pub fn run_benchmark_iter<I>(instance: &ZkVMInstance, config: &RunConfig, inputs: I) -> Result<()>
 where
     I: Iterator<Item = Result<Box<dyn GuestFixture>>> + Send,
 {
-    HardwareInfo::detect().to_path(config.output_folder.join("hardware.json"))?;
+    if config.repetitions.measured == 0 {
+        bail!("at least one measured iteration is required");
+    }
+    // Resource probes cannot tell concurrent fixtures apart, so sampled runs go one at a time.
+    if config.sample_resources
+        && matches!(config.action, Action::Prove)
+        && config.prove_concurrency.get() > 1
+    {
+        bail!("resource usage sampling requires proving one fixture at a time");
+    }
+    if config.sample_resources && matches!(config.action, Action::Execute) {
+        warn!("Sampling resource usage, executing fixtures one at a time");
+    }
+    HardwareInfo::detect().to_path(results::hardware_path(&config.output_folder))?;
+    instance
+        .provenance()
+        .to_path(benchmark_output_dir(instance, config).join(RUN_INFO_FILE_NAME))?;
+    let manifest = RunManifestStore::open(instance, config)?;
+    let journal = EventJournal::open(instance, config)?;
 
     match config.action {
-        Action::Execute => inputs.par_bridge().try_for_each(|input| {
-            let input = input?;
-            process_input(instance, input, config)
-        })?,
+        Action::Execute if !config.sample_resources => {
+            inputs.par_bridge().try_for_each(|input| {
+                let input = input?;
+                process_input(instance, input, config, &manifest, &journal)
+            })?
+        }
 
-        Action::Prove => inputs.into_iter().try_for_each(|input| {
+        Action::Prove if config.prove_concurrency.get() > 1 => {
+            let pool = rayon::ThreadPoolBuilder::new()
+                .num_threads(config.prove_concurrency.get())
+                .thread_name(|index| format!("prove-{index}"))
+                .build()
+                .context("Failed to build proving thread pool")?;
+            pool.install(|| {
+                inputs.par_bridge().try_for_each(|input| {
+                    let input = input?;
+                    process_input(instance, input, config, &manifest, &journal)
+                })
+            })?
+        }
+
+        Action::Execute | Action::Prove => inputs.into_iter().try_for_each(|input| {
             let input = input?;
-            process_input(instance, input, config)
+            process_input(instance, input, config, &manifest, &journal)
         })?,
 
         Action::Verify => {
@@ -239,15 +419,16 @@ where
     Ok(())
 }
 
-fn benchmark_zkvm_name(zkvm: &ZkVMInstance) -> String {
-    format!("{}-{}", zkvm.name(), zkvm.sdk_version())
+pub(crate) fn benchmark_zkvm_name(zkvm: &ZkVMInstance) -> String {
+    results::versioned_dir_name(zkvm.name(), zkvm.sdk_version())
 }
 
 fn benchmark_output_dir_for_name(config: &RunConfig, zkvm_name: &str) -> PathBuf {
-    config
-        .output_folder
-        .join(config.sub_folder.as_deref().unwrap_or(""))
-        .join(zkvm_name)
+    results::run_dir(
+        &config.output_folder,
+        config.sub_folder.as_deref(),
+        zkvm_name,
+    )
 }
 
 fn benchmark_output_path_for_name(
@@ -255,7 +436,12 @@ fn benchmark_output_path_for_name(
     zkvm_name: &str,
     fixture_name: &str,
 ) -> PathBuf {
-    benchmark_output_dir_for_name(config, zkvm_name).join(format!("{fixture_name}.json"))
+    results::run_path(
+        &config.output_folder,
+        config.sub_folder.as_deref(),
+        zkvm_name,
+        fixture_name,
+    )
 }
 
 /// Returns the output directory for a given zkVM benchmark run.
@@ -272,18 +458,45 @@ pub fn benchmark_output_path(
     benchmark_output_path_for_name(config, &benchmark_zkvm_name(zkvm), fixture_name)
 }
 
-/// Processes a single input through the zkVM
-fn process_input(zkvm: &ZkVMInstance, io: impl GuestFixture, config: &RunConfig) -> Result<()> {
+/// Processes a single input through the zkVM, unless the run manifest has an up to date result
+fn process_input(
+    zkvm: &ZkVMInstance,
+    io: impl GuestFixture,
+    config: &RunConfig,
+    manifest: &RunManifestStore,
+    journal: &EventJournal,
+) -> Result<()> {
     let zkvm_name = benchmark_zkvm_name(zkvm);
     let fixture_name = io.name();
     let out_path = benchmark_output_path_for_name(config, &zkvm_name, &fixture_name);
+    let started = Instant::now();
 
-    if !config.force_rerun && out_path.exists() {
-        info!("Skipping {} (already exists)", fixture_name);
+    let input = io.input()?;
+    let input_prepared = Instant::now();
+    let progress = config.progress.as_deref();
+    let labels = RunLabels::new(zkvm, config);
+    if !manifest.should_run(&fixture_name, input.stdin()) {
+        if let Some(progress) = progress {
+            progress.skipped(&labels);
+        }
+        journal.record(
+            &fixture_name,
+            RunEvent::Skipped {
+                reason: "up to date in run manifest".to_string(),
+            },
+        );
         return Ok(());
     }
-
-    let input = io.input()?;
+    // Skipped fixtures only get a `Skipped` event, so the start events are recorded once the
+    // fixture is known to run, with the time they happened.
+    journal.record_at(&fixture_name, RunEvent::FixtureStarted, started);
+    journal.record_at(
+        &fixture_name,
+        RunEvent::InputPrepared {
+            input_bytes: input.stdin().len(),
+        },
+        input_prepared,
+    );
 
     // Dump input if requested
     if let Some(ref dump_folder) = config.dump_inputs_folder {
@@ -296,90 +509,46 @@ fn process_input(zkvm: &ZkVMInstance, io: impl GuestFixture, config: &RunConfig)
     }
 
     info!("Running {}", fixture_name);
-    let (execution, proving) = match config.action {
+    let running = progress.map(|progress| progress.start(&labels, &fixture_name));
+    let (execution, proving, resources, profile, outcome) = match config.action {
         Action::Execute => {
-            // Run Zisk profiling if configured
-            if let Some(profile_config) = &config.zisk_profile_config {
-                let Some(profiling_elf) = zkvm.profiling_elf() else {
-                    bail!("Zisk profiling configured but profiling ELF not found")
-                };
+            // Run guest profiling if configured
+            let mut profile = None;
+            if let Some(profile_config) = &config.profile_config {
                 let outcome = run_profiling(
                     profile_config,
-                    profiling_elf,
-                    input.stdin(),
+                    zkvm,
+                    &input,
                     &fixture_name,
                     config.sub_folder.as_deref(),
                 );
-                if let ProfileOutcome::Failed(message) = outcome {
-                    warn!(
-                        "Zisk profiling failed for {} but benchmark execution will continue: {}",
+                match outcome {
+                    ProfileOutcome::Success(parsed) => profile = parsed,
+                    ProfileOutcome::Failed(message) => warn!(
+                        "Profiling failed for {} but benchmark execution will continue: {}",
                         fixture_name, message
-                    );
+                    ),
                 }
             }
 
-            let run = panic::catch_unwind(panic::AssertUnwindSafe(|| zkvm.execute(&input)));
-            let execution = match run {
-                Ok(Ok((public_values, report))) => {
-                    let output_matched = public_output_matched(&io, &public_values)
-                        .context("Failed to compare public output from execution")?;
-
-                    ExecutionMetrics::Success {
-                        output_matched,
-                        total_num_cycles: report.total_num_cycles,
-                        region_cycles: report.region_cycles.into_iter().collect(),
-                        execution_duration: report.execution_duration,
-                    }
-                }
-                Ok(Err(e)) => ExecutionMetrics::Crashed(CrashInfo {
-                    reason: e.to_string(),
-                }),
-                Err(panic_info) => ExecutionMetrics::Crashed(CrashInfo {
-                    reason: get_panic_msg(panic_info),
-                }),
-            };
-            (Some(execution), None)
+            journal.record(&fixture_name, RunEvent::ExecuteStarted);
+            let (execution, resources) = execute_fixture(zkvm, &io, &input, config)?;
+            let outcome = FixtureOutcome::of_execution(&execution);
+            journal.record(&fixture_name, RunEvent::ExecuteFinished { outcome });
+            if let ExecutionMetrics::Crashed(crash) = &execution {
+                journal.record(&fixture_name, RunEvent::crashed(crash));
+            }
+            (Some(execution), None, resources, profile, outcome)
         }
         Action::Prove => {
-            let run = panic::catch_unwind(panic::AssertUnwindSafe(|| zkvm.prove(&input)));
-            let proving = match run {
-                Ok(Ok((public_values, proof, report))) => {
-                    let prover_output_matched = public_output_matched(&io, &public_values)
-                        .context("Failed to compare public output from proof")?;
-
-                    // Save proof to disk if requested
-                    if let Some(ref proofs_folder) = config.save_proofs_folder {
-                        save_proof(
-                            &proof,
-                            &fixture_name,
-                            &zkvm_name,
-                            proofs_folder,
-                            config.sub_folder.as_deref(),
-                        )?;
-                    }
-
-                    let verify_start = std::time::Instant::now();
-                    let verif_public_values =
-                        zkvm.verify(&proof).context("Failed to verify proof")?;
-                    let verification_time_ms = verify_start.elapsed().as_millis();
-                    let verifier_output_matched = public_output_matched(&io, &verif_public_values)
-                        .context("Failed to compare public output from proof verification")?;
-
-                    ProvingMetrics::Success {
-                        output_matched: prover_output_matched && verifier_output_matched,
-                        proof_size: proof.len(),
-                        proving_time_ms: report.proving_time.as_millis(),
-                        verification_time_ms,
-                    }
-                }
-                Ok(Err(e)) => ProvingMetrics::Crashed(CrashInfo {
-                    reason: e.to_string(),
-                }),
-                Err(panic_info) => ProvingMetrics::Crashed(CrashInfo {
-                    reason: get_panic_msg(panic_info),
-                }),
-            };
-            (None, Some(proving))
+            journal.record(&fixture_name, RunEvent::ProveStarted);
+            let (proving, resources) = prove_fixture(zkvm, &io, &input, config)?;
+            let outcome = FixtureOutcome::of_proving(&proving);
+            journal.record(&fixture_name, RunEvent::ProveFinished { outcome });
+            if let ProvingMetrics::Crashed(crash) = &proving {
+                journal.record(&fixture_name, RunEvent::crashed(crash));
+            }
+            (None, Some(proving), resources, None, outcome)
         }
         Action::Verify => {
             return Err(anyhow!(
@@ -388,21 +557,189 @@ fn process_input(zkvm: &ZkVMInstance, io: impl GuestFixture, config: &RunConfig)
         }
     };
 
+    let metadata = io.metadata();
+    let per_gas = metadata
+        .get("block_used_gas")
+        .and_then(serde_json::Value::as_u64)
+        .and_then(|gas_used| {
+            GasNormalizedMetrics::new(gas_used, execution.as_ref(), proving.as_ref())
+        });
     let report = BenchmarkRun {
         name: fixture_name.clone(),
         timestamp_completed: zkevm_metrics::chrono::Utc::now(),
-        metadata: io.metadata(),
+        metadata,
         execution,
         proving,
         verification: None,
+        resources,
+        provenance: Some(zkvm.provenance()),
+        profile,
+        per_gas,
     };
 
     info!("Saving report {}", fixture_name);
     report.to_path(out_path)?;
+    manifest.record(&fixture_name, input.stdin(), outcome)?;
+    journal.record(&fixture_name, RunEvent::FixtureFinished { outcome });
+    if let Some(running) = running {
+        running.finish(report.execution.as_ref(), report.proving.as_ref(), None);
+    }
 
     Ok(())
 }
 
+/// Executes the fixture for the configured repetitions, sampling resource usage over the
+/// measured iterations when configured.
+fn execute_fixture(
+    zkvm: &ZkVMInstance,
+    io: &impl GuestFixture,
+    input: &Input,
+    config: &RunConfig,
+) -> Result<(ExecutionMetrics, Option<ResourceUsage>)> {
+    let repetitions = config.repetitions;
+    repetitions.warm_up(&io.name(), || zkvm.execute(input));
+
+    let sampler = config
+        .sample_resources
+        .then(|| ResourceSampler::start(zkvm));
+    let execution = measure_execution(zkvm, io, input, repetitions);
+    let resources = sampler.and_then(ResourceSampler::finish);
+    Ok((execution?, resources))
+}
+
+fn measure_execution(
+    zkvm: &ZkVMInstance,
+    io: &impl GuestFixture,
+    input: &Input,
+    repetitions: Repetitions,
+) -> Result<ExecutionMetrics> {
+    let mut output_matched = true;
+    let mut durations = Vec::new();
+    let mut last_report = None;
+    for _ in 0..repetitions.measured {
+        let (public_values, report) = match catch_crash(|| zkvm.execute(input)) {
+            Ok(run) => run,
+            Err(reason) => return Ok(ExecutionMetrics::Crashed(CrashInfo { reason })),
+        };
+        output_matched &= public_output_matched(io, &public_values)
+            .context("Failed to compare public output from execution")?;
+        durations.push(report.execution_duration);
+        last_report = Some(report);
+    }
+
+    let report = last_report.context("at least one measured iteration is required")?;
+    let execution_duration =
+        DurationStats::from_samples(durations).context("no execution samples recorded")?;
+    Ok(ExecutionMetrics::Success {
+        output_matched,
+        total_num_cycles: report.total_num_cycles,
+        region_cycles: report.region_cycles.into_iter().collect(),
+        execution_duration: execution_duration.median,
+        statistics: repetitions.records_statistics().then(|| {
+            Box::new(ExecutionStatistics {
+                warmup_iterations: repetitions.warmup,
+                execution_duration,
+            })
+        }),
+    })
+}
+
+/// Proves and verifies the fixture for the configured repetitions, sampling resource usage over
+/// the measured iterations when configured.
+///
+/// The proof from the first measured iteration is saved when requested.
+fn prove_fixture(
+    zkvm: &ZkVMInstance,
+    io: &impl GuestFixture,
+    input: &Input,
+    config: &RunConfig,
+) -> Result<(ProvingMetrics, Option<ResourceUsage>)> {
+    config.repetitions.warm_up(&io.name(), || {
+        let (_, proof, _) = zkvm.prove(input)?;
+        zkvm.verify(&proof)
+    });
+
+    let sampler = config
+        .sample_resources
+        .then(|| ResourceSampler::start(zkvm));
+    let proving = measure_proving(zkvm, io, input, config);
+    let resources = sampler.and_then(ResourceSampler::finish);
+    Ok((proving?, resources))
+}
+
+fn measure_proving(
+    zkvm: &ZkVMInstance,
+    io: &impl GuestFixture,
+    input: &Input,
+    config: &RunConfig,
+) -> Result<ProvingMetrics> {
+    let fixture_name = io.name();
+    let zkvm_name = benchmark_zkvm_name(zkvm);
+    let repetitions = config.repetitions;
+    let mut output_matched = true;
+    let mut proof_size = 0;
+    let mut proving_times = Vec::new();
+    let mut verification_times = Vec::new();
+    for iteration in 0..repetitions.measured {
+        let (public_values, proof, report) = match catch_crash(|| zkvm.prove(input)) {
+            Ok(run) => run,
+            Err(reason) => return Ok(ProvingMetrics::Crashed(CrashInfo { reason })),
+        };
+        let prover_output_matched = public_output_matched(io, &public_values)
+            .context("Failed to compare public output from proof")?;
+
+        // Save proof to disk if requested
+        if iteration == 0 {
+            if let Some(ref proofs_folder) = config.save_proofs_folder {
+                save_proof(
+                    &proof,
+                    &fixture_name,
+                    &zkvm_name,
+                    proofs_folder,
+                    config.sub_folder.as_deref(),
+                )?;
+            }
+        }
+
+        let verify_start = std::time::Instant::now();
+        let verif_public_values = zkvm.verify(&proof).context("Failed to verify proof")?;
+        verification_times.push(verify_start.elapsed());
+        let verifier_output_matched = public_output_matched(io, &verif_public_values)
+            .context("Failed to compare public output from proof verification")?;
+
+        output_matched &= prover_output_matched && verifier_output_matched;
+        proof_size = proof.len();
+        proving_times.push(report.proving_time);
+    }
+
+    let proving_time =
+        DurationStats::from_samples(proving_times).context("no proving samples recorded")?;
+    let verification_time = DurationStats::from_samples(verification_times)
+        .context("no verification samples recorded")?;
+    Ok(ProvingMetrics::Success {
+        output_matched,
+        proof_size,
+        proving_time_ms: proving_time.median.as_millis(),
+        verification_time_ms: verification_time.median.as_millis(),
+        statistics: repetitions.records_statistics().then(|| {
+            Box::new(ProvingStatistics {
+                warmup_iterations: repetitions.warmup,
+                proving_time,
+                verification_time,
+            })
+        }),
+    })
+}
+
+/// Runs `op`, converting both errors and panics into a crash reason.
+pub(crate) fn catch_crash<T>(op: impl FnOnce() -> Result<T>) -> Result<T, String> {
+    match panic::catch_unwind(panic::AssertUnwindSafe(op)) {
+        Ok(Ok(value)) => Ok(value),
+        Ok(Err(e)) => Err(e.to_string()),
+        Err(panic_info) => Err(get_panic_msg(panic_info)),
+    }
+}
+
 pub(crate) fn get_panic_msg(panic_info: Box<dyn Any + Send>) -> String {
     panic_info
         .downcast_ref::<&str>()
@@ -418,6 +755,7 @@ pub async fn get_el_zkvm_instances(
     resource: ProverResource,
     zkvm_config: DockerizedzkVMConfig,
     guest_source: &GuestProgramSource,
+    guest_cache: &GuestCache,
 ) -> Result<Vec<ZkVMInstance>> {
     let guest_name_prefix = format!("stateless-validator-{el}");
     get_guest_zkvm_instances(
@@ -426,6 +764,7 @@ pub async fn get_el_zkvm_instances(
         resource,
         zkvm_config,
         guest_source,
+        guest_cache,
     )
     .await
 }
@@ -437,11 +776,18 @@ pub async fn get_guest_zkvm_instances(
     resource: ProverResource,
     zkvm_config: DockerizedzkVMConfig,
     guest_source: &GuestProgramSource,
+    guest_cache: &GuestCache,
 ) -> Result<Vec<ZkVMInstance>> {
     let mut instances = Vec::new();
     for zkvm in zkvms {
         let guest_name = format!("{}-{}", guest_name_prefix, zkvm.as_str());
-        let compiled = load_compiled(&guest_name, guest_source).await?;
+        let compiled = load_compiled(&guest_name, guest_source, guest_cache).await?;
+        let elf_sha256 = sha256_hex(&compiled.elf);
+        let profiling_elf = match zkvm {
+            zkVMKind::SP1 | zkVMKind::Risc0 => Some(compiled.elf.clone()),
+            _ => compiled.profiling_elf,
+        }
+        .map(Elf);
         let instance = match &resource {
             ProverResource::Cpu | ProverResource::Gpu => {
                 let zkvm = DockerizedzkVM::new(
@@ -453,25 +799,22 @@ pub async fn get_guest_zkvm_instances(
                 .with_context(|| format!("Failed to initialize DockerizedzkVM, kind {zkvm}"))?;
                 ZkVMInstance::Dockerized {
                     zkvm,
-                    profiling_elf: compiled.profiling_elf.map(Elf),
+                    elf_sha256,
+                    guest_source: guest_source.provenance(),
+                    profiling_elf,
                 }
             }
-            ProverResource::Cluster(cfg) if *zkvm == zkVMKind::Zisk => {
-                const DEFAULT_PROVE_TIMEOUT: Duration = Duration::from_mins(3);
-
-                let client = ZiskClusterClient::new(cfg, Elf(compiled.elf.clone()))
+            ProverResource::Cluster(_) | ProverResource::Network(_) => {
+                let prover = remote::connect(*zkvm, &resource, Elf(compiled.elf), &zkvm_config)
                     .await
-                    .map_err(|e| anyhow!("Failed to connect to Zisk cluster: {e}"))?;
-                ZkVMInstance::ZiskClusterClient {
-                    client,
-                    prove_timeout: zkvm_config.prove_timeout.unwrap_or(DEFAULT_PROVE_TIMEOUT),
-                    profiling_elf: compiled.profiling_elf.map(Elf),
+                    .with_context(|| format!("Failed to connect to remote prover, kind {zkvm}"))?;
+                ZkVMInstance::Remote {
+                    prover,
+                    elf_sha256,
+                    guest_source: guest_source.provenance(),
+                    profiling_elf,
                 }
             }
-            ProverResource::Cluster(_) => {
-                bail!("Cluster is only implemented for Zisk, got {zkvm}")
-            }
-            ProverResource::Network(_) => unreachable!(),
         };
         instances.push(instance);
     }
@@ -481,29 +824,96 @@ pub async fn get_guest_zkvm_instances(
 async fn load_compiled(
     guest_name: &str,
     guest_source: &GuestProgramSource,
+    cache: &GuestCache,
 ) -> Result<CompiledGuest> {
     if let GuestProgramSource::LocalPath(path) = guest_source {
-        let elf = fs::read(path.join(format!("{guest_name}.elf")))
-            .with_context(|| format!("Failed to read ELF from path: {}", path.display()))?;
-        let program_vk = fs::read(path.join(format!("{guest_name}.vk")))
-            .with_context(|| format!("Failed to read program vk from path: {}", path.display()))?;
-        let profiling_elf = fs::read(path.join(format!("{guest_name}-profiling.elf"))).ok();
-        return Ok(CompiledGuest {
-            elf,
-            program_vk,
-            profiling_elf,
-        });
+        return load_compiled_from_path(guest_name, path, cache.requires_checksums());
     }
 
-    if let GuestProgramSource::ArtifactBaseUrl(base_url) = guest_source {
-        return load_compiled_from_artifact_base_url(guest_name, base_url).await;
+    let client = reqwest::Client::new();
+    let checksum_base_url = guest_source.checksum_base_url();
+    let source_key = guest_source.cache_key();
+    if let Some(compiled) = cache.load(&source_key, guest_name)? {
+        // Published artifacts can be replaced, so cached guests are checked against the
+        // current checksums whenever they can be fetched.
+        let Some(base_url) = checksum_base_url.as_deref().filter(|_| !cache.is_offline()) else {
+            return Ok(compiled);
+        };
+        match verify_published_checksums(&client, Some(base_url), guest_name, &compiled, cache)
+            .await
+        {
+            Ok(()) => return Ok(compiled),
+            Err(err) => {
+                warn!(
+                    "Downloading {guest_name} again, its cached copy failed verification: {err:#}"
+                )
+            }
+        }
+    }
+    if cache.is_offline() {
+        bail!(
+            "Guest program {guest_name} is not in the guest cache at {} and offline mode forbids downloading it",
+            cache.root().display()
+        );
     }
 
-    let downloader = guest_downloader().await?;
-    downloader
-        .download(guest_name)
-        .await
-        .with_context(|| format!("Failed to download guest program: {guest_name}"))
+    let compiled = if let GuestProgramSource::ArtifactBaseUrl(base_url) = guest_source {
+        load_compiled_from_artifact_base_url(&client, guest_name, base_url).await?
+    } else {
+        let downloader = guest_downloader().await?;
+        downloader
+            .download(guest_name)
+            .await
+            .with_context(|| format!("Failed to download guest program: {guest_name}"))?
+    };
+    verify_published_checksums(
+        &client,
+        checksum_base_url.as_deref(),
+        guest_name,
+        &compiled,
+        cache,
+    )
+    .await?;
+    cache.store(&source_key, guest_name, &compiled)?;
+    Ok(compiled)
+}
+
+fn load_compiled_from_path(
+    guest_name: &str,
+    path: &Path,
+    require_checksums: bool,
+) -> Result<CompiledGuest> {
+    let checksums = fs::read_to_string(path.join(CHECKSUM_MANIFEST_FILE_NAME))
+        .map(|text| parse_checksums(&text))
+        .unwrap_or_default();
+    let verify = |file_name: &str, bytes: &[u8]| {
+        let expected = checksums.get(file_name).cloned().or_else(|| {
+            fs::read_to_string(path.join(format!("{file_name}.sha256")))
+                .ok()
+                .and_then(|text| parse_sidecar(&text))
+        });
+        verify_digest(file_name, bytes, expected.as_deref(), require_checksums)
+    };
+
+    let elf_name = format!("{guest_name}.elf");
+    let elf = fs::read(path.join(&elf_name))
+        .with_context(|| format!("Failed to read ELF from path: {}", path.display()))?;
+    verify(&elf_name, &elf)?;
+    let vk_name = format!("{guest_name}.vk");
+    let program_vk = fs::read(path.join(&vk_name))
+        .with_context(|| format!("Failed to read program vk from path: {}", path.display()))?;
+    verify(&vk_name, &program_vk)?;
+    let profiling_name = format!("{guest_name}-profiling.elf");
+    let profiling_elf = fs::read(path.join(&profiling_name)).ok();
+    if let Some(profiling_elf) = &profiling_elf {
+        verify(&profiling_name, profiling_elf)?;
+    }
+
+    Ok(CompiledGuest {
+        elf,
+        program_vk,
+        profiling_elf,
+    })
 }
 
 async fn guest_downloader() -> Result<Downloader> {
@@ -554,20 +964,21 @@ async fn guest_downloader() -> Result<Downloader> {
 }
 
 async fn load_compiled_from_artifact_base_url(
+    client: &reqwest::Client,
     guest_name: &str,
     base_url: &str,
 ) -> Result<CompiledGuest> {
-    let client = reqwest::Client::new();
     let elf_url = guest_artifact_url(base_url, &format!("{guest_name}.elf"));
-    let vk_url = guest_artifact_url(base_url, &format!("{guest_name}.vk"));
-    let profiling_url = guest_artifact_url(base_url, &format!("{guest_name}-profiling.elf"));
-
     info!("Downloading guest program from {elf_url}");
-    let elf = download_required_artifact(&client, &elf_url).await?;
-    let program_vk = download_optional_artifact(&client, &vk_url)
+    let elf = download_required_artifact(client, &elf_url).await?;
+
+    let vk_url = guest_artifact_url(base_url, &format!("{guest_name}.vk"));
+    let program_vk = download_optional_artifact(client, &vk_url)
         .await?
         .unwrap_or_default();
-    let profiling_elf = download_optional_artifact(&client, &profiling_url).await?;
+
+    let profiling_url = guest_artifact_url(base_url, &format!("{guest_name}-profiling.elf"));
+    let profiling_elf = download_optional_artifact(client, &profiling_url).await?;
 
     Ok(CompiledGuest {
         elf,
@@ -576,6 +987,64 @@ async fn load_compiled_from_artifact_base_url(
     })
 }
 
+/// Checks the artifacts of `compiled` against the checksum manifest at `base_url`, or their
+/// `.sha256` sidecars when the manifest does not list them.
+///
+/// Without a base URL no checksum is published, which [`GuestCache::requires_checksums`] turns
+/// into an error.
+async fn verify_published_checksums(
+    client: &reqwest::Client,
+    base_url: Option<&str>,
+    guest_name: &str,
+    compiled: &CompiledGuest,
+    cache: &GuestCache,
+) -> Result<()> {
+    let checksums = match base_url {
+        Some(base_url) => {
+            let checksums_url = guest_artifact_url(base_url, CHECKSUM_MANIFEST_FILE_NAME);
+            download_optional_artifact(client, &checksums_url)
+                .await?
+                .map(|bytes| parse_checksums(&String::from_utf8_lossy(&bytes)))
+                .unwrap_or_default()
+        }
+        None => BTreeMap::new(),
+    };
+
+    let artifacts = [
+        (format!("{guest_name}.elf"), Some(&compiled.elf)),
+        (
+            format!("{guest_name}.vk"),
+            Some(&compiled.program_vk).filter(|vk| !vk.is_empty()),
+        ),
+        (
+            format!("{guest_name}-profiling.elf"),
+            compiled.profiling_elf.as_ref(),
+        ),
+    ];
+    for (file_name, bytes) in artifacts {
+        let Some(bytes) = bytes else {
+            continue;
+        };
+        let expected = match (checksums.get(&file_name), base_url) {
+            (Some(digest), _) => Some(digest.clone()),
+            (None, Some(base_url)) => {
+                let sidecar_url = guest_artifact_url(base_url, &format!("{file_name}.sha256"));
+                download_optional_artifact(client, &sidecar_url)
+                    .await?
+                    .and_then(|bytes| parse_sidecar(&String::from_utf8_lossy(&bytes)))
+            }
+            (None, None) => None,
+        };
+        verify_digest(
+            &file_name,
+            bytes,
+            expected.as_deref(),
+            cache.requires_checksums(),
+        )?;
+    }
+    Ok(())
+}
+
 async fn download_required_artifact(client: &reqwest::Client, url: &str) -> Result<Vec<u8>> {
     let response = client
         .get(url)
@@ -703,6 +1172,10 @@ fn public_output_matched(io: &impl GuestFixture, public_values: &[u8]) -> Result
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::progress::FIXTURES_TOTAL;
+    use crate::stateless_validator::{stateless_validator_input_iter, ExecutionClient};
+    use ere_util_tokio::block_on;
+    use zkevm_metrics::prometheus::Registry;
 
     struct Fixture {
         name: &'static str,
@@ -784,6 +1257,218 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn catch_crash_reports_errors_and_panics() {
+        assert_eq!(catch_crash(|| Ok(7)), Ok(7));
+        assert_eq!(
+            catch_crash(|| -> Result<()> { bail!("zkVM error") }),
+            Err("zkVM error".to_string())
+        );
+        assert_eq!(
+            catch_crash(|| -> Result<()> { panic!("guest panicked") }),
+            Err("guest panicked".to_string())
+        );
+    }
+
+    #[test]
+    fn repetitions_record_statistics_only_when_repeated_or_warmed_up() {
+        assert!(!Repetitions::default().records_statistics());
+        assert!(Repetitions {
+            warmup: 1,
+            measured: 1
+        }
+        .records_statistics());
+        assert!(Repetitions {
+            warmup: 0,
+            measured: 3
+        }
+        .records_statistics());
+    }
+
+    #[test]
+    fn warm_up_runs_and_discards_each_iteration() {
+        let mut runs = 0;
+        Repetitions {
+            warmup: 2,
+            measured: 1,
+        }
+        .warm_up("fixture", || -> Result<()> {
+            runs += 1;
+            bail!("discarded")
+        });
+
+        assert_eq!(runs, 2);
+    }
+
+    struct StubProver;
+
+    impl RemoteProver for StubProver {
+        fn zkvm_kind(&self) -> zkVMKind {
+            zkVMKind::SP1
+        }
+
+        fn sdk_version(&self) -> &str {
+            "1.0.0"
+        }
+
+        fn program_vk(&self) -> Vec<u8> {
+            Vec::new()
+        }
+
+        fn execute(&self, _input: &Input) -> Result<(PublicValues, ProgramExecutionReport)> {
+            let report = ProgramExecutionReport {
+                total_num_cycles: 7,
+                ..Default::default()
+            };
+            Ok((vec![1], report))
+        }
+
+        fn prove(
+            &self,
+            _input: &Input,
+        ) -> Result<(PublicValues, EncodedProof, ProgramProvingReport)> {
+            bail!("stub prover does not prove")
+        }
+
+        fn verify(&self, _proof: &EncodedProof) -> Result<PublicValues> {
+            bail!("stub prover does not verify")
+        }
+    }
+
+    #[test]
+    fn rerun_records_skip_of_completed_fixture() -> Result<()> {
+        let output_dir = tempfile::tempdir()?;
+        let registry = Arc::new(Registry::new());
+        let instance = ZkVMInstance::Remote {
+            prover: Box::new(StubProver),
+            elf_sha256: "aa".to_string(),
+            guest_source: GuestSource::LocalPath {
+                path: "guests".to_string(),
+            },
+            profiling_elf: None,
+        };
+        let config = RunConfig {
+            output_folder: output_dir.path().to_path_buf(),
+            sub_folder: None,
+            action: Action::Execute,
+            rerun: RerunPolicy::default(),
+            timeout: None,
+            dump_inputs_folder: None,
+            profile_config: None,
+            save_proofs_folder: None,
+            repetitions: Repetitions::default(),
+            prove_concurrency: NonZeroUsize::MIN,
+            sample_resources: true,
+            progress: Some(Arc::new(ProgressMetrics::new(registry.clone(), None))),
+        };
+        let fixtures =
+            || std::iter::once(Ok(Box::new(Fixture::new(vec![1])) as Box<dyn GuestFixture>));
+
+        run_benchmark_iter(&instance, &config, fixtures())?;
+        run_benchmark_iter(&instance, &config, fixtures())?;
+
+        let skipped = RunEvent::Skipped {
+            reason: "up to date in run manifest".to_string(),
+        };
+        let events = EventJournal::read(&benchmark_output_dir(&instance, &config))?;
+        let fixture_events: Vec<_> = events
+            .iter()
+            .filter(|entry| entry.fixture.as_deref() == Some("fixture"))
+            .map(|entry| &entry.event)
+            .collect();
+        assert_eq!(fixture_events.last(), Some(&&skipped));
+        // Only the first run started the fixture and prepared its input.
+        let started = fixture_events
+            .iter()
+            .filter(|event| matches!(event, RunEvent::FixtureStarted))
+            .count();
+        assert_eq!(started, 1);
+        assert!(matches!(
+            &fixture_events[..2],
+            [
+                RunEvent::FixtureStarted,
+                RunEvent::InputPrepared { input_bytes: 0 }
+            ]
+        ));
+
+        let zkvm = benchmark_zkvm_name(&instance);
+        let labels = [
+            ("el", ""),
+            ("zkvm", zkvm.as_str()),
+            ("action", "execute"),
+            ("outcome", "skipped"),
+        ];
+        assert_eq!(registry.value(FIXTURES_TOTAL, &labels), Some(1.0));
+        Ok(())
+    }
+
+    #[test]
+    fn rerun_through_fixture_iterator_records_skip() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let fixture_path = dir.path().join("fixture.json");
+        fs::write(
+            &fixture_path,
+            r#"{
+                "tests/foo.py::test_skip[fork_Amsterdam]": {
+                    "network": "Amsterdam",
+                    "config": {"chainid": "0x01"},
+                    "blocks": [
+                        {
+                            "statelessInputBytes": "0x0f",
+                            "statelessOutputBytes": "0x01",
+                            "blockHeader": {"number": "0x01", "gasUsed": "0x10"}
+                        }
+                    ]
+                }
+            }"#,
+        )?;
+        let instance = ZkVMInstance::Remote {
+            prover: Box::new(StubProver),
+            elf_sha256: "aa".to_string(),
+            guest_source: GuestSource::LocalPath {
+                path: "guests".to_string(),
+            },
+            profiling_elf: None,
+        };
+        let config = RunConfig {
+            output_folder: dir.path().join("output"),
+            sub_folder: None,
+            action: Action::Execute,
+            rerun: RerunPolicy::default(),
+            timeout: None,
+            dump_inputs_folder: None,
+            profile_config: None,
+            save_proofs_folder: None,
+            repetitions: Repetitions::default(),
+            prove_concurrency: NonZeroUsize::MIN,
+            sample_resources: false,
+            progress: None,
+        };
+        let run = || -> Result<()> {
+            let fixtures = stateless_validator_input_iter(
+                &fixture_path,
+                None,
+                ExecutionClient::Reth,
+                Some(RunManifestStore::open(&instance, &config)?),
+            )?;
+            run_benchmark_iter(&instance, &config, fixtures)
+        };
+
+        run()?;
+        run()?;
+
+        let events = EventJournal::read(&benchmark_output_dir(&instance, &config))?;
+        let last = events.last().expect("journal has events");
+        assert!(last.fixture.is_some());
+        assert_eq!(
+            last.event,
+            RunEvent::Skipped {
+                reason: "up to date in run manifest".to_string(),
+            }
+        );
+        Ok(())
+    }
+
     #[test]
     fn guest_artifact_url_joins_base_and_filename() {
         assert_eq!(
@@ -812,9 +1497,11 @@ mod tests {
             (path == "/stateless-validator-zesu-zisk.elf").then(|| Vec::from("elf-bytes"))
         });
 
+        let cache_dir = tempfile::tempdir()?;
         let compiled = block_on(load_compiled(
             "stateless-validator-zesu-zisk",
             &GuestProgramSource::ArtifactBaseUrl(server.base_url()),
+            &GuestCache::new(cache_dir.path().to_path_buf(), false),
         ))?;
 
         assert_eq!(compiled.elf, b"elf-bytes");
@@ -824,13 +1511,126 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn url_artifact_loader_rejects_checksum_mismatch() -> Result<()> {
+        let server = TestServer::spawn(|path| match path {
+            "/stateless-validator-zesu-zisk.elf" => Some(Vec::from("elf-bytes")),
+            "/SHA256SUMS" => Some(Vec::from(format!(
+                "{}  stateless-validator-zesu-zisk.elf\n",
+                sha256_hex(b"other-bytes")
+            ))),
+            _ => None,
+        });
+
+        let cache_dir = tempfile::tempdir()?;
+        let err = block_on(load_compiled(
+            "stateless-validator-zesu-zisk",
+            &GuestProgramSource::ArtifactBaseUrl(server.base_url()),
+            &GuestCache::new(cache_dir.path().to_path_buf(), false),
+        ))
+        .unwrap_err();
+
+        assert!(err.to_string().contains("Checksum mismatch"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn url_artifact_loader_can_require_checksums() -> Result<()> {
+        let server = TestServer::spawn(|path| {
+            (path == "/stateless-validator-zesu-zisk.elf").then(|| Vec::from("elf-bytes"))
+        });
+
+        let cache_dir = tempfile::tempdir()?;
+        let err = block_on(load_compiled(
+            "stateless-validator-zesu-zisk",
+            &GuestProgramSource::ArtifactBaseUrl(server.base_url()),
+            &GuestCache::new(cache_dir.path().to_path_buf(), false).with_required_checksums(true),
+        ))
+        .unwrap_err();
+
+        assert!(err.to_string().contains("Checksums are required"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn cached_guests_are_checked_against_published_checksums() -> Result<()> {
+        let server = TestServer::spawn(|path| match path {
+            "/stateless-validator-zesu-zisk.elf" => Some(Vec::from("new-bytes")),
+            "/SHA256SUMS" => Some(Vec::from(format!(
+                "{}  stateless-validator-zesu-zisk.elf\n",
+                sha256_hex(b"new-bytes")
+            ))),
+            _ => None,
+        });
+        let source = GuestProgramSource::ArtifactBaseUrl(server.base_url());
+        let cache_dir = tempfile::tempdir()?;
+        let cache = GuestCache::new(cache_dir.path().to_path_buf(), false);
+        let stale = CompiledGuest {
+            elf: b"old-bytes".to_vec(),
+            program_vk: Vec::new(),
+            profiling_elf: None,
+        };
+        cache.store(&source.cache_key(), "stateless-validator-zesu-zisk", &stale)?;
+
+        let compiled = block_on(load_compiled(
+            "stateless-validator-zesu-zisk",
+            &source,
+            &cache,
+        ))?;
+        assert_eq!(compiled.elf, b"new-bytes");
+
+        Ok(())
+    }
+
+    #[test]
+    fn offline_loader_uses_only_cached_guests() -> Result<()> {
+        let server = TestServer::spawn(|path| match path {
+            "/stateless-validator-zesu-zisk.elf" => Some(Vec::from("elf-bytes")),
+            "/stateless-validator-zesu-zisk.elf.sha256" => {
+                Some(Vec::from(sha256_hex(b"elf-bytes")))
+            }
+            _ => None,
+        });
+        let source = GuestProgramSource::ArtifactBaseUrl(server.base_url());
+        let cache_dir = tempfile::tempdir()?;
+        let offline = GuestCache::new(cache_dir.path().to_path_buf(), true);
+
+        let err = block_on(load_compiled(
+            "stateless-validator-zesu-zisk",
+            &source,
+            &offline,
+        ))
+        .unwrap_err();
+        assert!(err.to_string().contains("offline mode"));
+
+        let online = GuestCache::new(cache_dir.path().to_path_buf(), false);
+        block_on(load_compiled(
+            "stateless-validator-zesu-zisk",
+            &source,
+            &online,
+        ))?;
+
+        let compiled = block_on(load_compiled(
+            "stateless-validator-zesu-zisk",
+            &source,
+            &offline,
+        ))?;
+        assert_eq!(compiled.elf, b"elf-bytes");
+
+        Ok(())
+    }
+
     #[test]
     fn url_artifact_loader_fails_when_elf_is_missing() {
         let server = TestServer::spawn(|_| None);
 
+        let cache_dir = tempfile::tempdir().unwrap();
         let err = block_on(load_compiled(
             "stateless-validator-zesu-zisk",
             &GuestProgramSource::ArtifactBaseUrl(server.base_url()),
+            &GuestCache::new(cache_dir.path().to_path_buf(), false),
         ))
         .unwrap_err();
 
@@ -860,7 +1660,7 @@ mod tests {
             let handler = Arc::new(handler);
 
             thread::spawn(move || {
-                for stream in listener.incoming().take(3) {
+                for stream in listener.incoming() {
                     let Ok(mut stream) = stream else {
                         continue;
                     };
@@ -0,0 +1,227 @@
+//! Parser for the traces the SP1 executor writes to `TRACE_FILE`
+//!
+//! With `TRACE_FILE` set, the SP1 executor samples the guest's call stack every
+//! `TRACE_SAMPLE_RATE` cycles and writes the samples as a Gecko profile, the format of the Firefox
+//! profiler. Only the tables needed for folded stacks are read from each thread: `samples`,
+//! `stackTable`, `frameTable` and `stringTable`. Tables are indexed through their `schema`, so
+//! column order does not matter.
+
+use anyhow::{bail, Context, Result};
+use serde_json::{Map, Value};
+
+use crate::profiling::{FoldedStacks, UNATTRIBUTED_FRAME};
+
+/// Converts a Gecko profile written by the SP1 executor into folded stacks, weighting every
+/// sample with the `sample_rate` cycles it stands for.
+///
+/// Samples without a stack are attributed to [`UNATTRIBUTED_FRAME`].
+pub fn folded_stacks(trace: &[u8], sample_rate: u64) -> Result<FoldedStacks> {
+    let profile: Value = serde_json::from_slice(trace).context("SP1 trace is not valid JSON")?;
+    let threads = profile
+        .get("threads")
+        .and_then(Value::as_array)
+        .context("SP1 trace has no threads")?;
+
+    let mut folded = FoldedStacks::new();
+    for thread in threads {
+        add_thread(&mut folded, thread, sample_rate)?;
+    }
+    Ok(folded)
+}
+
+fn add_thread(folded: &mut FoldedStacks, thread: &Value, sample_rate: u64) -> Result<()> {
+    let strings = thread
+        .get("stringTable")
+        .and_then(Value::as_array)
+        .context("SP1 trace thread has no stringTable")?;
+
+    let frames = Table::new(thread, "frameTable")?;
+    let location = frames.column("location")?;
+    let frame_names = frames
+        .rows()
+        .map(|row| {
+            let index = row.index(location)?;
+            strings
+                .get(index)
+                .and_then(Value::as_str)
+                .with_context(|| format!("SP1 trace frame names missing string {index}"))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    // Stacks refer to their prefix, which always comes earlier in the table.
+    let stack_table = Table::new(thread, "stackTable")?;
+    let (prefix, frame) = (stack_table.column("prefix")?, stack_table.column("frame")?);
+    let mut stacks: Vec<Vec<&str>> = Vec::new();
+    for (index, row) in stack_table.rows().enumerate() {
+        let mut stack = match row.optional_index(prefix)? {
+            Some(prefix) if prefix < index => stacks[prefix].clone(),
+            Some(prefix) => bail!("SP1 trace stack {index} has a later prefix {prefix}"),
+            None => Vec::new(),
+        };
+        let frame = row.index(frame)?;
+        stack.push(
+            frame_names.get(frame).copied().with_context(|| {
+                format!("SP1 trace stack {index} refers to missing frame {frame}")
+            })?,
+        );
+        stacks.push(stack);
+    }
+
+    let samples = Table::new(thread, "samples")?;
+    let stack_column = samples.column("stack")?;
+    let mut counts = vec![0u64; stacks.len()];
+    let mut unattributed = 0u64;
+    for row in samples.rows() {
+        match row.optional_index(stack_column)? {
+            Some(stack) => {
+                *counts.get_mut(stack).with_context(|| {
+                    format!("SP1 trace sample refers to missing stack {stack}")
+                })? += 1;
+            }
+            None => unattributed += 1,
+        }
+    }
+
+    for (stack, count) in stacks.iter().zip(counts) {
+        folded.add(stack, count.saturating_mul(sample_rate));
+    }
+    folded.add(
+        &[UNATTRIBUTED_FRAME],
+        unattributed.saturating_mul(sample_rate),
+    );
+    Ok(())
+}
+
+/// A Gecko profile table: a `schema` mapping column names to positions and `data` rows.
+struct Table<'a> {
+    name: &'static str,
+    schema: &'a Map<String, Value>,
+    data: &'a [Value],
+}
+
+impl<'a> Table<'a> {
+    fn new(thread: &'a Value, name: &'static str) -> Result<Self> {
+        let table = thread
+            .get(name)
+            .with_context(|| format!("SP1 trace thread has no {name}"))?;
+        let schema = table
+            .get("schema")
+            .and_then(Value::as_object)
+            .with_context(|| format!("SP1 trace {name} has no schema"))?;
+        let data = table
+            .get("data")
+            .and_then(Value::as_array)
+            .with_context(|| format!("SP1 trace {name} has no data"))?;
+        Ok(Self { name, schema, data })
+    }
+
+    fn column(&self, column: &str) -> Result<usize> {
+        self.schema
+            .get(column)
+            .and_then(Value::as_u64)
+            .and_then(|position| usize::try_from(position).ok())
+            .with_context(|| format!("SP1 trace {} has no {column} column", self.name))
+    }
+
+    fn rows(&self) -> impl Iterator<Item = Row<'a>> + '_ {
+        self.data.iter().map(|row| Row {
+            table: self.name,
+            values: row.as_array().map(Vec::as_slice).unwrap_or_default(),
+        })
+    }
+}
+
+struct Row<'a> {
+    table: &'static str,
+    values: &'a [Value],
+}
+
+impl Row<'_> {
+    /// Reads an index column, where `null` means no entry.
+    fn optional_index(&self, column: usize) -> Result<Option<usize>> {
+        match self.values.get(column) {
+            None | Some(Value::Null) => Ok(None),
+            Some(value) => value
+                .as_u64()
+                .and_then(|index| usize::try_from(index).ok())
+                .map(Some)
+                .with_context(|| format!("SP1 trace {} has an invalid index {value}", self.table)),
+        }
+    }
+
+    fn index(&self, column: usize) -> Result<usize> {
+        self.optional_index(column)?
+            .with_context(|| format!("SP1 trace {} row is missing column {column}", self.table))
+    }
+}
+
+/// Encodes `stdin` as the bincode serialized `SP1Stdin` the SP1 executor reads, holding `stdin`
+/// as its only buffer like Ere's SP1 backend writes it.
+pub(crate) fn encode_stdin(stdin: &[u8]) -> Vec<u8> {
+    let mut encoded = Vec::with_capacity(stdin.len() + 32);
+    // `buffer: Vec<Vec<u8>>` with a single entry.
+    encoded.extend_from_slice(&1u64.to_le_bytes());
+    encoded.extend_from_slice(&(stdin.len() as u64).to_le_bytes());
+    encoded.extend_from_slice(stdin);
+    // `ptr: usize` and the empty `proofs: Vec<_>`.
+    encoded.extend_from_slice(&0u64.to_le_bytes());
+    encoded.extend_from_slice(&0u64.to_le_bytes());
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn samples_are_folded_by_stack_and_weighted_by_sample_rate() -> Result<()> {
+        let trace = r#"{
+            "meta": {"interval": 1},
+            "threads": [{
+                "name": "SP1 ZKVM",
+                "samples": {
+                    "schema": {"time": 0, "stack": 1},
+                    "data": [[0, 1], [1, 1], [2, 2], [3, 0], [4, null]]
+                },
+                "stackTable": {
+                    "schema": {"frame": 0, "prefix": 1},
+                    "data": [[0, null], [1, 0], [2, 1]]
+                },
+                "frameTable": {
+                    "schema": {"location": 0, "category": 1},
+                    "data": [[2, 0], [1, 0], [0, 0]]
+                },
+                "stringTable": ["keccak256", "execute block", "main"]
+            }]
+        }"#;
+
+        let folded = folded_stacks(trace.as_bytes(), 100)?;
+        assert_eq!(
+            folded.to_string(),
+            "[unattributed] 100\nmain 100\nmain;execute_block 200\nmain;execute_block;keccak256 100\n"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_traces_without_tables() {
+        let err = folded_stacks(br#"{"threads": [{"stringTable": []}]}"#, 1).unwrap_err();
+        assert_eq!(err.to_string(), "SP1 trace thread has no frameTable");
+        assert!(folded_stacks(b"not json", 1).is_err());
+    }
+
+    #[test]
+    fn stdin_is_encoded_as_a_single_buffer() {
+        assert_eq!(
+            encode_stdin(&[0xaa]),
+            [
+                [1, 0, 0, 0, 0, 0, 0, 0].as_slice(),
+                &[1, 0, 0, 0, 0, 0, 0, 0],
+                &[0xaa],
+                &[0; 16],
+            ]
+            .concat()
+        );
+    }
+}
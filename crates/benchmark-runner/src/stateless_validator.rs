@@ -5,6 +5,7 @@ mod fixtures;
 mod inputs;
 
 use crate::guest_programs::GuestFixture;
+use crate::manifest::RunManifestStore;
 use anyhow::Result;
 use stateless_validator_catalog::StatelessValidatorKind;
 use std::path::Path;
@@ -39,16 +40,14 @@ impl ExecutionClient {
 }
 
 /// Lazily prepares stateless validator inputs from a fixture folder.
+///
+/// When a run manifest is given, fixtures with an up to date recorded result are yielded without
+/// decoding their input. The runner still decides which fixtures to skip and records their skip.
 pub fn stateless_validator_input_iter(
     input_folder: &Path,
     selected_fixtures: Option<&[String]>,
     el: ExecutionClient,
-    existing_output_dir: Option<&Path>,
+    manifest: Option<RunManifestStore>,
 ) -> Result<impl Iterator<Item = Result<Box<dyn GuestFixture>>>> {
-    fixtures::stateless_validator_input_iter(
-        input_folder,
-        selected_fixtures,
-        el,
-        existing_output_dir,
-    )
+    fixtures::stateless_validator_input_iter(input_folder, selected_fixtures, el, manifest)
 }
@@ -1,8 +1,9 @@
 use crate::{
     guest_programs::GuestFixture,
+    manifest::RunManifestStore,
     stateless_validator::{
         eest::{load_eest_benchmark_fixtures, EestStatelessFixture},
-        inputs::stateless_validator_input_from_fixture,
+        inputs::{raw_eest_input_from_fixture, stateless_validator_input_from_fixture},
         ExecutionClient,
     },
 };
@@ -11,7 +12,6 @@ use std::{
     collections::HashSet,
     path::{Path, PathBuf},
 };
-use tracing::info;
 use walkdir::WalkDir;
 
 const EEST_BLOCKCHAIN_TESTS_DIR: &str = "blockchain_tests";
@@ -93,7 +93,7 @@ pub(super) fn stateless_validator_input_iter(
     input_folder: &Path,
     selected_fixtures: Option<&[String]>,
     el: ExecutionClient,
-    existing_output_dir: Option<&Path>,
+    manifest: Option<RunManifestStore>,
 ) -> Result<impl Iterator<Item = Result<Box<dyn GuestFixture>>>> {
     let fixture_prefixes = selected_fixtures
         .filter(|fixtures| !fixtures.is_empty())
@@ -105,7 +105,7 @@ pub(super) fn stateless_validator_input_iter(
         input_folder.to_path_buf(),
         fixture_prefixes,
         el,
-        existing_output_dir.map(Path::to_path_buf),
+        manifest,
     ))
 }
 
@@ -114,7 +114,7 @@ fn stateless_validator_input_iter_from_paths<I>(
     input_root: PathBuf,
     fixture_prefixes: Option<Vec<String>>,
     el: ExecutionClient,
-    existing_output_dir: Option<PathBuf>,
+    manifest: Option<RunManifestStore>,
 ) -> impl Iterator<Item = Result<Box<dyn GuestFixture>>>
 where
     I: Iterator<Item = PathBuf>,
@@ -124,14 +124,19 @@ where
             Ok(fixtures) => fixtures
                 .into_iter()
                 .filter(|fixture| fixture_matches_prefixes(fixture, fixture_prefixes.as_deref()))
-                .filter_map(|fixture| {
-                    match skip_existing_fixture_output(
-                        &fixture.name,
-                        existing_output_dir.as_deref(),
-                    ) {
-                        Ok(true) => None,
-                        Ok(false) => Some(stateless_validator_input_from_fixture(fixture, el)),
-                        Err(err) => Some(Err(err)),
+                .map(|fixture| {
+                    // Up to date fixtures are still yielded so the runner records their skip,
+                    // but their input is not decoded. The guest stdin is the raw stateless
+                    // input, so its hash matches the one the runner checks.
+                    let up_to_date = manifest.as_ref().is_some_and(|manifest| {
+                        manifest
+                            .rerun_reason(&fixture.name, &fixture.stateless_input_bytes)
+                            .is_none()
+                    });
+                    if up_to_date {
+                        raw_eest_input_from_fixture(fixture)
+                    } else {
+                        stateless_validator_input_from_fixture(fixture, el)
                     }
                 })
                 .collect(),
@@ -165,23 +170,6 @@ fn load_benchmark_fixtures(path: &Path, input_root: &Path) -> Result<Vec<EestSta
     load_eest_benchmark_fixtures(value, path, input_root)
 }
 
-fn skip_existing_fixture_output(
-    fixture_name: &str,
-    existing_output_dir: Option<&Path>,
-) -> Result<bool> {
-    let Some(existing_output_dir) = existing_output_dir else {
-        return Ok(false);
-    };
-
-    let output_path = existing_output_dir.join(format!("{fixture_name}.json"));
-    if output_path.exists() {
-        info!("Skipping {fixture_name} (already exists)");
-        return Ok(true);
-    }
-
-    Ok(false)
-}
-
 fn normalize_fixture_prefixes(prefixes: &[String]) -> Result<Vec<String>> {
     let mut normalized_prefixes = Vec::with_capacity(prefixes.len());
     let mut seen_prefixes = HashSet::new();
@@ -211,6 +199,7 @@ fn normalize_fixture_prefix(prefix: &str) -> Result<String> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::manifest::{FixtureOutcome, RerunPolicy, RunFingerprint, RunManifest};
     use std::fs;
 
     #[test]
@@ -292,7 +281,7 @@ mod tests {
     }
 
     #[test]
-    fn eest_fixture_iter_skips_existing_outputs() -> Result<()> {
+    fn eest_fixture_iter_yields_fixtures_recorded_in_manifest() -> Result<()> {
         let dir = tempfile::tempdir()?;
         let fixture_path = dir.path().join("mcopy.json");
         fs::write(&fixture_path, sample_eest_fixture())?;
@@ -300,17 +289,38 @@ mod tests {
         let loaded = load_benchmark_fixtures(&fixture_path, dir.path())?;
         let output_dir = dir.path().join("output");
         fs::create_dir(&output_dir)?;
-        fs::write(output_dir.join(format!("{}.json", loaded[0].name)), "{}")?;
+        for fixture in &loaded {
+            fs::write(output_dir.join(format!("{}.json", fixture.name)), "{}")?;
+        }
+
+        let manifest = RunManifestStore::new(
+            RunManifest::default(),
+            output_dir,
+            RunFingerprint {
+                elf_sha256: "aa".to_string(),
+                program_vk: "bb".to_string(),
+                sdk_version: "1.0.0".to_string(),
+                action: "execute".to_string(),
+                timeout: None,
+            },
+            RerunPolicy::default(),
+        );
+        manifest.record(
+            &loaded[0].name,
+            &loaded[0].stateless_input_bytes,
+            FixtureOutcome::Success,
+        )?;
 
+        // Up to date fixtures are left to the runner, which records their skip.
         let fixtures = stateless_validator_input_iter(
             &fixture_path,
             None,
             ExecutionClient::Reth,
-            Some(&output_dir),
+            Some(manifest),
         )?
         .collect::<Result<Vec<_>>>()?;
-        assert_eq!(fixtures.len(), 1);
-        assert_eq!(fixtures[0].name(), loaded[1].name);
+        let names: Vec<_> = fixtures.iter().map(|fixture| fixture.name()).collect();
+        assert_eq!(names, [loaded[0].name.clone(), loaded[1].name.clone()]);
 
         Ok(())
     }
@@ -56,7 +56,9 @@ fn validate_zesu_fork(fork: ProtocolFork, fixture_name: &str) -> Result<()> {
     Ok(())
 }
 
-fn raw_eest_input_from_fixture(fixture: EestStatelessFixture) -> Result<Box<dyn GuestFixture>> {
+pub(crate) fn raw_eest_input_from_fixture(
+    fixture: EestStatelessFixture,
+) -> Result<Box<dyn GuestFixture>> {
     let metadata = EestBlockMetadata {
         fixture_format: "eest",
         original_test_name: fixture.original_test_name,
@@ -3,12 +3,21 @@
 use anyhow::{anyhow, Context, Result};
 use ere_dockerized::EncodedProof;
 use std::fs;
-use std::panic;
 use std::path::{Path, PathBuf};
+use std::time::Instant;
 use tracing::info;
-use zkevm_metrics::{BenchmarkRun, CrashInfo, HardwareInfo, VerificationMetrics};
-
-use crate::runner::{get_panic_msg, RunConfig, ZkVMInstance};
+use zkevm_metrics::{
+    results, BenchmarkRun, CrashInfo, DurationStats, HardwareInfo, VerificationMetrics,
+    VerificationStatistics,
+};
+
+use crate::journal::{EventJournal, RunEvent};
+use crate::manifest::{FixtureOutcome, RunManifestStore};
+use crate::progress::RunLabels;
+use crate::runner::{
+    benchmark_output_dir, benchmark_output_path, benchmark_zkvm_name, catch_crash, Repetitions,
+    RunConfig, ZkVMInstance, RUN_INFO_FILE_NAME,
+};
 
 /// Loads proof artifacts from disk and verifies them using the given zkVM.
 pub fn run_verify_from_disk(
@@ -16,9 +25,9 @@ pub fn run_verify_from_disk(
     config: &RunConfig,
     proofs_folder: &Path,
 ) -> Result<()> {
-    HardwareInfo::detect().to_path(config.output_folder.join("hardware.json"))?;
+    HardwareInfo::detect().to_path(results::hardware_path(&config.output_folder))?;
 
-    let zkvm_name = format!("{}-{}", zkvm.name(), zkvm.sdk_version());
+    let zkvm_name = benchmark_zkvm_name(zkvm);
     let proof_dir = proofs_folder
         .join(config.sub_folder.as_deref().unwrap_or(""))
         .join(&zkvm_name);
@@ -28,6 +37,13 @@ pub fn run_verify_from_disk(
         return Ok(());
     }
 
+    zkvm.provenance()
+        .to_path(benchmark_output_dir(zkvm, config).join(RUN_INFO_FILE_NAME))?;
+    let manifest = RunManifestStore::open(zkvm, config)?;
+    let journal = EventJournal::open(zkvm, config)?;
+    let progress = config.progress.as_deref();
+    let labels = RunLabels::new(zkvm, config);
+
     let proof_entries: Vec<_> = walkdir::WalkDir::new(&proof_dir)
         .min_depth(1)
         .max_depth(1)
@@ -39,19 +55,6 @@ pub fn run_verify_from_disk(
         })
         .collect();
 
-    // Warmup pass: verify the first proof to warm up the zkVM setup (if any).
-    if let Some(first) = proof_entries.first() {
-        info!(
-            "Warmup: verifying {} (result will be discarded)",
-            first.path().display()
-        );
-        let proof_bytes = fs::read(first.path())
-            .with_context(|| format!("Failed to read proof from {}", first.path().display()))?;
-        let proof = EncodedProof(proof_bytes);
-        let _ = panic::catch_unwind(panic::AssertUnwindSafe(|| zkvm.verify(&proof)));
-        info!("Warmup complete");
-    }
-
     for entry in &proof_entries {
         let fixture_name = entry
             .path()
@@ -60,58 +63,95 @@ pub fn run_verify_from_disk(
             .to_string_lossy()
             .to_string();
 
-        let out_path = config
-            .output_folder
-            .join(config.sub_folder.as_deref().unwrap_or(""))
-            .join(format!("{zkvm_name}/{fixture_name}.json"));
+        let out_path = benchmark_output_path(zkvm, config, &fixture_name);
+        let started = Instant::now();
 
-        if !config.force_rerun && out_path.exists() {
-            info!("Skipping {fixture_name} (already exists)");
+        // The proof is the input of a verification run.
+        let proof_bytes = fs::read(entry.path())
+            .with_context(|| format!("Failed to read proof from {}", entry.path().display()))?;
+        if !manifest.should_run(&fixture_name, &proof_bytes) {
+            if let Some(progress) = progress {
+                progress.skipped(&labels);
+            }
+            journal.record(
+                &fixture_name,
+                RunEvent::Skipped {
+                    reason: "up to date in run manifest".to_string(),
+                },
+            );
             continue;
         }
+        journal.record_at(&fixture_name, RunEvent::FixtureStarted, started);
 
         info!("Verifying proof for {fixture_name}");
-
-        let proof_bytes = fs::read(entry.path())
-            .with_context(|| format!("Failed to read proof from {}", entry.path().display()))?;
+        let running = progress.map(|progress| progress.start(&labels, &fixture_name));
         let proof = EncodedProof(proof_bytes);
 
-        let verify_start = std::time::Instant::now();
-        let verification_result =
-            panic::catch_unwind(panic::AssertUnwindSafe(|| zkvm.verify(&proof)));
-
-        let verification = match verification_result {
-            Ok(Ok(_public_values)) => {
-                let verification_time_ms = verify_start.elapsed().as_millis();
-                VerificationMetrics::Success {
-                    proof_size: proof.len(),
-                    verification_time_ms,
-                }
-            }
-            Ok(Err(e)) => VerificationMetrics::Crashed(CrashInfo {
-                reason: e.to_string(),
-            }),
-            Err(panic_info) => VerificationMetrics::Crashed(CrashInfo {
-                reason: get_panic_msg(panic_info),
-            }),
-        };
+        journal.record(&fixture_name, RunEvent::VerifyStarted);
+        let verification = verify_proof(zkvm, &proof, &fixture_name, config.repetitions)?;
+        let outcome = FixtureOutcome::of_verification(&verification);
+        journal.record(&fixture_name, RunEvent::VerifyFinished { outcome });
+        if let VerificationMetrics::Crashed(crash) = &verification {
+            journal.record(&fixture_name, RunEvent::crashed(crash));
+        }
 
         let report = BenchmarkRun {
-            name: fixture_name,
+            name: fixture_name.clone(),
             timestamp_completed: zkevm_metrics::chrono::Utc::now(),
             metadata: serde_json::Value::Null,
             execution: None,
             proving: None,
             verification: Some(verification),
+            resources: None,
+            provenance: Some(zkvm.provenance()),
+            profile: None,
+            per_gas: None,
         };
 
         info!("Saving verification report");
         report.to_path(out_path)?;
+        manifest.record(&fixture_name, &proof.0, outcome)?;
+        journal.record(&fixture_name, RunEvent::FixtureFinished { outcome });
+        if let Some(running) = running {
+            running.finish(None, None, report.verification.as_ref());
+        }
     }
 
     Ok(())
 }
 
+/// Verifies a proof for the configured repetitions.
+fn verify_proof(
+    zkvm: &ZkVMInstance,
+    proof: &EncodedProof,
+    fixture_name: &str,
+    repetitions: Repetitions,
+) -> Result<VerificationMetrics> {
+    repetitions.warm_up(fixture_name, || zkvm.verify(proof));
+
+    let mut verification_times = Vec::new();
+    for _ in 0..repetitions.measured {
+        let verify_start = std::time::Instant::now();
+        if let Err(reason) = catch_crash(|| zkvm.verify(proof)) {
+            return Ok(VerificationMetrics::Crashed(CrashInfo { reason }));
+        }
+        verification_times.push(verify_start.elapsed());
+    }
+
+    let verification_time = DurationStats::from_samples(verification_times)
+        .context("at least one measured iteration is required")?;
+    Ok(VerificationMetrics::Success {
+        proof_size: proof.len(),
+        verification_time_ms: verification_time.median.as_millis(),
+        statistics: repetitions.records_statistics().then(|| {
+            Box::new(VerificationStatistics {
+                warmup_iterations: repetitions.warmup,
+                verification_time,
+            })
+        }),
+    })
+}
+
 /// Downloads a `.tar.gz` archive from a URL and extracts it to a temporary directory.
 pub async fn download_and_extract_proofs(url: &str) -> Result<tempfile::TempDir> {
     info!("Downloading proofs archive from {url}");
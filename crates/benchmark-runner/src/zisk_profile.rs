@@ -0,0 +1,689 @@
+//! Parser and aggregate reports for `ziskemu` profiles
+//!
+//! A profile is the stdout of `ziskemu -X -S -D`, saved by Zisk profiling as
+//! `zisk_profile_<fixture>.prof`. Only the sections used by the reports are parsed: the
+//! `COST DISTRIBUTION` totals, the `COST BY OPCODE` table, and the `PROFILE TAGS COST` and
+//! `PROFILE TAGS STEPS` tables of the scopes marked in the guest.
+
+use anyhow::{bail, Context, Result};
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+use tracing::warn;
+use zkevm_metrics::{
+    markdown::{escape_markdown, format_number},
+    stats::percentile,
+    ExecutionProfile, ScopeCost,
+};
+
+use crate::profiling::FoldedStacks;
+
+/// Cost `ziskemu` charges to the MAIN category per executed step.
+///
+/// Used to split the cost of a scope into MAIN and the remaining OPCODES, PRECOMPILES and MEMORY
+/// cost, since the profile only reports total cost and steps per scope.
+/// See <https://github.com/0xPolygonHermez/zisk/blob/v0.18.0/emulator/src/emu_costs.rs#L18>.
+pub const MAIN_COST_PER_STEP: u64 = 68;
+
+/// File name prefix of the profiles written by Zisk profiling.
+const PROFILE_FILE_PREFIX: &str = "zisk_profile_";
+
+/// Number of opcodes listed in the Markdown report.
+const TOP_OPCODES: usize = 10;
+
+/// A parsed `ziskemu` profile.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ZiskProfile {
+    /// Total cost of the execution.
+    pub total_cost: u64,
+    /// Cost per category, e.g. `MAIN`, `OPCODES`, `PRECOMPILES` and `MEMORY`.
+    pub cost_distribution: BTreeMap<String, u64>,
+    /// Count and cost of each executed opcode.
+    pub opcodes: BTreeMap<String, OpcodeCost>,
+    /// Cost of each profiling scope, keyed by lowercase scope name.
+    pub scopes: BTreeMap<String, ScopeCost>,
+}
+
+/// Count and cost of one opcode.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct OpcodeCost {
+    /// Number of times the opcode was executed.
+    pub count: u64,
+    /// Total cost of its executions.
+    pub cost: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Section {
+    None,
+    CostDistribution,
+    /// After the cost categories, until the `TOTAL` line.
+    CostTotal,
+    Opcodes,
+    TagCosts,
+    TagSteps,
+}
+
+impl ZiskProfile {
+    /// Parses the text of a `ziskemu` profile.
+    ///
+    /// Rows that do not have the expected shape are skipped. Fails when the text has no
+    /// `COST DISTRIBUTION` section, i.e. it is not a profile at all.
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut profile = Self::default();
+        let mut found_distribution = false;
+        let mut section = Section::None;
+        let mut lines = text.lines().map(str::trim);
+
+        while let Some(line) = lines.next() {
+            let next_section = if line.starts_with("COST DISTRIBUTION") {
+                Some(Section::CostDistribution)
+            } else if line.starts_with("COST BY OPCODE") {
+                Some(Section::Opcodes)
+            } else if line.starts_with("PROFILE TAGS COST") {
+                Some(Section::TagCosts)
+            } else if line.starts_with("PROFILE TAGS STEPS") {
+                Some(Section::TagSteps)
+            } else {
+                None
+            };
+            if let Some(next_section) = next_section {
+                found_distribution |= next_section == Section::CostDistribution;
+                section = next_section;
+                // Every table starts with a column header line.
+                lines.next();
+                continue;
+            }
+
+            let columns: Vec<&str> = line.split_whitespace().collect();
+            section = match section {
+                Section::None => Section::None,
+                Section::CostDistribution | Section::CostTotal if line.starts_with("FROPS") => {
+                    Section::None
+                }
+                Section::CostDistribution | Section::CostTotal if line.starts_with("TOTAL") => {
+                    if let Some(total) = columns.get(1).and_then(|c| parse_number(c)) {
+                        profile.total_cost = total;
+                    }
+                    Section::None
+                }
+                Section::CostDistribution if line.is_empty() => Section::CostTotal,
+                Section::CostDistribution => {
+                    // `VARIABLE` is the subtotal of MAIN, OPCODES, PRECOMPILES and MEMORY.
+                    if let [category, cost, percent] = columns[..] {
+                        if category != "VARIABLE" && percent.ends_with('%') {
+                            if let Some(cost) = parse_number(cost) {
+                                profile.cost_distribution.insert(category.to_string(), cost);
+                            }
+                        }
+                    }
+                    Section::CostDistribution
+                }
+                Section::CostTotal => Section::CostTotal,
+                Section::Opcodes if line.is_empty() || line.starts_with("FROPS") => Section::None,
+                Section::Opcodes => {
+                    // OP <name> <count> <count %> <cost> <cost %> ...
+                    if let ["OP", name, count, _, cost, ..] = columns[..] {
+                        if let (Some(count), Some(cost)) = (parse_number(count), parse_number(cost))
+                        {
+                            profile
+                                .opcodes
+                                .insert(name.to_string(), OpcodeCost { count, cost });
+                        }
+                    }
+                    Section::Opcodes
+                }
+                Section::TagCosts | Section::TagSteps if line.is_empty() => Section::None,
+                Section::TagCosts | Section::TagSteps => {
+                    // <total> <total %> <calls> <avg> <min> <max> <name>
+                    if let [total, _, calls, _, _, _, name] = columns[..] {
+                        if let (Some(total), Some(calls)) =
+                            (parse_number(total), parse_number(calls))
+                        {
+                            let scope = profile.scopes.entry(name.to_lowercase()).or_default();
+                            scope.calls = calls;
+                            if section == Section::TagCosts {
+                                scope.cost = total;
+                            } else {
+                                scope.steps = total;
+                            }
+                        }
+                    }
+                    section
+                }
+            };
+        }
+
+        if !found_distribution {
+            bail!("profile has no COST DISTRIBUTION section");
+        }
+        Ok(profile)
+    }
+
+    /// Reads and parses the profile at `path`.
+    pub fn from_path(path: &Path) -> Result<Self> {
+        let text = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read profile {}", path.display()))?;
+        Self::parse(&text).with_context(|| format!("Failed to parse profile {}", path.display()))
+    }
+
+    /// Returns the steps of each scope as flat folded stacks.
+    pub fn folded_stacks(&self) -> FoldedStacks {
+        let mut folded = FoldedStacks::new();
+        for (name, scope) in &self.scopes {
+            folded.add(&[name], scope.steps);
+        }
+        folded
+    }
+
+    /// Returns the per-category and per-scope costs recorded in a `BenchmarkRun`.
+    pub fn to_execution_profile(&self) -> ExecutionProfile {
+        ExecutionProfile {
+            total_cost: self.total_cost,
+            cost_distribution: self.cost_distribution.clone(),
+            scopes: self.scopes.clone(),
+        }
+    }
+}
+
+/// Parses a number with `,` thousands separators.
+fn parse_number(value: &str) -> Option<u64> {
+    value.replace(',', "").parse().ok()
+}
+
+/// A profile together with the fixture it was recorded for.
+#[derive(Debug, Clone)]
+pub struct FixtureProfile {
+    /// Fixture name taken from the profile file name.
+    pub fixture: String,
+    /// The parsed profile.
+    pub profile: ZiskProfile,
+}
+
+/// Loads every `.prof` file directly inside `dir`, sorted by file name.
+///
+/// Profiles that fail to parse are skipped with a warning. Fails when `dir` has no profiles or
+/// none of them parse.
+pub fn load_profiles(dir: &Path) -> Result<Vec<FixtureProfile>> {
+    let mut paths = Vec::new();
+    for entry in fs::read_dir(dir)
+        .with_context(|| format!("Failed to read profile directory {}", dir.display()))?
+    {
+        let path = entry?.path();
+        if path.is_file() && path.extension().is_some_and(|ext| ext == "prof") {
+            paths.push(path);
+        }
+    }
+    if paths.is_empty() {
+        bail!("No .prof files found in {}", dir.display());
+    }
+    paths.sort();
+
+    let mut profiles = Vec::with_capacity(paths.len());
+    for path in &paths {
+        match ZiskProfile::from_path(path) {
+            Ok(profile) => profiles.push(FixtureProfile {
+                fixture: fixture_name(path),
+                profile,
+            }),
+            Err(err) => warn!("Skipping profile: {err:#}"),
+        }
+    }
+    if profiles.is_empty() {
+        bail!(
+            "None of the {} profiles in {} could be parsed",
+            paths.len(),
+            dir.display()
+        );
+    }
+    Ok(profiles)
+}
+
+fn fixture_name(path: &Path) -> String {
+    let stem = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    stem.strip_prefix(PROFILE_FILE_PREFIX)
+        .map(str::to_string)
+        .unwrap_or(stem)
+}
+
+/// Statistics of a cost over a set of profiles.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct CostStats {
+    /// Sum over all profiles.
+    pub sum: f64,
+    /// Mean.
+    pub mean: f64,
+    /// Median.
+    pub median: f64,
+    /// 5th percentile.
+    pub p5: f64,
+    /// 95th percentile.
+    pub p95: f64,
+    /// Minimum.
+    pub min: f64,
+    /// Maximum.
+    pub max: f64,
+    /// Sample standard deviation.
+    pub std_dev: f64,
+}
+
+impl CostStats {
+    /// Computes the statistics of `values`, with percentiles interpolated between samples.
+    pub fn from_values(values: &[u64]) -> Self {
+        if values.is_empty() {
+            return Self::default();
+        }
+        let mut sorted: Vec<f64> = values.iter().map(|&v| v as f64).collect();
+        sorted.sort_by(f64::total_cmp);
+
+        let n = sorted.len() as f64;
+        let sum: f64 = sorted.iter().sum();
+        let mean = sum / n;
+        let std_dev = if sorted.len() > 1 {
+            (sorted.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (n - 1.0)).sqrt()
+        } else {
+            0.0
+        };
+        Self {
+            sum,
+            mean,
+            median: percentile(&sorted, 0.5),
+            p5: percentile(&sorted, 0.05),
+            p95: percentile(&sorted, 0.95),
+            min: sorted[0],
+            max: sorted[sorted.len() - 1],
+            std_dev,
+        }
+    }
+}
+
+/// Aggregated cost of one profiling scope.
+#[derive(Debug, Clone, Serialize)]
+pub struct ScopeSummary {
+    /// Scope name.
+    pub name: String,
+    /// Total cost of the scope.
+    pub cost: CostStats,
+    /// Steps executed inside the scope.
+    pub steps: CostStats,
+    /// MAIN cost of the scope, derived from its steps.
+    pub main_cost: CostStats,
+    /// OPCODES, PRECOMPILES and MEMORY cost of the scope.
+    pub rest_cost: CostStats,
+    /// Average share of the total cost in percent.
+    pub average_percent: f64,
+}
+
+/// Aggregated cost of one cost category.
+#[derive(Debug, Clone, Serialize)]
+pub struct CategorySummary {
+    /// Category name.
+    pub name: String,
+    /// Cost of the category.
+    pub cost: CostStats,
+    /// Average share of the total cost in percent.
+    pub average_percent: f64,
+}
+
+/// Aggregated cost of one opcode.
+#[derive(Debug, Clone, Serialize)]
+pub struct OpcodeSummary {
+    /// Opcode name.
+    pub name: String,
+    /// Cost of the opcode.
+    pub cost: CostStats,
+    /// Average number of executions.
+    pub average_count: f64,
+    /// Average share of the total cost in percent.
+    pub average_percent: f64,
+}
+
+/// Report formats of a [`ProfileSummary`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProfileReportFormat {
+    /// Markdown summary and tables.
+    Markdown,
+    /// Pretty-printed JSON.
+    Json,
+}
+
+/// Costs aggregated over a directory of profiles.
+///
+/// Scopes, categories and opcodes missing from a profile count as zero cost for it. All lists
+/// are sorted by mean cost, highest first.
+#[derive(Debug, Clone, Serialize)]
+pub struct ProfileSummary {
+    /// Directory the profiles were loaded from.
+    pub directory: String,
+    /// Fixtures of the aggregated profiles.
+    pub fixtures: Vec<String>,
+    /// Total cost per profile.
+    pub total_cost: CostStats,
+    /// Profiling scopes.
+    pub scopes: Vec<ScopeSummary>,
+    /// Cost categories.
+    pub cost_distribution: Vec<CategorySummary>,
+    /// Opcodes.
+    pub opcodes: Vec<OpcodeSummary>,
+}
+
+impl ProfileSummary {
+    /// Aggregates `profiles`, leaving out the scopes named in `ignored_scopes`.
+    pub fn new(directory: &Path, profiles: &[FixtureProfile], ignored_scopes: &[String]) -> Self {
+        let ignored: BTreeSet<String> = ignored_scopes.iter().map(|s| s.to_lowercase()).collect();
+        let totals: Vec<u64> = profiles.iter().map(|p| p.profile.total_cost).collect();
+        let average_percent = |costs: &[u64]| {
+            let percents = costs.iter().zip(&totals).map(|(&cost, &total)| {
+                if total == 0 {
+                    0.0
+                } else {
+                    cost as f64 / total as f64 * 100.0
+                }
+            });
+            percents.sum::<f64>() / profiles.len().max(1) as f64
+        };
+
+        let scope_names: BTreeSet<&String> = profiles
+            .iter()
+            .flat_map(|p| p.profile.scopes.keys())
+            .filter(|name| !ignored.contains(*name))
+            .collect();
+        let mut scopes: Vec<ScopeSummary> = scope_names
+            .into_iter()
+            .map(|name| {
+                let scope =
+                    |p: &FixtureProfile| p.profile.scopes.get(name).copied().unwrap_or_default();
+                let costs: Vec<u64> = profiles.iter().map(|p| scope(p).cost).collect();
+                let steps: Vec<u64> = profiles.iter().map(|p| scope(p).steps).collect();
+                let main_costs: Vec<u64> = steps.iter().map(|s| s * MAIN_COST_PER_STEP).collect();
+                let rest_costs: Vec<u64> = costs
+                    .iter()
+                    .zip(&main_costs)
+                    .map(|(cost, main)| cost.saturating_sub(*main))
+                    .collect();
+                ScopeSummary {
+                    name: name.clone(),
+                    cost: CostStats::from_values(&costs),
+                    steps: CostStats::from_values(&steps),
+                    main_cost: CostStats::from_values(&main_costs),
+                    rest_cost: CostStats::from_values(&rest_costs),
+                    average_percent: average_percent(&costs),
+                }
+            })
+            .collect();
+        scopes.sort_by(|a, b| b.cost.mean.total_cmp(&a.cost.mean));
+
+        let category_names: BTreeSet<&String> = profiles
+            .iter()
+            .flat_map(|p| p.profile.cost_distribution.keys())
+            .collect();
+        let mut cost_distribution: Vec<CategorySummary> = category_names
+            .into_iter()
+            .map(|name| {
+                let costs: Vec<u64> = profiles
+                    .iter()
+                    .map(|p| p.profile.cost_distribution.get(name).copied().unwrap_or(0))
+                    .collect();
+                CategorySummary {
+                    name: name.clone(),
+                    cost: CostStats::from_values(&costs),
+                    average_percent: average_percent(&costs),
+                }
+            })
+            .collect();
+        cost_distribution.sort_by(|a, b| b.cost.mean.total_cmp(&a.cost.mean));
+
+        let opcode_names: BTreeSet<&String> = profiles
+            .iter()
+            .flat_map(|p| p.profile.opcodes.keys())
+            .collect();
+        let mut opcodes: Vec<OpcodeSummary> = opcode_names
+            .into_iter()
+            .map(|name| {
+                let opcode =
+                    |p: &FixtureProfile| p.profile.opcodes.get(name).copied().unwrap_or_default();
+                let costs: Vec<u64> = profiles.iter().map(|p| opcode(p).cost).collect();
+                let counts: Vec<u64> = profiles.iter().map(|p| opcode(p).count).collect();
+                OpcodeSummary {
+                    name: name.clone(),
+                    cost: CostStats::from_values(&costs),
+                    average_count: CostStats::from_values(&counts).mean,
+                    average_percent: average_percent(&costs),
+                }
+            })
+            .collect();
+        opcodes.sort_by(|a, b| b.cost.mean.total_cmp(&a.cost.mean));
+
+        Self {
+            directory: directory.display().to_string(),
+            fixtures: profiles.iter().map(|p| p.fixture.clone()).collect(),
+            total_cost: CostStats::from_values(&totals),
+            scopes,
+            cost_distribution,
+            opcodes,
+        }
+    }
+
+    /// Renders the summary in the requested format.
+    pub fn render(&self, format: ProfileReportFormat) -> Result<String> {
+        Ok(match format {
+            ProfileReportFormat::Markdown => self.to_markdown(),
+            ProfileReportFormat::Json => {
+                serde_json::to_string_pretty(self).context("Failed to serialize profile summary")?
+            }
+        })
+    }
+
+    /// Renders a Markdown summary followed by scope, category and top opcode tables.
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::from("# Zisk Profile Summary\n\n");
+        let total = &self.total_cost;
+        let _ = writeln!(out, "- **Profiles analyzed:** {}", self.fixtures.len());
+        let _ = writeln!(out, "- **Directory:** `{}`", self.directory);
+        let _ = writeln!(out, "- **Total cost (sum):** {}", format_number(total.sum));
+        let _ = writeln!(
+            out,
+            "- **Avg cost per profile:** {}",
+            format_number(total.mean)
+        );
+        let _ = writeln!(out, "- **Median cost:** {}", format_number(total.median));
+        let _ = writeln!(
+            out,
+            "- **Cost range:** {} – {}",
+            format_number(total.min),
+            format_number(total.max)
+        );
+        let _ = writeln!(out, "- **Std dev:** {}", format_number(total.std_dev));
+
+        out.push_str("\n## Custom Scopes (PROFILE TAGS)\n\n");
+        out.push_str("| Scope | Avg Cost | Avg % | Median | Min | Max | Std Dev |\n");
+        out.push_str("|-------|----------|-------|--------|-----|-----|---------|\n");
+        for scope in &self.scopes {
+            let cost = &scope.cost;
+            let _ = writeln!(
+                out,
+                "| {} | {} | {:.1}% | {} | {} | {} | {} |",
+                escape_markdown(&scope.name),
+                format_number(cost.mean),
+                scope.average_percent,
+                format_number(cost.median),
+                format_number(cost.min),
+                format_number(cost.max),
+                format_number(cost.std_dev)
+            );
+        }
+
+        if self.scopes.iter().any(|scope| scope.steps.mean > 0.0) {
+            out.push_str("\n### Cost Breakdown by Scope\n\n");
+            out.push_str("| Scope | Main | Rest (Opcodes + Precompiles + Memory) |\n");
+            out.push_str("|-------|------|---------------------------------------|\n");
+            for scope in &self.scopes {
+                let share = |part: f64| {
+                    if scope.cost.mean > 0.0 {
+                        part / scope.cost.mean * 100.0
+                    } else {
+                        0.0
+                    }
+                };
+                let _ = writeln!(
+                    out,
+                    "| {} | {:.1}% | {:.1}% |",
+                    escape_markdown(&scope.name),
+                    share(scope.main_cost.mean),
+                    share(scope.rest_cost.mean)
+                );
+            }
+        }
+
+        out.push_str("\n## Cost Distribution\n\n");
+        out.push_str("| Category | Avg Cost | Avg % | Median | Min | Max |\n");
+        out.push_str("|----------|----------|-------|--------|-----|-----|\n");
+        for category in &self.cost_distribution {
+            let cost = &category.cost;
+            let _ = writeln!(
+                out,
+                "| {} | {} | {:.1}% | {} | {} | {} |",
+                category.name,
+                format_number(cost.mean),
+                category.average_percent,
+                format_number(cost.median),
+                format_number(cost.min),
+                format_number(cost.max)
+            );
+        }
+
+        let _ = writeln!(out, "\n## Top {TOP_OPCODES} Opcodes by Cost\n");
+        out.push_str("| Opcode | Avg Cost | Avg % | Avg Count | Median Cost |\n");
+        out.push_str("|--------|----------|-------|-----------|-------------|\n");
+        for opcode in self.opcodes.iter().take(TOP_OPCODES) {
+            let _ = writeln!(
+                out,
+                "| {} | {} | {:.1}% | {} | {} |",
+                opcode.name,
+                format_number(opcode.cost.mean),
+                opcode.average_percent,
+                format_number(opcode.average_count),
+                format_number(opcode.cost.median)
+            );
+        }
+
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROFILE: &str = "\
+COST DISTRIBUTION
+CATEGORY                           COST       %
+BASE                          1,000,000   1.00%
+MAIN                         60,000,000  60.00%
+OPCODES                      30,000,000  30.00%
+PRECOMPILES                   5,000,000   5.00%
+MEMORY                        4,000,000   4.00%
+VARIABLE                     99,000,000  99.00%
+
+TOTAL                       100,000,000 100.00%
+
+COST BY OPCODE
+OPCODE                COUNT       %            COST       %
+OP keccak                10   0.01%       4,000,000   4.00% #1
+OP add            1,000,000  99.00%       1,000,000   1.00% #2
+
+PROFILE TAGS COST
+           COST       %      CALLS             AVG             MIN             MAX NAME
+     80,000,000  80.00%          1      80,000,000      80,000,000      80,000,000 STF
+      8,000,000   8.00%          4       2,000,000       1,000,000       3,000,000 validation
+
+PROFILE TAGS STEPS
+          STEPS       %      CALLS             AVG             MIN             MAX NAME
+      1,000,000  90.00%          1       1,000,000       1,000,000       1,000,000 STF
+         50,000   4.00%          4          12,500          10,000          15,000 validation
+";
+
+    #[test]
+    fn parses_costs_opcodes_and_scopes() -> Result<()> {
+        let profile = ZiskProfile::parse(PROFILE)?;
+
+        assert_eq!(profile.total_cost, 100_000_000);
+        assert_eq!(profile.cost_distribution.len(), 5);
+        assert_eq!(profile.cost_distribution["MAIN"], 60_000_000);
+        assert!(!profile.cost_distribution.contains_key("VARIABLE"));
+        assert_eq!(
+            profile.opcodes["keccak"],
+            OpcodeCost {
+                count: 10,
+                cost: 4_000_000
+            }
+        );
+        assert_eq!(
+            profile.scopes["stf"],
+            ScopeCost {
+                cost: 80_000_000,
+                steps: 1_000_000,
+                calls: 1
+            }
+        );
+        assert_eq!(profile.scopes["validation"].calls, 4);
+
+        let execution_profile = profile.to_execution_profile();
+        assert_eq!(execution_profile.scopes, profile.scopes);
+        assert!(ZiskProfile::parse("ziskemu: error\n").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn aggregates_profile_directory() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        fs::write(dir.path().join("zisk_profile_block_1.prof"), PROFILE)?;
+        fs::write(
+            dir.path().join("zisk_profile_block_2.prof"),
+            PROFILE.replace("100,000,000 100.00%", "200,000,000 100.00%"),
+        )?;
+        fs::write(
+            dir.path().join("zisk_profile_block_3.prof"),
+            "not a profile",
+        )?;
+        fs::write(dir.path().join("zisk_profile_block_3.error.txt"), "failed")?;
+
+        let profiles = load_profiles(dir.path())?;
+        assert_eq!(profiles.len(), 2);
+        assert_eq!(profiles[0].fixture, "block_1");
+
+        let summary = ProfileSummary::new(dir.path(), &profiles, &["Validation".to_string()]);
+        assert_eq!(summary.total_cost.mean, 150_000_000.0);
+        assert_eq!(summary.scopes.len(), 1);
+        let stf = &summary.scopes[0];
+        assert_eq!(stf.main_cost.mean, (1_000_000 * MAIN_COST_PER_STEP) as f64);
+        assert_eq!(stf.rest_cost.mean, 12_000_000.0);
+        assert_eq!(stf.average_percent, 60.0);
+        assert_eq!(summary.opcodes[0].name, "keccak");
+
+        let markdown = summary.to_markdown();
+        assert!(markdown.contains("| stf | 80.0M | 60.0% |"));
+        assert!(markdown.contains("| stf | 85.0% | 15.0% |"));
+        assert!(summary
+            .render(ProfileReportFormat::Json)?
+            .contains("\"fixtures\""));
+
+        Ok(())
+    }
+
+    #[test]
+    fn percentiles_interpolate_between_samples() {
+        let stats = CostStats::from_values(&[10, 20, 30, 40]);
+        assert_eq!(stats.median, 25.0);
+        assert_eq!(stats.min, 10.0);
+        assert_eq!(stats.max, 40.0);
+        assert!((stats.p95 - 38.5).abs() < 1e-9);
+        assert_eq!(CostStats::from_values(&[]), CostStats::default());
+    }
+}
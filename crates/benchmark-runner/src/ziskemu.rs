@@ -0,0 +1,164 @@
+//! Local `ziskemu` runs inside the Zisk server Docker image
+//!
+//! Used for Zisk profiling and to execute guests locally when proving happens on a remote Zisk
+//! cluster, which has no execution endpoint.
+
+use anyhow::{bail, Context, Result};
+use ere_dockerized::{zkVMKind, ProgramExecutionReport, PublicValues};
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use std::time::{Duration, Instant};
+
+use crate::docker::server_image_command;
+
+/// Returns a `docker run` command that invokes `ziskemu` with `data_dir` mounted at `/data`.
+pub(crate) fn ziskemu_command(data_dir: &Path, args: &[&str]) -> Command {
+    let mut command = server_image_command(zkVMKind::Zisk, data_dir, "ziskemu", &[]);
+    command.args(args);
+    command
+}
+
+/// Writes `elf` and `stdin` to `data_dir` as `program.elf` and `input.bin`.
+pub(crate) fn write_emulator_inputs(data_dir: &Path, elf: &[u8], stdin: &[u8]) -> Result<()> {
+    let input_path = data_dir.join("input.bin");
+    let elf_path = data_dir.join("program.elf");
+    fs::write(&input_path, length_prefixed_and_padded(stdin))
+        .with_context(|| format!("Failed to write input.bin to {}", input_path.display()))?;
+    fs::write(&elf_path, elf)
+        .with_context(|| format!("Failed to write program.elf to {}", elf_path.display()))?;
+    Ok(())
+}
+
+/// Executes `elf` with `stdin` in `ziskemu` and reports its steps as cycles.
+///
+/// The execution duration is the emulator's own measurement when it logs one, and the wall-clock
+/// time of the Docker run otherwise.
+pub(crate) fn execute(elf: &[u8], stdin: &[u8]) -> Result<(PublicValues, ProgramExecutionReport)> {
+    let temp_dir = tempfile::tempdir().context("Failed to create temp directory for ziskemu")?;
+    write_emulator_inputs(temp_dir.path(), elf, stdin)?;
+
+    let start = Instant::now();
+    let output = ziskemu_command(
+        temp_dir.path(),
+        &[
+            "-e",
+            "/data/program.elf",
+            "-i",
+            "/data/input.bin",
+            "-o",
+            "/data/output.bin",
+            "-m",
+        ],
+    )
+    .output()
+    .context("Failed to execute docker command for ziskemu")?;
+    let wall_time = start.elapsed();
+
+    if !output.status.success() {
+        bail!(
+            "ziskemu exited with code {}\nstderr: {}",
+            output.status.code().unwrap_or(-1),
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+
+    // Depending on the log configuration metrics end up on stdout or stderr.
+    let log = format!(
+        "{}\n{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let report = parse_execution_log(&log, wall_time)?;
+    Ok((read_emulator_output(temp_dir.path())?, report))
+}
+
+/// Reads the public values `ziskemu -o` wrote to `output.bin` in `data_dir`.
+///
+/// The log is not parsed for output words, as guest or emulator log lines could pass for them.
+fn read_emulator_output(data_dir: &Path) -> Result<PublicValues> {
+    let output_path = data_dir.join("output.bin");
+    fs::read(&output_path)
+        .with_context(|| format!("Failed to read ziskemu output {}", output_path.display()))
+}
+
+/// Parses the `-m` metrics line of a `ziskemu` run.
+fn parse_execution_log(log: &str, wall_time: Duration) -> Result<ProgramExecutionReport> {
+    let Some(total_num_cycles) = metric_value(log, "steps=").and_then(|v| v.parse().ok()) else {
+        bail!("ziskemu output does not contain a step count");
+    };
+    let execution_duration = metric_value(log, "duration=")
+        .and_then(|v| v.parse::<f64>().ok())
+        .and_then(|secs| Duration::try_from_secs_f64(secs).ok())
+        .unwrap_or(wall_time);
+
+    Ok(ProgramExecutionReport {
+        total_num_cycles,
+        execution_duration,
+        ..Default::default()
+    })
+}
+
+/// Returns the value following the first `key` in `log`, up to the next whitespace.
+fn metric_value<'a>(log: &'a str, key: &str) -> Option<&'a str> {
+    let (_, rest) = log.split_once(key)?;
+    rest.split_whitespace().next()
+}
+
+/// Mirrors `ere-prover-zisk` input preparation for direct `ziskemu` execution.
+fn length_prefixed_and_padded(data: &[u8]) -> Vec<u8> {
+    let len = (8 + data.len()).next_multiple_of(8);
+    let mut buf = Vec::with_capacity(len);
+    buf.extend_from_slice(&(data.len() as u64).to_le_bytes());
+    buf.extend_from_slice(data);
+    buf.resize(len, 0);
+    buf
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_steps_and_duration() -> Result<()> {
+        let log = "\
+process_rom() steps=1048576 duration=0.5000 tp=2.0972 Msteps/s\n\
+0badf00d\n";
+        let report = parse_execution_log(log, Duration::from_secs(3))?;
+
+        assert_eq!(report.total_num_cycles, 1_048_576);
+        assert_eq!(report.execution_duration, Duration::from_millis(500));
+
+        Ok(())
+    }
+
+    #[test]
+    fn falls_back_to_wall_time_and_rejects_missing_steps() {
+        let report = parse_execution_log("steps=7\n", Duration::from_secs(3)).unwrap();
+        assert_eq!(report.execution_duration, Duration::from_secs(3));
+
+        assert!(parse_execution_log("no metrics\n", Duration::ZERO).is_err());
+    }
+
+    #[test]
+    fn public_values_are_read_from_output_file() -> Result<()> {
+        let data_dir = tempfile::tempdir()?;
+        assert!(read_emulator_output(data_dir.path()).is_err());
+
+        fs::write(data_dir.path().join("output.bin"), [0x0d, 0xf0, 0xad, 0x0b])?;
+        assert_eq!(
+            read_emulator_output(data_dir.path())?,
+            [0x0d, 0xf0, 0xad, 0x0b]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn input_is_length_prefixed_and_padded() {
+        assert_eq!(
+            length_prefixed_and_padded(&[0xaa, 0xbb]),
+            [2, 0, 0, 0, 0, 0, 0, 0, 0xaa, 0xbb, 0, 0, 0, 0, 0, 0]
+        );
+    }
+}
@@ -1,11 +1,19 @@
 //! CLI definitions for the zkVM benchmarker
 
 use anyhow::{Result as AnyhowResult, bail};
-use benchmark_runner::{runner::Action, stateless_validator};
-use clap::{Parser, Subcommand, ValueEnum};
+use benchmark_runner::{
+    manifest::RerunPolicy,
+    runner::{Action, Repetitions},
+    stateless_validator, zisk_profile,
+};
+use clap::{Args, Parser, Subcommand, ValueEnum};
 use ere_dockerized::{ProverResource, RemoteProverConfig, zkVMKind};
+use std::env;
+use std::net::SocketAddr;
+use std::num::NonZeroUsize;
 use std::path::PathBuf;
 use std::time::Duration;
+use zkevm_metrics::{compare, gas};
 
 /// Command line interface for the zkVM benchmarker
 #[derive(Parser)]
@@ -22,21 +30,38 @@ pub struct Cli {
     #[arg(long, required_if_eq("resource", "cluster"))]
     pub cluster_endpoint: Option<String>,
 
+    /// Endpoint URL of the hosted proving network (required when --resource network)
+    #[arg(long, required_if_eq("resource", "network"))]
+    pub network_endpoint: Option<String>,
+
+    /// API key sent to the proving cluster or network. Defaults to the
+    /// `ZKVM_PROVER_API_KEY` environment variable
+    #[arg(long)]
+    pub api_key: Option<String>,
+
     /// Action to perform
     #[arg(short, long, value_enum, default_value = "execute")]
     pub action: BenchmarkAction,
 
-    /// zkVM instances to benchmark
-    #[arg(long, required(true), value_parser = <zkVMKind as std::str::FromStr>::from_str)]
+    /// zkVM instances to benchmark (required when benchmarking a guest program)
+    #[arg(long, value_parser = <zkVMKind as std::str::FromStr>::from_str)]
     pub zkvms: Vec<zkVMKind>,
 
-    /// Rerun the benchmarks even if the output folder already contains results
+    /// Rerun the benchmarks even if the run manifest has up to date results
     #[arg(long, default_value_t = false)]
     pub force_rerun: bool,
 
-    /// Guest program to benchmark
+    /// Rerun fixtures whose recorded run crashed
+    #[arg(long)]
+    pub rerun_crashed: bool,
+
+    /// Rerun fixtures whose recorded public output did not match the fixture
+    #[arg(long)]
+    pub rerun_mismatched: bool,
+
+    /// Guest program to benchmark, or a metrics tool to run
     #[command(subcommand)]
-    pub guest_program: GuestProgramCommand,
+    pub command: Command,
 
     /// Output folder for benchmark results
     #[arg(short, long, default_value = "zkevm-metrics")]
@@ -71,17 +96,96 @@ pub struct Cli {
     #[arg(long, conflicts_with = "bin_path")]
     pub guest_artifact_base_url: Option<String>,
 
+    /// Use only guest programs from the guest cache, without downloading
+    #[arg(long)]
+    pub offline: bool,
+
+    /// Guest cache directory (default: `$XDG_CACHE_HOME/zkevm-benchmark-workload/guests`)
+    #[arg(long, value_name = "DIR")]
+    pub guest_cache_dir: Option<PathBuf>,
+
+    /// Fail when a downloaded or `--bin-path` guest artifact has no published checksum
+    #[arg(long)]
+    pub require_checksums: bool,
+
     /// Timeout for the selected action only, for example `15m`, `5m`, or `2s`.
     #[arg(long, value_name = "DURATION", value_parser = parse_duration)]
     pub timeout: Option<Duration>,
 
-    /// Enable Zisk profiling (requires --zkvms zisk, --action execute)
+    /// Discarded warmup iterations per fixture (default: 1 for --action verify, 0 otherwise)
+    #[arg(long, value_name = "N")]
+    pub warmup_iterations: Option<u32>,
+
+    /// Measured iterations per fixture; repeated runs report the median and record statistics
+    #[arg(long, value_name = "M", default_value_t = 1, value_parser = clap::value_parser!(u32).range(1..))]
+    pub iterations: u32,
+
+    /// Number of fixtures proven concurrently per zkVM (only valid with --action prove)
+    #[arg(long, value_name = "N", default_value = "1")]
+    pub prove_concurrency: NonZeroUsize,
+
+    /// Sample peak memory and CPU usage of the measured iterations. Fixtures then run one at a
+    /// time, so it cannot be combined with --prove-concurrency or --parallel-zkvms
+    #[arg(long, conflicts_with = "parallel_zkvms")]
+    pub resource_usage: bool,
+
+    /// Benchmark all selected zkVMs at the same time instead of one after another
     #[arg(long)]
-    pub zisk_profile: bool,
+    pub parallel_zkvms: bool,
+
+    /// Profile guest cycles of every fixture (requires --action execute)
+    #[arg(long, alias = "zisk-profile")]
+    pub profile: bool,
+
+    /// Profile zkVMs without a native profiler from the cycle regions their guests report, with
+    /// `/` in a region name nesting it inside another region
+    #[arg(long, requires = "profile")]
+    pub profile_regions: bool,
+
+    /// Output folder for profile results
+    #[arg(long, alias = "zisk-profile-output", default_value = "zkvm-profiles")]
+    pub profile_output: PathBuf,
+
+    /// Serve Prometheus metrics of benchmark progress at this address, for example `0.0.0.0:9464`
+    #[arg(long, value_name = "ADDR")]
+    pub metrics_listen: Option<SocketAddr>,
+
+    /// Rewrite Prometheus metrics of benchmark progress to this file after every fixture, for the
+    /// node exporter textfile collector
+    #[arg(long, value_name = "FILE")]
+    pub metrics_textfile: Option<PathBuf>,
+}
+
+/// Top-level subcommands
+#[derive(Subcommand, Clone, Debug)]
+pub enum Command {
+    /// Benchmark a guest program
+    #[command(flatten)]
+    GuestProgram(GuestProgramCommand),
+    /// Compare execution metrics between two metrics folders
+    Compare(CompareArgs),
+    /// Fail when a candidate metrics folder regresses against a baseline
+    CheckRegressions(CheckRegressionsArgs),
+    /// Fit per-opcode cycle costs to the execution metrics of a metrics folder
+    CostModel(CostModelArgs),
+    /// Rank the fixtures of a metrics folder by cost per gas to find prover-killer blocks
+    GasReport(GasReportArgs),
+    /// Aggregate a folder of Zisk profiles into a summary report
+    AnalyzeProfiles(AnalyzeProfilesArgs),
+    /// Render a cycle profile as a flamegraph, or diff two profiles of the same fixture
+    Flamegraph(FlamegraphArgs),
+    /// Render reports of a metrics folder
+    #[command(subcommand)]
+    Report(ReportCommand),
+    /// Export a metrics folder as CSV, Parquet or `SQLite` tables for data analysis
+    Export(ExportArgs),
+}
 
-    /// Output folder for Zisk profile results
-    #[arg(long, default_value = "zisk-profiles")]
-    pub zisk_profile_output: PathBuf,
+/// Subcommands of the `report` subcommand
+#[derive(Subcommand, Clone, Debug)]
+pub enum ReportCommand {
+    /// Build a self-contained HTML dashboard of a metrics folder
+    Html(ReportHtmlArgs),
 }
 
 /// Subcommands for different guest programs
@@ -101,6 +205,197 @@ pub enum GuestProgramCommand {
     },
 }
 
+/// Arguments for the `compare` subcommand
+#[derive(Args, Clone, Debug)]
+pub struct CompareArgs {
+    /// Baseline metrics folder
+    pub baseline: PathBuf,
+    /// Candidate metrics folder
+    pub candidate: PathBuf,
+    /// Report format
+    #[arg(long, value_enum, default_value = "markdown")]
+    pub format: ReportFormat,
+    /// Write the report to a file instead of stdout
+    #[arg(long)]
+    pub output_file: Option<PathBuf>,
+    /// Exit with an error when a fixture crashes or stops matching its expected output
+    #[arg(long)]
+    pub fail_on_regression: bool,
+    /// Compare runs even when their provenance shows they were produced by different guests
+    #[arg(long)]
+    pub allow_provenance_mismatch: bool,
+}
+
+/// Arguments for the `check-regressions` subcommand
+#[derive(Args, Clone, Debug)]
+pub struct CheckRegressionsArgs {
+    /// Baseline metrics folder
+    pub baseline: PathBuf,
+    /// Candidate metrics folder
+    pub candidate: PathBuf,
+    /// TOML regression policy with per-metric thresholds
+    #[arg(long)]
+    pub policy: PathBuf,
+    /// Report format
+    #[arg(long, value_enum, default_value = "markdown")]
+    pub format: ReportFormat,
+    /// Write the report to a file instead of stdout
+    #[arg(long)]
+    pub output_file: Option<PathBuf>,
+    /// Check runs even when their provenance shows they were produced by different guests
+    #[arg(long)]
+    pub allow_provenance_mismatch: bool,
+}
+
+/// Arguments for the `cost-model` subcommand
+#[derive(Args, Clone, Debug)]
+pub struct CostModelArgs {
+    /// Metrics folder with execution results carrying `opcode_count` and `block_used_gas`
+    pub metrics: PathBuf,
+    /// Report format
+    #[arg(long, value_enum, default_value = "markdown")]
+    pub format: ReportFormat,
+    /// Write the report to a file instead of stdout
+    #[arg(long)]
+    pub output_file: Option<PathBuf>,
+    /// Residual, in root mean squared errors, above which a fixture is reported as an outlier
+    #[arg(long, default_value_t = zkevm_metrics::cost_model::DEFAULT_OUTLIER_THRESHOLD)]
+    pub outlier_threshold: f64,
+}
+
+/// Arguments for the `gas-report` subcommand
+#[derive(Args, Clone, Debug)]
+pub struct GasReportArgs {
+    /// Metrics folder with results of fixtures carrying `block_used_gas`
+    pub metrics: PathBuf,
+    /// Cost per gas to rank fixtures by
+    #[arg(long, value_enum, default_value = "cycles")]
+    pub rank_by: GasRankMetric,
+    /// Number of costliest fixtures to list per zkVM/EL pair
+    #[arg(long, default_value_t = 20)]
+    pub top: usize,
+    /// Report format
+    #[arg(long, value_enum, default_value = "markdown")]
+    pub format: ReportFormat,
+    /// Write the report to a file instead of stdout
+    #[arg(long)]
+    pub output_file: Option<PathBuf>,
+}
+
+/// Costs per gas fixtures can be ranked by
+#[derive(Debug, Copy, Clone, ValueEnum)]
+pub enum GasRankMetric {
+    /// Execution cycles per gas
+    Cycles,
+    /// Proving milliseconds per million gas
+    ProvingTime,
+    /// Proof bytes per million gas
+    ProofSize,
+}
+
+/// Arguments for the `report html` subcommand
+#[derive(Args, Clone, Debug)]
+pub struct ReportHtmlArgs {
+    /// Metrics folders, as written by benchmark runs, combined into one page
+    #[arg(required = true)]
+    pub metrics: Vec<PathBuf>,
+    /// HTML file to write
+    #[arg(short, long, default_value = "index.html")]
+    pub output_file: PathBuf,
+    /// Page title
+    #[arg(long, default_value = "zkEVM Stateless Validator Benchmark Results")]
+    pub title: String,
+}
+
+/// Arguments for the `export` subcommand
+#[derive(Args, Clone, Debug)]
+pub struct ExportArgs {
+    /// Metrics folder, as written by a benchmark run
+    pub metrics: PathBuf,
+    /// Export format
+    #[arg(long, value_enum, default_value = "csv")]
+    pub format: ExportFormat,
+    /// Directory to write the tables into for CSV and Parquet, database file for `SQLite`
+    #[arg(short, long)]
+    pub output: PathBuf,
+}
+
+/// Table export formats
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ValueEnum)]
+pub enum ExportFormat {
+    /// One CSV file per table
+    Csv,
+    /// One Parquet file per table
+    Parquet,
+    /// A single `SQLite` database
+    Sqlite,
+}
+
+/// Arguments for the `analyze-profiles` subcommand
+#[derive(Args, Clone, Debug)]
+pub struct AnalyzeProfilesArgs {
+    /// Folder containing `.prof` files written by --profile for Zisk
+    pub directory: PathBuf,
+    /// Report format
+    #[arg(long, value_enum, default_value = "markdown")]
+    pub format: ProfileReportFormat,
+    /// Write the report to a file instead of stdout
+    #[arg(long)]
+    pub output_file: Option<PathBuf>,
+    /// Comma-separated profiling scopes to leave out of the report (case-insensitive)
+    #[arg(long, value_name = "SCOPES", value_delimiter = ',')]
+    pub ignore_scopes: Vec<String>,
+}
+
+/// Arguments for the `flamegraph` subcommand
+#[derive(Args, Clone, Debug)]
+pub struct FlamegraphArgs {
+    /// Profile to render: a `.folded` file, a Zisk `.prof` file, or a metrics `.json` file whose
+    /// execution cycle regions are used
+    pub profile: PathBuf,
+    /// Baseline profile to diff against, rendering a differential flamegraph
+    #[arg(long)]
+    pub baseline: Option<PathBuf>,
+    /// Output format
+    #[arg(long, value_enum, default_value = "svg")]
+    pub format: FlamegraphFormat,
+    /// Title of the flamegraph (default: the profile file name)
+    #[arg(long)]
+    pub title: Option<String>,
+    /// Write the output to a file instead of stdout
+    #[arg(long)]
+    pub output_file: Option<PathBuf>,
+}
+
+/// Flamegraph output formats
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ValueEnum)]
+pub enum FlamegraphFormat {
+    /// Self-contained SVG flamegraph
+    Svg,
+    /// Speedscope JSON profile
+    Speedscope,
+}
+
+/// Profile summary report formats
+#[derive(Debug, Copy, Clone, ValueEnum)]
+pub enum ProfileReportFormat {
+    /// Markdown tables
+    Markdown,
+    /// JSON document
+    Json,
+}
+
+/// Comparison report formats
+#[derive(Debug, Copy, Clone, ValueEnum)]
+pub enum ReportFormat {
+    /// Markdown tables
+    Markdown,
+    /// JSON document
+    Json,
+    /// CSV with one row per fixture and metric
+    Csv,
+}
+
 /// Execution clients for the stateless validator
 #[derive(Debug, Copy, Clone, ValueEnum)]
 pub enum ExecutionClient {
@@ -121,6 +416,8 @@ pub enum Resource {
     Gpu,
     /// Proving cluster (requires --cluster-endpoint)
     Cluster,
+    /// Hosted proving network (requires --network-endpoint)
+    Network,
 }
 
 /// Benchmark actions
@@ -134,6 +431,9 @@ pub enum BenchmarkAction {
     Verify,
 }
 
+/// Environment variable holding the remote prover API key when --api-key is not given.
+const API_KEY_ENV: &str = "ZKVM_PROVER_API_KEY";
+
 fn parse_duration(value: &str) -> Result<Duration, String> {
     humantime::parse_duration(value).map_err(|err| err.to_string())
 }
@@ -141,11 +441,17 @@ fn parse_duration(value: &str) -> Result<Duration, String> {
 impl Cli {
     /// Validate arguments whose requirements depend on the selected action.
     pub fn validate(&self) -> AnyhowResult<()> {
+        let Command::GuestProgram(guest_program) = &self.command else {
+            return Ok(());
+        };
+        if self.zkvms.is_empty() {
+            bail!("--zkvms is required when benchmarking a guest program");
+        }
         if matches!(self.action, BenchmarkAction::Verify) {
             return Ok(());
         }
 
-        let GuestProgramCommand::StatelessValidator { input_folder, .. } = &self.guest_program;
+        let GuestProgramCommand::StatelessValidator { input_folder, .. } = guest_program;
         let Some(input_folder) = input_folder else {
             bail!("--input-folder is required with --action execute or --action prove");
         };
@@ -156,6 +462,24 @@ impl Cli {
         Ok(())
     }
 
+    /// Which fixtures with recorded results are run again.
+    pub const fn rerun_policy(&self) -> RerunPolicy {
+        RerunPolicy {
+            force: self.force_rerun,
+            crashed: self.rerun_crashed,
+            mismatched: self.rerun_mismatched,
+        }
+    }
+
+    /// Warmup and measured iterations per fixture.
+    pub fn repetitions(&self) -> Repetitions {
+        let default_warmup = u32::from(matches!(self.action, BenchmarkAction::Verify));
+        Repetitions {
+            warmup: self.warmup_iterations.unwrap_or(default_warmup),
+            measured: self.iterations,
+        }
+    }
+
     /// Build the Ere [`ProverResource`] from parsed CLI args.
     pub fn prover_resource(&self) -> ProverResource {
         match self.resource {
@@ -166,10 +490,25 @@ impl Cli {
                     .cluster_endpoint
                     .clone()
                     .expect("clap required_if_eq should guarantee cluster_endpoint set"),
-                api_key: None,
+                api_key: self.api_key(),
+            }),
+            Resource::Network => ProverResource::Network(RemoteProverConfig {
+                endpoint: self
+                    .network_endpoint
+                    .clone()
+                    .expect("clap required_if_eq should guarantee network_endpoint set"),
+                api_key: self.api_key(),
             }),
         }
     }
+
+    /// API key for the remote prover, from --api-key or the environment.
+    fn api_key(&self) -> Option<String> {
+        self.api_key
+            .clone()
+            .or_else(|| env::var(API_KEY_ENV).ok())
+            .filter(|key| !key.is_empty())
+    }
 }
 
 impl From<BenchmarkAction> for Action {
@@ -182,6 +521,35 @@ impl From<BenchmarkAction> for Action {
     }
 }
 
+impl From<ReportFormat> for compare::ReportFormat {
+    fn from(format: ReportFormat) -> Self {
+        match format {
+            ReportFormat::Markdown => Self::Markdown,
+            ReportFormat::Json => Self::Json,
+            ReportFormat::Csv => Self::Csv,
+        }
+    }
+}
+
+impl From<GasRankMetric> for gas::GasMetric {
+    fn from(metric: GasRankMetric) -> Self {
+        match metric {
+            GasRankMetric::Cycles => Self::CyclesPerGas,
+            GasRankMetric::ProvingTime => Self::ProvingMsPerMgas,
+            GasRankMetric::ProofSize => Self::ProofBytesPerMgas,
+        }
+    }
+}
+
+impl From<ProfileReportFormat> for zisk_profile::ProfileReportFormat {
+    fn from(format: ProfileReportFormat) -> Self {
+        match format {
+            ProfileReportFormat::Markdown => Self::Markdown,
+            ProfileReportFormat::Json => Self::Json,
+        }
+    }
+}
+
 impl From<ExecutionClient> for stateless_validator::ExecutionClient {
     fn from(client: ExecutionClient) -> Self {
         match client {
@@ -0,0 +1,158 @@
+//! Comparison of benchmark metrics between two metrics folders
+
+use anyhow::{Context, Result, bail};
+use std::{fs, path::Path};
+use tracing::{info, warn};
+use zkevm_metrics::{
+    compare::{ExecutionComparison, LoadedRuns, load_runs, provenance_mismatches},
+    cost_model::CostModelReport,
+    gas::GasCostReport,
+    regression::{RegressionPolicy, RegressionReport},
+};
+
+use crate::cli::{CheckRegressionsArgs, CompareArgs, CostModelArgs, GasReportArgs};
+
+/// Compares the execution metrics of two metrics folders and writes the report.
+pub fn run_compare(args: &CompareArgs) -> Result<()> {
+    let (baseline, candidate) = load_comparable_runs(
+        &args.baseline,
+        &args.candidate,
+        args.allow_provenance_mismatch,
+    )?;
+    let comparison = ExecutionComparison::new(&baseline, &candidate);
+    let report = comparison
+        .render(args.format.into())
+        .context("Failed to render comparison report")?;
+
+    write_report(&report, args.output_file.as_deref())?;
+
+    if args.fail_on_regression && comparison.has_regressions() {
+        bail!(
+            "{} crash regression(s) and {} output regression(s) found",
+            comparison.summary.crash_regressions,
+            comparison.summary.output_regressions
+        );
+    }
+
+    Ok(())
+}
+
+/// Checks a candidate metrics folder against a baseline using a TOML regression policy.
+///
+/// Returns an error, and therefore a non-zero exit code, when any regression is found.
+pub fn run_check_regressions(args: &CheckRegressionsArgs) -> Result<()> {
+    let policy = fs::read_to_string(&args.policy)
+        .with_context(|| format!("Failed to read regression policy {}", args.policy.display()))?;
+    let policy: RegressionPolicy = toml::from_str(&policy).with_context(|| {
+        format!(
+            "Failed to parse regression policy {}",
+            args.policy.display()
+        )
+    })?;
+
+    let (baseline, candidate) = load_comparable_runs(
+        &args.baseline,
+        &args.candidate,
+        args.allow_provenance_mismatch,
+    )?;
+    let report = RegressionReport::new(&baseline, &candidate, &policy);
+    let rendered = report
+        .render(args.format.into())
+        .context("Failed to render regression report")?;
+    write_report(&rendered, args.output_file.as_deref())?;
+
+    if !report.passed() {
+        bail!(
+            "{} regression(s) found in {}",
+            report.regressions.len(),
+            args.candidate.display()
+        );
+    }
+
+    Ok(())
+}
+
+/// Fits per-opcode cycle costs to each zkVM/EL pair of a metrics folder and writes the report.
+pub fn run_cost_model(args: &CostModelArgs) -> Result<()> {
+    if args.outlier_threshold.is_nan() || args.outlier_threshold <= 0.0 {
+        bail!(
+            "--outlier-threshold must be positive, got {}",
+            args.outlier_threshold
+        );
+    }
+    let runs = load_runs(&args.metrics)
+        .with_context(|| format!("Failed to load metrics from {}", args.metrics.display()))?;
+    let report = CostModelReport::new(&runs, args.outlier_threshold);
+    if report.groups.is_empty() {
+        warn!(
+            "No zkVM/EL pair in {} has two successful runs with opcode counts and gas used",
+            args.metrics.display()
+        );
+    }
+    let rendered = report
+        .render(args.format.into())
+        .context("Failed to render cost model report")?;
+    write_report(&rendered, args.output_file.as_deref())
+}
+
+/// Ranks the fixtures of each zkVM/EL pair of a metrics folder by cost per gas and writes the
+/// report.
+pub fn run_gas_report(args: &GasReportArgs) -> Result<()> {
+    let runs = load_runs(&args.metrics)
+        .with_context(|| format!("Failed to load metrics from {}", args.metrics.display()))?;
+    let report = GasCostReport::new(&runs, args.rank_by.into(), args.top);
+    if report.groups.is_empty() {
+        warn!(
+            "No run in {} has a {} value",
+            args.metrics.display(),
+            report.metric.label()
+        );
+    }
+    let rendered = report
+        .render(args.format.into())
+        .context("Failed to render gas report")?;
+    write_report(&rendered, args.output_file.as_deref())
+}
+
+/// Loads both metrics folders, refusing runs of different guests unless `allow_mismatch` is set.
+fn load_comparable_runs(
+    baseline: &Path,
+    candidate: &Path,
+    allow_mismatch: bool,
+) -> Result<(LoadedRuns, LoadedRuns)> {
+    let load = |path: &Path| {
+        load_runs(path).with_context(|| format!("Failed to load metrics from {}", path.display()))
+    };
+    let (baseline_runs, candidate_runs) = (load(baseline)?, load(candidate)?);
+
+    let mismatches = provenance_mismatches(&baseline_runs, &candidate_runs);
+    if let Some(first) = mismatches.first() {
+        let message = format!(
+            "{} matched fixture(s) in {} and {} were produced by different guests, e.g. `{}` differs in {}",
+            mismatches.len(),
+            baseline.display(),
+            candidate.display(),
+            first.key.label(),
+            first.fields.join(", ")
+        );
+        if !allow_mismatch {
+            bail!("{message}; pass --allow-provenance-mismatch to compare them anyway");
+        }
+        warn!("{message}");
+    }
+
+    Ok((baseline_runs, candidate_runs))
+}
+
+/// Writes `report` to `output_file`, or to stdout when no file is given.
+pub(crate) fn write_report(report: &str, output_file: Option<&Path>) -> Result<()> {
+    match output_file {
+        Some(path) => {
+            fs::write(path, report)
+                .with_context(|| format!("Failed to write report to {}", path.display()))?;
+            info!("Report written to {}", path.display());
+        }
+        None => print!("{report}"),
+    }
+    Ok(())
+}
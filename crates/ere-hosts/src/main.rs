@@ -2,11 +2,14 @@
 
 #![cfg_attr(not(test), warn(unused_crate_dependencies))]
 
-use anyhow::{Context, Result, bail};
+use anyhow::{Context, Result, anyhow, bail};
 use benchmark_runner::{
+    guest_cache::GuestCache,
+    profiling::profiler_for,
+    progress::ProgressMetrics,
     runner::{
-        Action, GuestProgramSource, ProfileConfig, RunConfig, benchmark_output_dir,
-        get_el_zkvm_instances, run_benchmark_iter,
+        Action, GuestProgramSource, ProfileConfig, RunConfig, ZkVMInstance, get_el_zkvm_instances,
+        run_benchmark_iter,
     },
     stateless_validator::{self},
     verification::{download_and_extract_proofs, resolve_extracted_root, run_verify_from_disk},
@@ -14,13 +17,20 @@ use benchmark_runner::{
 use ere_dockerized::{DockerizedzkVMConfig, ProverResource, zkVMKind};
 
 use clap::Parser;
-use std::time::Duration;
+use std::{net::SocketAddr, path::PathBuf, sync::Arc, time::Duration};
 use tracing::info;
 use tracing_subscriber::EnvFilter;
+use zkevm_metrics::{
+    prometheus::{self, Registry},
+    results,
+};
 
-use crate::cli::{Cli, GuestProgramCommand};
+use crate::cli::{Cli, Command, GuestProgramCommand, ReportCommand};
 
 pub mod cli;
+pub mod compare;
+pub mod profiles;
+pub mod report;
 
 const DEFAULT_EXECUTE_TIMEOUT: Duration = Duration::from_secs(5 * 60);
 const DEFAULT_PROVE_TIMEOUT: Duration = Duration::from_secs(15 * 60);
@@ -37,39 +47,68 @@ async fn main() -> Result<()> {
     let cli = Cli::parse();
     cli.validate()?;
 
-    if cli.zisk_profile {
-        if !matches!(cli.action, cli::BenchmarkAction::Execute) {
-            bail!(
-                "--zisk-profile requires --action execute, but got {:?}",
-                cli.action
-            );
-        }
-        if cli.zkvms.len() != 1 || cli.zkvms[0] != zkVMKind::Zisk {
-            let zkvm_names: Vec<_> = cli.zkvms.iter().map(|z| z.as_str()).collect();
+    match &cli.command {
+        Command::GuestProgram(_) => {}
+        Command::Compare(args) => return compare::run_compare(args),
+        Command::CheckRegressions(args) => return compare::run_check_regressions(args),
+        Command::CostModel(args) => return compare::run_cost_model(args),
+        Command::GasReport(args) => return compare::run_gas_report(args),
+        Command::AnalyzeProfiles(args) => return profiles::run_analyze_profiles(args),
+        Command::Flamegraph(args) => return profiles::run_flamegraph(args),
+        Command::Report(ReportCommand::Html(args)) => return report::run_report_html(args),
+        Command::Export(args) => return report::run_export(args),
+    }
+
+    if cli.profile && !matches!(cli.action, cli::BenchmarkAction::Execute) {
+        bail!(
+            "--profile requires --action execute, but got {:?}",
+            cli.action
+        );
+    }
+    if cli.profile && !cli.profile_regions {
+        let unprofiled: Vec<_> = cli
+            .zkvms
+            .iter()
+            .filter(|zkvm| profiler_for(**zkvm, false).is_none())
+            .map(zkVMKind::as_str)
+            .collect();
+        if !unprofiled.is_empty() {
             bail!(
-                "--zisk-profile requires --zkvms zisk only, but got: {}",
-                zkvm_names.join(", ")
+                "{} have no native profiler, pass --profile-regions to profile their cycle regions",
+                unprofiled.join(", ")
             );
         }
     }
 
     let resource: ProverResource = cli.prover_resource();
+    let repetitions = cli.repetitions();
+    let rerun = cli.rerun_policy();
     let action: Action = cli.action.into();
     let zkvm_config = build_zkvm_config(action, cli.timeout);
     info!(
         "Running benchmarks with resource={:?} and action={:?}",
-        resource, action
+        cli.resource, action
     );
 
-    let zisk_profile_config = cli
-        .zisk_profile
-        .then(|| ProfileConfig::new(cli.zisk_profile_output.clone()));
+    let profile_config = cli.profile.then(|| {
+        ProfileConfig::new(cli.profile_output.clone()).with_region_fallback(cli.profile_regions)
+    });
 
     // Validate: --save-proofs is only valid with --action prove
     if cli.save_proofs.is_some() && !matches!(action, Action::Prove) {
         anyhow::bail!("--save-proofs is only valid with --action prove");
     }
 
+    // Validate: --prove-concurrency is only valid with --action prove
+    if cli.prove_concurrency.get() > 1 && !matches!(action, Action::Prove) {
+        anyhow::bail!("--prove-concurrency is only valid with --action prove");
+    }
+
+    // Validate: sampled fixtures run one at a time
+    if cli.resource_usage && cli.prove_concurrency.get() > 1 {
+        anyhow::bail!("--resource-usage cannot be combined with --prove-concurrency above 1");
+    }
+
     // Validate: --proofs-url is only valid with --action verify
     if cli.proofs_url.is_some() && !matches!(action, Action::Verify) {
         anyhow::bail!("--proofs-url is only valid with --action verify");
@@ -80,16 +119,34 @@ async fn main() -> Result<()> {
         anyhow::bail!("--cluster-endpoint is only valid with --resource cluster");
     }
 
-    // Validate: --resource cluster currently only supports zisk zkVM and not support --action execute
-    if matches!(cli.resource, cli::Resource::Cluster) {
-        if cli.zkvms.iter().any(|z| *z != zkVMKind::Zisk) {
-            anyhow::bail!("--resource cluster is only implemented for --zkvms zisk");
-        }
-        if matches!(action, Action::Execute) {
-            anyhow::bail!("--resource cluster is not implemented for --action execute");
-        }
+    // Validate: --network-endpoint is only valid with --resource network
+    if cli.network_endpoint.is_some() && !matches!(cli.resource, cli::Resource::Network) {
+        anyhow::bail!("--network-endpoint is only valid with --resource network");
+    }
+
+    // Validate: --api-key is only valid with a remote prover
+    if cli.api_key.is_some()
+        && !matches!(
+            cli.resource,
+            cli::Resource::Cluster | cli::Resource::Network
+        )
+    {
+        anyhow::bail!("--api-key is only valid with --resource cluster or --resource network");
     }
 
+    // Validate: --offline cannot download proofs
+    if cli.offline && cli.proofs_url.is_some() {
+        anyhow::bail!("--proofs-url cannot be used with --offline");
+    }
+
+    let guest_cache_dir = cli
+        .guest_cache_dir
+        .clone()
+        .or_else(GuestCache::default_dir)
+        .context("Failed to determine the guest cache directory, set --guest-cache-dir")?;
+    let guest_cache = GuestCache::new(guest_cache_dir, cli.offline)
+        .with_required_checksums(cli.require_checksums);
+
     // Resolve proofs source: download from URL or use local folder.
     // _proofs_tmpdir must live until verification completes (drop = cleanup).
     let (_proofs_tmpdir, proofs_folder) = if let Some(ref url) = cli.proofs_url {
@@ -109,13 +166,21 @@ async fn main() -> Result<()> {
         output_folder: cli.output_folder,
         sub_folder: None,
         action,
-        force_rerun: cli.force_rerun,
+        rerun,
+        timeout: action_timeout(&zkvm_config, action),
         dump_inputs_folder: cli.dump_inputs,
-        zisk_profile_config,
+        profile_config,
         save_proofs_folder: cli.save_proofs,
+        repetitions,
+        prove_concurrency: cli.prove_concurrency,
+        sample_resources: cli.resource_usage,
+        progress: progress_metrics(cli.metrics_listen, cli.metrics_textfile)?,
     };
 
-    match cli.guest_program {
+    let Command::GuestProgram(guest_program) = cli.command else {
+        unreachable!("metrics tool commands return before benchmarking")
+    };
+    match guest_program {
         GuestProgramCommand::StatelessValidator {
             input_folder,
             fixture,
@@ -132,13 +197,14 @@ async fn main() -> Result<()> {
             } else {
                 el.version().to_string()
             };
-            let el_str = format!("{}-{}", el_name, el_version);
+            let el_str = results::versioned_dir_name(&el_name, &el_version);
             let zkvms = get_el_zkvm_instances(
                 &el_name,
                 &cli.zkvms,
                 resource,
                 zkvm_config.clone(),
                 &guest_source,
+                &guest_cache,
             )
             .await
             .context("Failed to get EL zkvm instances")?;
@@ -149,11 +215,9 @@ async fn main() -> Result<()> {
             };
 
             match action {
-                Action::Verify => {
-                    for instance in &zkvms {
-                        run_verify_from_disk(instance, &config, &proofs_folder)?;
-                    }
-                }
+                Action::Verify => for_each_zkvm(&zkvms, cli.parallel_zkvms, |instance| {
+                    run_verify_from_disk(instance, &config, &proofs_folder)
+                })?,
                 _ => {
                     let input_folder = input_folder
                         .expect("CLI validation requires an input folder for execute and prove");
@@ -161,18 +225,16 @@ async fn main() -> Result<()> {
                         "Running stateless-validator benchmark for input folder: {}",
                         input_folder.display()
                     );
-                    for zkvm in &zkvms {
-                        let existing_output_dir =
-                            (!config.force_rerun).then(|| benchmark_output_dir(zkvm, &config));
+                    for_each_zkvm(&zkvms, cli.parallel_zkvms, |zkvm| {
                         let guest_io = stateless_validator::stateless_validator_input_iter(
                             input_folder.as_path(),
                             fixture.as_deref(),
                             el,
-                            existing_output_dir.as_deref(),
+                            None,
                         )?
                         .map(|input| input.context("Failed to get stateless validator input"));
-                        run_benchmark_iter(zkvm, &config, guest_io)?;
-                    }
+                        run_benchmark_iter(zkvm, &config, guest_io)
+                    })?;
                 }
             }
         }
@@ -181,6 +243,60 @@ async fn main() -> Result<()> {
     Ok(())
 }
 
+/// Sets up benchmark progress metrics when they are served or written to a textfile.
+fn progress_metrics(
+    listen: Option<SocketAddr>,
+    textfile: Option<PathBuf>,
+) -> Result<Option<Arc<ProgressMetrics>>> {
+    if listen.is_none() && textfile.is_none() {
+        return Ok(None);
+    }
+    let registry = Arc::new(Registry::new());
+    let progress = ProgressMetrics::new(Arc::clone(&registry), textfile);
+    if let Some(addr) = listen {
+        let server = prometheus::serve(registry, addr)
+            .with_context(|| format!("Failed to serve metrics on {addr}"))?;
+        info!(
+            "Serving benchmark progress metrics at http://{}/metrics",
+            server.local_addr()
+        );
+    }
+    Ok(Some(Arc::new(progress)))
+}
+
+/// Runs `run` for every zkVM instance, each on its own thread when `parallel` is set.
+///
+/// All instances run to completion before the first error is returned.
+fn for_each_zkvm<F>(zkvms: &[ZkVMInstance], parallel: bool, run: F) -> Result<()>
+where
+    F: Fn(&ZkVMInstance) -> Result<()> + Sync,
+{
+    if !parallel {
+        return zkvms.iter().try_for_each(run);
+    }
+
+    let run = &run;
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = zkvms
+            .iter()
+            .map(|zkvm| {
+                let handle = scope.spawn(move || run(zkvm));
+                (zkvm, handle)
+            })
+            .collect();
+        let results: Vec<Result<()>> = handles
+            .into_iter()
+            .map(|(zkvm, handle)| {
+                handle
+                    .join()
+                    .unwrap_or_else(|_| Err(anyhow!("benchmark thread panicked")))
+                    .with_context(|| format!("Benchmark failed for {}", zkvm.name()))
+            })
+            .collect();
+        results.into_iter().collect()
+    })
+}
+
 fn validate_guest_compatibility(
     el: stateless_validator::ExecutionClient,
     zkvms: &[zkVMKind],
@@ -234,3 +350,11 @@ const fn build_zkvm_config(
 
     config
 }
+
+const fn action_timeout(config: &DockerizedzkVMConfig, action: Action) -> Option<Duration> {
+    match action {
+        Action::Execute => config.execute_timeout,
+        Action::Prove => config.prove_timeout,
+        Action::Verify => config.verify_timeout,
+    }
+}
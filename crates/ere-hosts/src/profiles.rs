@@ -0,0 +1,54 @@
+//! Aggregate reports and flamegraphs of guest profiles
+
+use anyhow::{Context, Result, bail};
+use benchmark_runner::{
+    flamegraph,
+    profiling::load_folded_stacks,
+    zisk_profile::{ProfileSummary, load_profiles},
+};
+use tracing::info;
+
+use crate::{
+    cli::{AnalyzeProfilesArgs, FlamegraphArgs, FlamegraphFormat},
+    compare::write_report,
+};
+
+/// Aggregates the profiles in a folder and writes the summary report.
+pub fn run_analyze_profiles(args: &AnalyzeProfilesArgs) -> Result<()> {
+    let profiles = load_profiles(&args.directory)?;
+    info!(
+        "Analyzing {} profiles from {}",
+        profiles.len(),
+        args.directory.display()
+    );
+
+    let summary = ProfileSummary::new(&args.directory, &profiles, &args.ignore_scopes);
+    let report = summary
+        .render(args.format.into())
+        .context("Failed to render profile summary")?;
+    write_report(&report, args.output_file.as_deref())
+}
+
+/// Renders a profile, or the diff of two profiles, and writes it.
+pub fn run_flamegraph(args: &FlamegraphArgs) -> Result<()> {
+    let candidate = load_folded_stacks(&args.profile)?;
+    let title = args.title.clone().unwrap_or_else(|| {
+        args.profile
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    });
+
+    let output = match (&args.baseline, args.format) {
+        (None, FlamegraphFormat::Svg) => flamegraph::render_svg(&candidate, &title),
+        (None, FlamegraphFormat::Speedscope) => flamegraph::render_speedscope(&candidate, &title)?,
+        (Some(baseline), FlamegraphFormat::Svg) => {
+            let baseline = load_folded_stacks(baseline)?;
+            flamegraph::render_diff_svg(&baseline, &candidate, &title)
+        }
+        (Some(_), FlamegraphFormat::Speedscope) => {
+            bail!("--baseline only supports --format svg")
+        }
+    };
+    write_report(&output, args.output_file.as_deref())
+}
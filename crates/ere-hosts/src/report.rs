@@ -0,0 +1,104 @@
+//! Static reports and table exports of a metrics folder
+
+use anyhow::{Context, Result, bail};
+use std::{fs, path::Path};
+use tracing::{info, warn};
+use zkevm_metrics::{
+    compare::LoadedRuns, export::ExportTables, html::render_dashboard, results::ResultsIndex,
+};
+
+use crate::cli::{ExportArgs, ExportFormat, ReportHtmlArgs};
+
+/// Renders one HTML dashboard of the given metrics folders, including the `hardware.json` of the
+/// first folder that has one.
+pub fn run_report_html(args: &ReportHtmlArgs) -> Result<()> {
+    let mut runs = LoadedRuns::new();
+    let mut hardware = None;
+    for metrics in &args.metrics {
+        let index = ResultsIndex::open(metrics)
+            .with_context(|| format!("Failed to load metrics from {}", metrics.display()))?;
+        if index.runs().is_empty() {
+            warn!("No benchmark results found in {}", metrics.display());
+        }
+        match (&hardware, index.hardware()) {
+            (None, found) => hardware = found.cloned(),
+            (Some(shown), Some(found)) if shown != found => warn!(
+                "Hardware of {} differs from the hardware shown in the report",
+                metrics.display()
+            ),
+            _ => {}
+        }
+        for (mut key, run) in index.into_loaded_runs() {
+            if key.group.is_empty() {
+                key.group = root_group(metrics);
+            }
+            let label = key.label();
+            if runs.insert(key, run).is_some() {
+                bail!("{label} is in more than one metrics folder");
+            }
+        }
+    }
+
+    let html = render_dashboard(
+        &runs,
+        hardware.as_ref(),
+        &args.title,
+        zkevm_metrics::chrono::Utc::now(),
+    );
+    if let Some(parent) = args
+        .output_file
+        .parent()
+        .filter(|p| !p.as_os_str().is_empty())
+    {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    fs::write(&args.output_file, html)
+        .with_context(|| format!("Failed to write {}", args.output_file.display()))?;
+    info!(
+        "HTML report with {} result(s) written to {}",
+        runs.len(),
+        args.output_file.display()
+    );
+    Ok(())
+}
+
+/// Returns the group of runs stored directly in the `metrics` folder: the folder name without a
+/// `zkevm-metrics-` prefix, which names the zkVM of per-zkVM CI folders.
+fn root_group(metrics: &Path) -> String {
+    let name = fs::canonicalize(metrics)
+        .ok()
+        .and_then(|path| {
+            path.file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+        })
+        .unwrap_or_default();
+    name.strip_prefix("zkevm-metrics-")
+        .unwrap_or(&name)
+        .to_string()
+}
+
+/// Exports the runs of a metrics folder, with its `hardware.json` when present, as tables.
+pub fn run_export(args: &ExportArgs) -> Result<()> {
+    let index = ResultsIndex::open(&args.metrics)
+        .with_context(|| format!("Failed to load metrics from {}", args.metrics.display()))?;
+    if index.runs().is_empty() {
+        warn!("No benchmark results found in {}", args.metrics.display());
+    }
+    let tables = ExportTables::from_index(&index);
+
+    let written = match args.format {
+        ExportFormat::Csv => tables.write_csv(&args.output),
+        ExportFormat::Parquet => tables.write_parquet(&args.output),
+        ExportFormat::Sqlite => tables.write_sqlite(&args.output).map(|()| Vec::new()),
+    };
+    written.with_context(|| format!("Failed to export tables to {}", args.output.display()))?;
+    info!(
+        "Exported {} run(s), {} region cycle and {} opcode count row(s) to {}",
+        tables.runs.num_rows(),
+        tables.region_cycles.num_rows(),
+        tables.opcode_counts.num_rows(),
+        args.output.display()
+    );
+    Ok(())
+}
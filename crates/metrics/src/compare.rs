@@ -0,0 +1,820 @@
+//! Comparison of execution metrics between two metrics folders.
+//!
+//! A metrics folder is any directory containing `BenchmarkRun` JSON files, such as the
+//! `zkevm-metrics/` root written by `ere-hosts` or one of its `<el>-<version>/<zkvm>-<version>/`
+//! subfolders. Runs are matched by their directory relative to the folder root and by
+//! [`BenchmarkRun::name`], so comparing two zkVMs or EL clients means pointing both sides at the
+//! corresponding subfolders.
+
+use crate::{BenchmarkRun, ExecutionMetrics, MetricsError, markdown::escape_markdown, results};
+use serde_derive::Serialize;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Write as _,
+    path::Path,
+};
+
+/// Benchmark runs loaded from a metrics folder, keyed by relative directory and fixture name.
+pub type LoadedRuns = BTreeMap<RunKey, BenchmarkRun<serde_json::Value>>;
+
+/// Identifies a benchmark run within a metrics folder.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+pub struct RunKey {
+    /// Directory of the run relative to the metrics folder root, using `/` separators.
+    pub group: String,
+    /// Fixture name, as stored in [`BenchmarkRun::name`].
+    pub name: String,
+}
+
+impl RunKey {
+    /// Returns a `group/name` label, or just the name for runs at the folder root.
+    pub fn label(&self) -> String {
+        if self.group.is_empty() {
+            self.name.clone()
+        } else {
+            format!("{}/{}", self.group, self.name)
+        }
+    }
+}
+
+/// Loads every benchmark run below `root`, skipping known non-run files such as `hardware.json`
+/// and `run.json`.
+///
+/// See [`crate::results::ResultsIndex`] to also query runs by execution client, zkVM and outcome.
+///
+/// # Errors
+///
+/// Returns `MetricsError::Io` if the folder cannot be walked or a file cannot be read.
+/// Returns `MetricsError::Serde` if a JSON file is not a valid `BenchmarkRun`.
+pub fn load_runs<P: AsRef<Path>>(root: P) -> Result<LoadedRuns, MetricsError> {
+    Ok(results::index_runs(root.as_ref())?
+        .into_iter()
+        .map(|indexed| (indexed.key(), indexed.run))
+        .collect())
+}
+
+/// A fixture present in both folders whose runs were produced by different guest programs.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ProvenanceMismatch {
+    /// Relative directory and fixture name.
+    pub key: RunKey,
+    /// Provenance fields that differ, see [`crate::Provenance::guest_differences`].
+    pub fields: Vec<&'static str>,
+}
+
+/// Returns the matched runs whose recorded provenance shows different guest programs.
+///
+/// Runs without provenance, written before it was recorded, are never reported.
+pub fn provenance_mismatches(
+    baseline: &LoadedRuns,
+    candidate: &LoadedRuns,
+) -> Vec<ProvenanceMismatch> {
+    baseline
+        .iter()
+        .filter_map(|(key, baseline_run)| {
+            let baseline = baseline_run.provenance.as_ref()?;
+            let candidate = candidate.get(key)?.provenance.as_ref()?;
+            let fields = baseline.guest_differences(candidate);
+            (!fields.is_empty()).then(|| ProvenanceMismatch {
+                key: key.clone(),
+                fields,
+            })
+        })
+        .collect()
+}
+
+/// Execution outcome of one side of a comparison.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecutionOutcome {
+    /// The execution succeeded.
+    Success {
+        /// Whether the public output matched the fixture's expected public values.
+        output_matched: bool,
+    },
+    /// The execution crashed.
+    Crashed,
+    /// The run has no execution metrics.
+    Missing,
+}
+
+impl ExecutionOutcome {
+    const fn of(metrics: Option<&ExecutionMetrics>) -> Self {
+        match metrics {
+            Some(ExecutionMetrics::Success { output_matched, .. }) => Self::Success {
+                output_matched: *output_matched,
+            },
+            Some(ExecutionMetrics::Crashed(_)) => Self::Crashed,
+            None => Self::Missing,
+        }
+    }
+
+    const fn label(self) -> &'static str {
+        match self {
+            Self::Success {
+                output_matched: true,
+            } => "success",
+            Self::Success {
+                output_matched: false,
+            } => "output-mismatch",
+            Self::Crashed => "crashed",
+            Self::Missing => "missing",
+        }
+    }
+}
+
+/// Baseline and candidate values of a single metric.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct MetricDelta {
+    /// Baseline value.
+    pub baseline: f64,
+    /// Candidate value.
+    pub candidate: f64,
+    /// `candidate - baseline`.
+    pub delta: f64,
+    /// `delta / baseline`, or `None` when the baseline is zero.
+    pub relative: Option<f64>,
+}
+
+impl MetricDelta {
+    /// Builds a delta from a baseline and a candidate value.
+    pub fn new(baseline: f64, candidate: f64) -> Self {
+        let delta = candidate - baseline;
+        Self {
+            baseline,
+            candidate,
+            delta,
+            relative: (baseline != 0.0).then(|| delta / baseline),
+        }
+    }
+}
+
+/// Comparison of a single fixture present in both folders.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FixtureComparison {
+    /// Relative directory and fixture name.
+    pub key: RunKey,
+    /// Baseline execution outcome.
+    pub baseline: ExecutionOutcome,
+    /// Candidate execution outcome.
+    pub candidate: ExecutionOutcome,
+    /// Total cycles delta, when both executions succeeded.
+    pub total_num_cycles: Option<MetricDelta>,
+    /// Execution duration delta in milliseconds, when both executions succeeded.
+    pub execution_duration_ms: Option<MetricDelta>,
+    /// Per-region cycle deltas for regions reported by both executions.
+    pub region_cycles: BTreeMap<String, MetricDelta>,
+}
+
+impl FixtureComparison {
+    /// Returns whether the baseline succeeded and the candidate crashed.
+    pub const fn is_crash_regression(&self) -> bool {
+        matches!(self.baseline, ExecutionOutcome::Success { .. })
+            && matches!(self.candidate, ExecutionOutcome::Crashed)
+    }
+
+    /// Returns whether the baseline output matched and the candidate output did not.
+    pub const fn is_output_regression(&self) -> bool {
+        matches!(
+            self.baseline,
+            ExecutionOutcome::Success {
+                output_matched: true
+            }
+        ) && matches!(
+            self.candidate,
+            ExecutionOutcome::Success {
+                output_matched: false
+            }
+        )
+    }
+}
+
+/// Aggregate of a metric over all fixtures where it is available on both sides.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AggregateDelta {
+    /// Number of fixtures contributing to the aggregate.
+    pub fixtures: usize,
+    /// Sum of baseline and candidate values.
+    pub total: MetricDelta,
+    /// Geometric mean of `candidate / baseline` over fixtures with non-zero values.
+    pub geometric_mean_ratio: Option<f64>,
+}
+
+impl AggregateDelta {
+    fn from_deltas<'a>(deltas: impl IntoIterator<Item = &'a MetricDelta>) -> Option<Self> {
+        let mut fixtures = 0;
+        let (mut baseline, mut candidate) = (0.0, 0.0);
+        let (mut log_ratio_sum, mut ratios) = (0.0, 0_usize);
+        for delta in deltas {
+            fixtures += 1;
+            baseline += delta.baseline;
+            candidate += delta.candidate;
+            if delta.baseline > 0.0 && delta.candidate > 0.0 {
+                log_ratio_sum += (delta.candidate / delta.baseline).ln();
+                ratios += 1;
+            }
+        }
+
+        (fixtures > 0).then(|| Self {
+            fixtures,
+            total: MetricDelta::new(baseline, candidate),
+            geometric_mean_ratio: (ratios > 0).then(|| (log_ratio_sum / ratios as f64).exp()),
+        })
+    }
+}
+
+/// Aggregate view of an [`ExecutionComparison`].
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ComparisonSummary {
+    /// Number of fixtures present in both folders.
+    pub matched: usize,
+    /// Number of fixtures whose execution went from success to crashed.
+    pub crash_regressions: usize,
+    /// Number of fixtures whose execution went from crashed to success.
+    pub crash_fixes: usize,
+    /// Number of fixtures whose output went from matched to mismatched.
+    pub output_regressions: usize,
+    /// Aggregate total cycles delta.
+    pub total_num_cycles: Option<AggregateDelta>,
+    /// Aggregate execution duration delta in milliseconds.
+    pub execution_duration_ms: Option<AggregateDelta>,
+    /// Aggregate per-region cycle deltas.
+    pub region_cycles: BTreeMap<String, AggregateDelta>,
+}
+
+/// Comparison of the execution metrics in two metrics folders.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ExecutionComparison {
+    /// Fixtures present in both folders.
+    pub fixtures: Vec<FixtureComparison>,
+    /// Fixtures only present in the baseline folder.
+    pub only_in_baseline: Vec<RunKey>,
+    /// Fixtures only present in the candidate folder.
+    pub only_in_candidate: Vec<RunKey>,
+    /// Matched fixtures produced by different guest programs.
+    pub provenance_mismatches: Vec<ProvenanceMismatch>,
+    /// Aggregate deltas and regression counts.
+    pub summary: ComparisonSummary,
+}
+
+/// Output format of a rendered comparison.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportFormat {
+    /// Markdown summary and tables.
+    Markdown,
+    /// Pretty-printed JSON.
+    Json,
+    /// Long-format CSV with one row per fixture and metric.
+    Csv,
+}
+
+impl ExecutionComparison {
+    /// Loads both folders and compares their execution metrics.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if either folder cannot be loaded, see [`load_runs`].
+    pub fn from_paths<P: AsRef<Path>, Q: AsRef<Path>>(
+        baseline: P,
+        candidate: Q,
+    ) -> Result<Self, MetricsError> {
+        Ok(Self::new(&load_runs(baseline)?, &load_runs(candidate)?))
+    }
+
+    /// Compares already loaded runs.
+    pub fn new(baseline: &LoadedRuns, candidate: &LoadedRuns) -> Self {
+        let fixtures: Vec<_> = baseline
+            .iter()
+            .filter_map(|(key, baseline_run)| {
+                let candidate_run = candidate.get(key)?;
+                Some(compare_fixture(key, baseline_run, candidate_run))
+            })
+            .collect();
+        let only_in_baseline = missing_keys(baseline, candidate);
+        let only_in_candidate = missing_keys(candidate, baseline);
+        let provenance_mismatches = provenance_mismatches(baseline, candidate);
+
+        let regions: BTreeSet<_> = fixtures
+            .iter()
+            .flat_map(|fixture| fixture.region_cycles.keys().cloned())
+            .collect();
+        let summary = ComparisonSummary {
+            matched: fixtures.len(),
+            crash_regressions: fixtures
+                .iter()
+                .filter(|fixture| fixture.is_crash_regression())
+                .count(),
+            crash_fixes: fixtures
+                .iter()
+                .filter(|fixture| {
+                    matches!(fixture.baseline, ExecutionOutcome::Crashed)
+                        && matches!(fixture.candidate, ExecutionOutcome::Success { .. })
+                })
+                .count(),
+            output_regressions: fixtures
+                .iter()
+                .filter(|fixture| fixture.is_output_regression())
+                .count(),
+            total_num_cycles: AggregateDelta::from_deltas(
+                fixtures.iter().filter_map(|f| f.total_num_cycles.as_ref()),
+            ),
+            execution_duration_ms: AggregateDelta::from_deltas(
+                fixtures
+                    .iter()
+                    .filter_map(|f| f.execution_duration_ms.as_ref()),
+            ),
+            region_cycles: regions
+                .into_iter()
+                .filter_map(|region| {
+                    let aggregate = AggregateDelta::from_deltas(
+                        fixtures.iter().filter_map(|f| f.region_cycles.get(&region)),
+                    )?;
+                    Some((region, aggregate))
+                })
+                .collect(),
+        };
+
+        Self {
+            fixtures,
+            only_in_baseline,
+            only_in_candidate,
+            provenance_mismatches,
+            summary,
+        }
+    }
+
+    /// Returns whether any fixture crashed or lost its output match in the candidate.
+    pub const fn has_regressions(&self) -> bool {
+        self.summary.crash_regressions > 0 || self.summary.output_regressions > 0
+    }
+
+    /// Renders the comparison in the requested format.
+    ///
+    /// # Errors
+    ///
+    /// Returns `MetricsError::Serde` if JSON serialization fails.
+    pub fn render(&self, format: ReportFormat) -> Result<String, MetricsError> {
+        Ok(match format {
+            ReportFormat::Markdown => self.to_markdown(),
+            ReportFormat::Json => serde_json::to_string_pretty(self)?,
+            ReportFormat::Csv => self.to_csv(),
+        })
+    }
+
+    /// Renders a Markdown summary followed by aggregate and per-fixture tables.
+    pub fn to_markdown(&self) -> String {
+        let summary = &self.summary;
+        let mut out = String::from("# Execution Comparison\n\n");
+        let _ = writeln!(out, "- **Matched fixtures:** {}", summary.matched);
+        let _ = writeln!(
+            out,
+            "- **Only in baseline:** {}",
+            self.only_in_baseline.len()
+        );
+        let _ = writeln!(
+            out,
+            "- **Only in candidate:** {}",
+            self.only_in_candidate.len()
+        );
+        let _ = writeln!(
+            out,
+            "- **Crash regressions:** {}",
+            summary.crash_regressions
+        );
+        let _ = writeln!(out, "- **Crash fixes:** {}", summary.crash_fixes);
+        let _ = writeln!(
+            out,
+            "- **Output regressions:** {}",
+            summary.output_regressions
+        );
+        let _ = writeln!(
+            out,
+            "- **Different guests:** {}",
+            self.provenance_mismatches.len()
+        );
+
+        out.push_str("\n## Aggregate\n\n");
+        out.push_str(
+            "| Metric | Fixtures | Baseline total | Candidate total | Change | Geomean ratio |\n",
+        );
+        out.push_str("|---|---:|---:|---:|---:|---:|\n");
+        let aggregates = [
+            ("total_num_cycles", summary.total_num_cycles.as_ref()),
+            (
+                "execution_duration_ms",
+                summary.execution_duration_ms.as_ref(),
+            ),
+        ];
+        let region_aggregates = summary
+            .region_cycles
+            .iter()
+            .map(|(region, aggregate)| (format!("region_cycles.{region}"), Some(aggregate)));
+        let aggregates = aggregates
+            .into_iter()
+            .map(|(metric, aggregate)| (metric.to_string(), aggregate));
+        for (metric, aggregate) in aggregates.chain(region_aggregates) {
+            let Some(aggregate) = aggregate else {
+                continue;
+            };
+            let _ = writeln!(
+                out,
+                "| {} | {} | {} | {} | {} | {} |",
+                escape_markdown(&metric),
+                aggregate.fixtures,
+                format_value(aggregate.total.baseline),
+                format_value(aggregate.total.candidate),
+                format_relative(aggregate.total.relative),
+                aggregate
+                    .geometric_mean_ratio
+                    .map_or_else(|| "-".to_string(), |ratio| format!("{ratio:.3}x")),
+            );
+        }
+
+        let regressions: Vec<_> = self
+            .fixtures
+            .iter()
+            .filter(|f| f.is_crash_regression() || f.is_output_regression())
+            .collect();
+        if !regressions.is_empty() {
+            out.push_str("\n## Regressions\n\n");
+            for fixture in regressions {
+                let _ = writeln!(
+                    out,
+                    "- `{}`: {} -> {}",
+                    fixture.key.label(),
+                    fixture.baseline.label(),
+                    fixture.candidate.label()
+                );
+            }
+        }
+
+        out.push_str("\n## Fixtures\n\n");
+        out.push_str("| Fixture | Baseline | Candidate | Baseline cycles | Candidate cycles | Cycles change | Baseline ms | Candidate ms | Duration change |\n");
+        out.push_str("|---|---|---|---:|---:|---:|---:|---:|---:|\n");
+        for fixture in &self.fixtures {
+            let cycles = fixture.total_num_cycles.as_ref();
+            let duration = fixture.execution_duration_ms.as_ref();
+            let _ = writeln!(
+                out,
+                "| {} | {} | {} | {} | {} | {} | {} | {} | {} |",
+                escape_markdown(&fixture.key.label()),
+                fixture.baseline.label(),
+                fixture.candidate.label(),
+                cycles.map_or_else(|| "-".to_string(), |d| format_value(d.baseline)),
+                cycles.map_or_else(|| "-".to_string(), |d| format_value(d.candidate)),
+                format_relative(cycles.and_then(|d| d.relative)),
+                duration.map_or_else(|| "-".to_string(), |d| format_value(d.baseline)),
+                duration.map_or_else(|| "-".to_string(), |d| format_value(d.candidate)),
+                format_relative(duration.and_then(|d| d.relative)),
+            );
+        }
+
+        if !self.provenance_mismatches.is_empty() {
+            out.push_str("\n## Different Guests\n\n");
+            for mismatch in &self.provenance_mismatches {
+                let _ = writeln!(
+                    out,
+                    "- `{}`: {}",
+                    mismatch.key.label(),
+                    mismatch.fields.join(", ")
+                );
+            }
+        }
+
+        for (title, keys) in [
+            ("Only In Baseline", &self.only_in_baseline),
+            ("Only In Candidate", &self.only_in_candidate),
+        ] {
+            if keys.is_empty() {
+                continue;
+            }
+            let _ = write!(out, "\n## {title}\n\n");
+            for key in keys {
+                let _ = writeln!(out, "- `{}`", key.label());
+            }
+        }
+
+        out
+    }
+
+    /// Renders one CSV row per fixture and metric, including per-region cycles.
+    pub fn to_csv(&self) -> String {
+        let mut out = String::from(
+            "group,name,baseline_status,candidate_status,metric,baseline,candidate,delta,relative\n",
+        );
+        for fixture in &self.fixtures {
+            let metrics = [
+                ("total_num_cycles".to_string(), fixture.total_num_cycles),
+                (
+                    "execution_duration_ms".to_string(),
+                    fixture.execution_duration_ms,
+                ),
+            ];
+            let regions = fixture
+                .region_cycles
+                .iter()
+                .map(|(region, delta)| (format!("region_cycles.{region}"), Some(*delta)));
+            for (metric, delta) in metrics.into_iter().chain(regions) {
+                let (baseline, candidate, change, relative) = delta.map_or_else(
+                    || (String::new(), String::new(), String::new(), String::new()),
+                    |d| {
+                        (
+                            d.baseline.to_string(),
+                            d.candidate.to_string(),
+                            d.delta.to_string(),
+                            d.relative.map(|r| r.to_string()).unwrap_or_default(),
+                        )
+                    },
+                );
+                let _ = writeln!(
+                    out,
+                    "{},{},{},{},{},{},{},{},{}",
+                    csv_field(&fixture.key.group),
+                    csv_field(&fixture.key.name),
+                    fixture.baseline.label(),
+                    fixture.candidate.label(),
+                    csv_field(&metric),
+                    baseline,
+                    candidate,
+                    change,
+                    relative,
+                );
+            }
+        }
+        out
+    }
+}
+
+fn compare_fixture(
+    key: &RunKey,
+    baseline: &BenchmarkRun<serde_json::Value>,
+    candidate: &BenchmarkRun<serde_json::Value>,
+) -> FixtureComparison {
+    let mut comparison = FixtureComparison {
+        key: key.clone(),
+        baseline: ExecutionOutcome::of(baseline.execution.as_ref()),
+        candidate: ExecutionOutcome::of(candidate.execution.as_ref()),
+        total_num_cycles: None,
+        execution_duration_ms: None,
+        region_cycles: BTreeMap::new(),
+    };
+
+    if let (
+        Some(ExecutionMetrics::Success {
+            total_num_cycles: baseline_cycles,
+            region_cycles: baseline_regions,
+            execution_duration: baseline_duration,
+            ..
+        }),
+        Some(ExecutionMetrics::Success {
+            total_num_cycles: candidate_cycles,
+            region_cycles: candidate_regions,
+            execution_duration: candidate_duration,
+            ..
+        }),
+    ) = (&baseline.execution, &candidate.execution)
+    {
+        comparison.total_num_cycles = Some(MetricDelta::new(
+            *baseline_cycles as f64,
+            *candidate_cycles as f64,
+        ));
+        comparison.execution_duration_ms = Some(MetricDelta::new(
+            baseline_duration.as_nanos() as f64 / 1e6,
+            candidate_duration.as_nanos() as f64 / 1e6,
+        ));
+        comparison.region_cycles = baseline_regions
+            .iter()
+            .filter_map(|(region, baseline_cycles)| {
+                let candidate_cycles = candidate_regions.get(region)?;
+                Some((
+                    region.clone(),
+                    MetricDelta::new(*baseline_cycles as f64, *candidate_cycles as f64),
+                ))
+            })
+            .collect();
+    }
+
+    comparison
+}
+
+fn missing_keys(from: &LoadedRuns, other: &LoadedRuns) -> Vec<RunKey> {
+    from.keys()
+        .filter(|key| !other.contains_key(*key))
+        .cloned()
+        .collect()
+}
+
+fn format_value(value: f64) -> String {
+    if value.fract() == 0.0 {
+        format!("{value:.0}")
+    } else {
+        format!("{value:.2}")
+    }
+}
+
+fn format_relative(relative: Option<f64>) -> String {
+    relative.map_or_else(|| "-".to_string(), |r| format!("{:+.2}%", r * 100.0))
+}
+
+pub(crate) fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CrashInfo;
+    use std::{collections::HashMap, time::Duration};
+
+    fn execution_run(
+        name: &str,
+        cycles: u64,
+        output_matched: bool,
+    ) -> BenchmarkRun<serde_json::Value> {
+        BenchmarkRun {
+            name: name.into(),
+            timestamp_completed: chrono::Utc::now(),
+            metadata: serde_json::Value::Null,
+            execution: Some(ExecutionMetrics::Success {
+                output_matched,
+                total_num_cycles: cycles,
+                region_cycles: HashMap::from([("stf".to_string(), cycles / 2)]),
+                execution_duration: Duration::from_millis(cycles / 10),
+                statistics: None,
+            }),
+            proving: None,
+            verification: None,
+            resources: None,
+            provenance: None,
+            profile: None,
+            per_gas: None,
+        }
+    }
+
+    fn crashed_run(name: &str) -> BenchmarkRun<serde_json::Value> {
+        BenchmarkRun {
+            execution: Some(ExecutionMetrics::Crashed(CrashInfo {
+                reason: "boom".into(),
+            })),
+            ..execution_run(name, 0, true)
+        }
+    }
+
+    fn runs(
+        group: &str,
+        runs: impl IntoIterator<Item = BenchmarkRun<serde_json::Value>>,
+    ) -> LoadedRuns {
+        runs.into_iter()
+            .map(|run| {
+                let key = RunKey {
+                    group: group.to_string(),
+                    name: run.name.clone(),
+                };
+                (key, run)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn compares_matched_fixtures_and_reports_regressions() {
+        let baseline = runs(
+            "reth-v1/sp1-v5",
+            [
+                execution_run("a", 1_000, true),
+                execution_run("b", 2_000, true),
+                execution_run("c", 3_000, true),
+                execution_run("only-baseline", 1, true),
+            ],
+        );
+        let candidate = runs(
+            "reth-v1/sp1-v5",
+            [
+                execution_run("a", 500, true),
+                crashed_run("b"),
+                execution_run("c", 3_000, false),
+                execution_run("only-candidate", 1, true),
+            ],
+        );
+
+        let comparison = ExecutionComparison::new(&baseline, &candidate);
+
+        assert_eq!(comparison.summary.matched, 3);
+        assert_eq!(comparison.summary.crash_regressions, 1);
+        assert_eq!(comparison.summary.output_regressions, 1);
+        assert!(comparison.has_regressions());
+        assert_eq!(comparison.only_in_baseline[0].name, "only-baseline");
+        assert_eq!(comparison.only_in_candidate[0].name, "only-candidate");
+
+        let a = &comparison.fixtures[0];
+        let cycles = a.total_num_cycles.expect("cycles delta");
+        assert_eq!(cycles.delta, -500.0);
+        assert_eq!(cycles.relative, Some(-0.5));
+        assert_eq!(a.region_cycles["stf"].candidate, 250.0);
+        assert!(comparison.fixtures[1].total_num_cycles.is_none());
+
+        let aggregate = comparison.summary.total_num_cycles.as_ref().unwrap();
+        assert_eq!(aggregate.fixtures, 2);
+        assert_eq!(aggregate.total.baseline, 4_000.0);
+        assert_eq!(aggregate.total.candidate, 3_500.0);
+    }
+
+    #[test]
+    fn reports_runs_from_different_guests() {
+        let provenance = |elf_sha256: &str| crate::Provenance {
+            zkvm: "sp1".into(),
+            sdk_version: "v5".into(),
+            elf_sha256: elf_sha256.into(),
+            program_vk: "vk".into(),
+            guest_source: crate::GuestSource::LocalPath {
+                path: "/guests".into(),
+            },
+            ere_image_tag: "tag".into(),
+            ere_hosts_commit: "commit".into(),
+        };
+        let with_provenance = |name: &str, elf_sha256: Option<&str>| BenchmarkRun {
+            provenance: elf_sha256.map(provenance),
+            ..execution_run(name, 1_000, true)
+        };
+        let baseline = runs(
+            "",
+            [
+                with_provenance("same", Some("aa")),
+                with_provenance("different", Some("aa")),
+                with_provenance("unrecorded", None),
+            ],
+        );
+        let candidate = runs(
+            "",
+            [
+                with_provenance("same", Some("aa")),
+                with_provenance("different", Some("bb")),
+                with_provenance("unrecorded", Some("bb")),
+            ],
+        );
+
+        let comparison = ExecutionComparison::new(&baseline, &candidate);
+
+        assert_eq!(
+            comparison.provenance_mismatches,
+            [ProvenanceMismatch {
+                key: RunKey {
+                    group: String::new(),
+                    name: "different".into(),
+                },
+                fields: vec!["elf_sha256"],
+            }]
+        );
+        assert!(
+            comparison
+                .to_markdown()
+                .contains("- `different`: elf_sha256")
+        );
+    }
+
+    #[test]
+    fn renders_all_formats() -> Result<(), MetricsError> {
+        let baseline = runs("", [execution_run("fixture,1", 1_000, true)]);
+        let candidate = runs("", [execution_run("fixture,1", 1_100, true)]);
+        let comparison = ExecutionComparison::new(&baseline, &candidate);
+
+        let markdown = comparison.render(ReportFormat::Markdown)?;
+        assert!(markdown.contains("| fixture,1 | success | success | 1000 | 1100 | +10.00% |"));
+
+        let csv = comparison.render(ReportFormat::Csv)?;
+        assert!(
+            csv.contains(",\"fixture,1\",success,success,total_num_cycles,1000,1100,100,0.1\n")
+        );
+        assert!(csv.contains("region_cycles.stf"));
+
+        let json: serde_json::Value =
+            serde_json::from_str(&comparison.render(ReportFormat::Json)?)?;
+        assert_eq!(json["fixtures"][0]["key"]["name"], "fixture,1");
+        assert_eq!(json["summary"]["matched"], 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn loads_runs_by_relative_directory() -> Result<(), MetricsError> {
+        let dir = tempfile::tempdir()?;
+        execution_run("a", 10, true).to_path(dir.path().join("reth-v1/sp1-v5/a.json"))?;
+        execution_run("a", 20, true).to_path(dir.path().join("reth-v1/risc0-v3/a.json"))?;
+        crate::HardwareInfo {
+            cpu_model: "cpu".into(),
+            total_ram_gib: 1,
+            gpus: Vec::new(),
+        }
+        .to_path(dir.path().join("hardware.json"))?;
+
+        let loaded = load_runs(dir.path())?;
+
+        let keys: Vec<_> = loaded.keys().map(RunKey::label).collect();
+        assert_eq!(keys, ["reth-v1/risc0-v3/a", "reth-v1/sp1-v5/a"]);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,643 @@
+//! Per-opcode cycle cost estimates fitted from execution metrics.
+//!
+//! For every metrics group, usually one `<el>-<version>/<zkvm>-<version>/` folder, the cycles of
+//! each successfully executed fixture are modelled as
+//!
+//! ```text
+//! total_num_cycles ≈ base + cycles_per_gas * block_used_gas + Σ cycles(opcode) * opcode_count
+//! ```
+//!
+//! using the `block_used_gas` and `opcode_count` fields of the fixture metadata. The model is fitted
+//! with non-negative least squares (Lawson–Hanson), so every estimate is a cost of at least zero.
+//! Fixtures whose residual exceeds the outlier threshold in units of the root mean squared error
+//! are reported as outliers.
+
+use crate::{
+    BenchmarkRun, ExecutionMetrics, MetricsError,
+    compare::{LoadedRuns, ReportFormat, csv_field},
+    markdown::escape_markdown,
+};
+use serde_derive::Serialize;
+use std::{collections::BTreeMap, fmt::Write as _};
+
+/// Default outlier threshold in units of the root mean squared error.
+pub const DEFAULT_OUTLIER_THRESHOLD: f64 = 3.0;
+
+/// Relative ridge added to the normal equations so that collinear opcode counts stay solvable.
+const RIDGE: f64 = 1e-9;
+
+/// Cycle cost models of every group in a metrics folder.
+#[derive(Debug, Clone, Serialize)]
+pub struct CostModelReport {
+    /// Residual threshold above which a fixture is an outlier, in units of the RMSE.
+    pub outlier_threshold: f64,
+    /// One model per group with at least two usable fixtures, sorted by group.
+    pub groups: Vec<GroupCostModel>,
+}
+
+/// Cycle cost model fitted to the fixtures of one group.
+#[derive(Debug, Clone, Serialize)]
+pub struct GroupCostModel {
+    /// Directory of the runs relative to the metrics folder root.
+    pub group: String,
+    /// Fixtures used for the fit.
+    pub fixtures: usize,
+    /// Runs left out because they did not execute successfully or lack opcode counts or gas used.
+    pub skipped: usize,
+    /// Estimated fixed cycles per block.
+    pub base_cycles: f64,
+    /// Estimated cycles per unit of gas used, on top of the opcode costs.
+    pub cycles_per_gas: f64,
+    /// Estimated cycles per executed opcode, highest first.
+    pub opcode_costs: Vec<OpcodeCycleCost>,
+    /// Coefficient of determination of the fit.
+    pub r_squared: f64,
+    /// Root mean squared error of the fit, in cycles.
+    pub rmse: f64,
+    /// Residual of every fixture, largest absolute residual first.
+    pub residuals: Vec<FixtureResidual>,
+    /// Names of the fixtures whose residual exceeds the outlier threshold and one cycle.
+    pub outliers: Vec<String>,
+}
+
+/// Estimated cycle cost of one opcode.
+#[derive(Debug, Clone, Serialize)]
+pub struct OpcodeCycleCost {
+    /// Opcode name, as in the fixture's `opcode_count`.
+    pub opcode: String,
+    /// Estimated cycles per execution of the opcode.
+    pub cycles: f64,
+    /// Number of fixtures executing the opcode at least once.
+    pub fixtures: usize,
+}
+
+/// Difference between the measured and the modelled cycles of one fixture.
+#[derive(Debug, Clone, Serialize)]
+pub struct FixtureResidual {
+    /// Fixture name.
+    pub name: String,
+    /// Measured cycles.
+    pub actual_cycles: u64,
+    /// Cycles predicted by the model.
+    pub predicted_cycles: f64,
+    /// `actual_cycles - predicted_cycles`.
+    pub residual: f64,
+    /// Residual in units of the RMSE.
+    pub standardized: f64,
+}
+
+/// A fixture reduced to the model inputs.
+struct Observation {
+    name: String,
+    cycles: u64,
+    gas: u64,
+    opcode_count: BTreeMap<String, u64>,
+}
+
+impl Observation {
+    fn from_run(run: &BenchmarkRun<serde_json::Value>) -> Option<Self> {
+        let Some(ExecutionMetrics::Success {
+            total_num_cycles, ..
+        }) = &run.execution
+        else {
+            return None;
+        };
+        let gas = run.metadata.get("block_used_gas")?.as_u64()?;
+        // Fixtures predating per-block opcode counts carry an empty `opcode_count`.
+        let opcode_count = run
+            .metadata
+            .get("opcode_count")?
+            .as_object()
+            .filter(|counts| !counts.is_empty())?
+            .iter()
+            .map(|(opcode, count)| Some((opcode.clone(), count.as_u64()?)))
+            .collect::<Option<_>>()?;
+        Some(Self {
+            name: run.name.clone(),
+            cycles: *total_num_cycles,
+            gas,
+            opcode_count,
+        })
+    }
+}
+
+impl CostModelReport {
+    /// Fits a cost model to each group of `runs`.
+    ///
+    /// Groups with fewer than two usable fixtures are left out.
+    pub fn new(runs: &LoadedRuns, outlier_threshold: f64) -> Self {
+        let mut groups: BTreeMap<&str, (Vec<Observation>, usize)> = BTreeMap::new();
+        for (key, run) in runs {
+            let (observations, skipped) = groups.entry(key.group.as_str()).or_default();
+            match Observation::from_run(run) {
+                Some(observation) => observations.push(observation),
+                None => *skipped += 1,
+            }
+        }
+
+        let groups = groups
+            .into_iter()
+            .filter(|(_, (observations, _))| observations.len() >= 2)
+            .map(|(group, (observations, skipped))| {
+                GroupCostModel::fit(group, &observations, skipped, outlier_threshold)
+            })
+            .collect();
+        Self {
+            outlier_threshold,
+            groups,
+        }
+    }
+
+    /// Renders the report in the requested format.
+    ///
+    /// # Errors
+    ///
+    /// Returns `MetricsError::Serde` if JSON serialization fails.
+    pub fn render(&self, format: ReportFormat) -> Result<String, MetricsError> {
+        Ok(match format {
+            ReportFormat::Markdown => self.to_markdown(),
+            ReportFormat::Json => serde_json::to_string_pretty(self)?,
+            ReportFormat::Csv => self.to_csv(),
+        })
+    }
+
+    /// Renders the fit quality, estimated costs and outliers of each group.
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::from("# Opcode Cycle Cost Model\n\n");
+        if self.groups.is_empty() {
+            out.push_str(
+                "No group has at least two successful runs with `opcode_count` and `block_used_gas` metadata.\n",
+            );
+            return out;
+        }
+
+        for model in &self.groups {
+            let group = if model.group.is_empty() {
+                "(root)"
+            } else {
+                model.group.as_str()
+            };
+            let _ = writeln!(out, "## {group}\n");
+            let _ = writeln!(out, "- **Fixtures:** {}", model.fixtures);
+            let _ = writeln!(out, "- **Skipped runs:** {}", model.skipped);
+            let _ = writeln!(out, "- **R²:** {:.4}", model.r_squared);
+            let _ = writeln!(out, "- **RMSE:** {:.0} cycles", model.rmse);
+            let _ = writeln!(out, "- **Base cycles per block:** {:.0}", model.base_cycles);
+            let _ = writeln!(out, "- **Cycles per gas:** {:.4}", model.cycles_per_gas);
+            let terms = model.opcode_costs.len() + 2;
+            if model.fixtures <= terms {
+                let _ = writeln!(
+                    out,
+                    "- **Note:** {} fixtures for {terms} terms, the estimates are not unique",
+                    model.fixtures
+                );
+            }
+
+            out.push_str("\n| Opcode | Cycles | Fixtures |\n|--------|--------|----------|\n");
+            for cost in model.opcode_costs.iter().filter(|cost| cost.cycles > 0.0) {
+                let _ = writeln!(
+                    out,
+                    "| {} | {:.2} | {} |",
+                    cost.opcode, cost.cycles, cost.fixtures
+                );
+            }
+            let zero = model
+                .opcode_costs
+                .iter()
+                .filter(|cost| cost.cycles <= 0.0)
+                .count();
+            if zero > 0 {
+                let _ = writeln!(
+                    out,
+                    "\n{zero} opcode(s) have an estimated cost of zero cycles."
+                );
+            }
+
+            if !model.outliers.is_empty() {
+                out.push_str(
+                    "\n### Outliers\n\n| Fixture | Actual | Predicted | Residual | σ |\n|---------|--------|-----------|----------|---|\n",
+                );
+                for residual in model
+                    .residuals
+                    .iter()
+                    .filter(|residual| model.outliers.contains(&residual.name))
+                {
+                    let _ = writeln!(
+                        out,
+                        "| {} | {} | {:.0} | {:+.0} | {:+.2} |",
+                        escape_markdown(&residual.name),
+                        residual.actual_cycles,
+                        residual.predicted_cycles,
+                        residual.residual,
+                        residual.standardized
+                    );
+                }
+            }
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Renders one row per group and model term: `base`, `gas` and each opcode.
+    pub fn to_csv(&self) -> String {
+        let mut out = String::from("group,term,cycles,fixtures\n");
+        for model in &self.groups {
+            let group = csv_field(&model.group);
+            let _ = writeln!(out, "{group},base,{},{}", model.base_cycles, model.fixtures);
+            let _ = writeln!(
+                out,
+                "{group},gas,{},{}",
+                model.cycles_per_gas, model.fixtures
+            );
+            for cost in &model.opcode_costs {
+                let _ = writeln!(
+                    out,
+                    "{group},{},{},{}",
+                    csv_field(&cost.opcode),
+                    cost.cycles,
+                    cost.fixtures
+                );
+            }
+        }
+        out
+    }
+}
+
+impl GroupCostModel {
+    fn fit(
+        group: &str,
+        observations: &[Observation],
+        skipped: usize,
+        outlier_threshold: f64,
+    ) -> Self {
+        let mut opcode_fixtures: BTreeMap<&str, usize> = BTreeMap::new();
+        for observation in observations {
+            for (opcode, count) in &observation.opcode_count {
+                if *count > 0 {
+                    *opcode_fixtures.entry(opcode).or_default() += 1;
+                }
+            }
+        }
+        let opcodes: Vec<&str> = opcode_fixtures.keys().copied().collect();
+
+        // Columns: base, gas, then one per opcode.
+        let rows: Vec<Vec<f64>> = observations
+            .iter()
+            .map(|observation| {
+                let mut row = vec![1.0, observation.gas as f64];
+                row.extend(opcodes.iter().map(|opcode| {
+                    observation.opcode_count.get(*opcode).copied().unwrap_or(0) as f64
+                }));
+                row
+            })
+            .collect();
+        let targets: Vec<f64> = observations.iter().map(|o| o.cycles as f64).collect();
+        let coefficients = nnls(&rows, &targets);
+
+        let predictions: Vec<f64> = rows
+            .iter()
+            .map(|row| row.iter().zip(&coefficients).map(|(x, c)| x * c).sum())
+            .collect();
+        let n = targets.len() as f64;
+        let mean = targets.iter().sum::<f64>() / n;
+        let ss_res: f64 = targets
+            .iter()
+            .zip(&predictions)
+            .map(|(actual, predicted)| (actual - predicted).powi(2))
+            .sum();
+        let ss_tot: f64 = targets.iter().map(|actual| (actual - mean).powi(2)).sum();
+        let rmse = (ss_res / n).sqrt();
+
+        let mut residuals: Vec<FixtureResidual> = observations
+            .iter()
+            .zip(&predictions)
+            .map(|(observation, predicted)| {
+                let residual = observation.cycles as f64 - predicted;
+                FixtureResidual {
+                    name: observation.name.clone(),
+                    actual_cycles: observation.cycles,
+                    predicted_cycles: *predicted,
+                    residual,
+                    standardized: if rmse > 0.0 { residual / rmse } else { 0.0 },
+                }
+            })
+            .collect();
+        residuals.sort_by(|a, b| b.residual.abs().total_cmp(&a.residual.abs()));
+        let outliers = residuals
+            .iter()
+            // Sub-cycle residuals are rounding noise of a near-exact fit.
+            .filter(|residual| {
+                residual.residual.abs() >= 1.0 && residual.standardized.abs() > outlier_threshold
+            })
+            .map(|residual| residual.name.clone())
+            .collect();
+
+        let mut opcode_costs: Vec<OpcodeCycleCost> = opcodes
+            .iter()
+            .zip(&coefficients[2..])
+            .map(|(opcode, cycles)| OpcodeCycleCost {
+                opcode: (*opcode).to_string(),
+                cycles: *cycles,
+                fixtures: opcode_fixtures[opcode],
+            })
+            .collect();
+        opcode_costs.sort_by(|a, b| b.cycles.total_cmp(&a.cycles));
+
+        Self {
+            group: group.to_string(),
+            fixtures: observations.len(),
+            skipped,
+            base_cycles: coefficients[0],
+            cycles_per_gas: coefficients[1],
+            opcode_costs,
+            r_squared: if ss_tot > 0.0 {
+                1.0 - ss_res / ss_tot
+            } else {
+                1.0
+            },
+            rmse,
+            residuals,
+            outliers,
+        }
+    }
+}
+
+/// Solves `min ‖A x - b‖²` subject to `x ≥ 0` with the Lawson–Hanson active set method.
+///
+/// Columns are scaled to unit norm first, so opcode counts and gas of very different magnitudes
+/// converge alike. All-zero columns get a coefficient of zero.
+fn nnls(rows: &[Vec<f64>], targets: &[f64]) -> Vec<f64> {
+    let columns = rows.first().map_or(0, Vec::len);
+    let norms: Vec<f64> = (0..columns)
+        .map(|j| rows.iter().map(|row| row[j] * row[j]).sum::<f64>().sqrt())
+        .collect();
+    let scaled = |row: &[f64], j: usize| {
+        if norms[j] > 0.0 {
+            row[j] / norms[j]
+        } else {
+            0.0
+        }
+    };
+
+    // Normal equations of the scaled problem.
+    let mut ata = vec![vec![0.0; columns]; columns];
+    let mut atb = vec![0.0; columns];
+    for (row, target) in rows.iter().zip(targets) {
+        let row: Vec<f64> = (0..columns).map(|j| scaled(row, j)).collect();
+        for ((xi, ata_row), atb) in row.iter().zip(&mut ata).zip(&mut atb) {
+            if *xi == 0.0 {
+                continue;
+            }
+            *atb += xi * target;
+            for (ata, xj) in ata_row.iter_mut().zip(&row) {
+                *ata += xi * xj;
+            }
+        }
+    }
+    for (i, row) in ata.iter_mut().enumerate() {
+        row[i] += RIDGE;
+    }
+
+    let tolerance = 1e-10 * atb.iter().fold(1.0_f64, |max, v| max.max(v.abs()));
+    let usable: Vec<bool> = norms.iter().map(|norm| *norm > 0.0).collect();
+    let mut x = vec![0.0; columns];
+    let mut passive = vec![false; columns];
+
+    for _ in 0..3 * columns.max(1) {
+        let gradient = gradient(&ata, &atb, &x);
+        let Some(entering) = (0..columns)
+            .filter(|&j| usable[j] && !passive[j] && gradient[j] > tolerance)
+            .max_by(|&a, &b| gradient[a].total_cmp(&gradient[b]))
+        else {
+            break;
+        };
+        passive[entering] = true;
+
+        loop {
+            let z = solve_passive(&ata, &atb, &passive);
+            if (0..columns).all(|j| !passive[j] || z[j] > 0.0) {
+                x = z;
+                break;
+            }
+            let alpha = (0..columns)
+                .filter(|&j| passive[j] && z[j] <= 0.0)
+                .map(|j| x[j] / (x[j] - z[j]))
+                .fold(f64::INFINITY, f64::min);
+            for j in 0..columns {
+                x[j] += alpha * (z[j] - x[j]);
+                if passive[j] && x[j] <= tolerance {
+                    passive[j] = false;
+                    x[j] = 0.0;
+                }
+            }
+            if !passive.iter().any(|p| *p) {
+                break;
+            }
+        }
+    }
+
+    x.iter()
+        .zip(&norms)
+        .map(|(x, norm)| if *norm > 0.0 { x / norm } else { 0.0 })
+        .collect()
+}
+
+fn gradient(ata: &[Vec<f64>], atb: &[f64], x: &[f64]) -> Vec<f64> {
+    ata.iter()
+        .zip(atb)
+        .map(|(row, b)| b - row.iter().zip(x).map(|(a, x)| a * x).sum::<f64>())
+        .collect()
+}
+
+/// Solves the normal equations restricted to the passive columns, leaving the others at zero.
+fn solve_passive(ata: &[Vec<f64>], atb: &[f64], passive: &[bool]) -> Vec<f64> {
+    let indices: Vec<usize> = (0..atb.len()).filter(|&j| passive[j]).collect();
+    let mut system: Vec<Vec<f64>> = indices
+        .iter()
+        .map(|&i| {
+            let mut row: Vec<f64> = indices.iter().map(|&j| ata[i][j]).collect();
+            row.push(atb[i]);
+            row
+        })
+        .collect();
+    let solution = gaussian_elimination(&mut system);
+
+    let mut z = vec![0.0; atb.len()];
+    for (index, value) in indices.into_iter().zip(solution) {
+        z[index] = value;
+    }
+    z
+}
+
+/// Solves the augmented system `[M | v]` in place with partial pivoting.
+fn gaussian_elimination(system: &mut [Vec<f64>]) -> Vec<f64> {
+    let n = system.len();
+    for column in 0..n {
+        let pivot = (column..n)
+            .max_by(|&a, &b| system[a][column].abs().total_cmp(&system[b][column].abs()))
+            .unwrap_or(column);
+        system.swap(column, pivot);
+        let pivot_value = system[column][column];
+        if pivot_value.abs() < f64::MIN_POSITIVE {
+            continue;
+        }
+        let (pivot_rows, rows) = system.split_at_mut(column + 1);
+        let pivot_row = &pivot_rows[column];
+        for row in rows {
+            let factor = row[column] / pivot_value;
+            if factor == 0.0 {
+                continue;
+            }
+            for (value, pivot) in row[column..].iter_mut().zip(&pivot_row[column..]) {
+                *value -= factor * pivot;
+            }
+        }
+    }
+
+    let mut solution = vec![0.0; n];
+    for row in (0..n).rev() {
+        let pivot_value = system[row][row];
+        if pivot_value.abs() < f64::MIN_POSITIVE {
+            continue;
+        }
+        let known: f64 = (row + 1..n).map(|k| system[row][k] * solution[k]).sum();
+        solution[row] = (system[row][n] - known) / pivot_value;
+    }
+    solution
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compare::RunKey;
+    use std::{collections::HashMap, time::Duration};
+
+    fn run(
+        name: &str,
+        cycles: u64,
+        gas: u64,
+        opcodes: &[(&str, u64)],
+    ) -> BenchmarkRun<serde_json::Value> {
+        BenchmarkRun {
+            name: name.into(),
+            timestamp_completed: chrono::Utc::now(),
+            metadata: serde_json::json!({
+                "block_used_gas": gas,
+                "opcode_count": opcodes.iter().copied().collect::<BTreeMap<_, _>>(),
+            }),
+            execution: Some(ExecutionMetrics::Success {
+                output_matched: true,
+                total_num_cycles: cycles,
+                region_cycles: HashMap::new(),
+                execution_duration: Duration::from_millis(1),
+                statistics: None,
+            }),
+            proving: None,
+            verification: None,
+            resources: None,
+            provenance: None,
+            profile: None,
+            per_gas: None,
+        }
+    }
+
+    fn loaded(group: &str, runs: Vec<BenchmarkRun<serde_json::Value>>) -> LoadedRuns {
+        runs.into_iter()
+            .map(|run| {
+                let key = RunKey {
+                    group: group.into(),
+                    name: run.name.clone(),
+                };
+                (key, run)
+            })
+            .collect()
+    }
+
+    /// Cycles of a block under base 1000, 2 cycles per gas, ADD 3 and SSTORE 500.
+    fn cycles(gas: u64, add: u64, sstore: u64) -> u64 {
+        1_000 + 2 * gas + 3 * add + 500 * sstore
+    }
+
+    #[test]
+    fn recovers_opcode_costs() {
+        let fixtures = [
+            (21_000, 10, 0),
+            (50_000, 200, 1),
+            (80_000, 30, 4),
+            (30_000, 500, 2),
+            (60_000, 0, 3),
+        ];
+        let runs = fixtures
+            .iter()
+            .enumerate()
+            .map(|(i, &(gas, add, sstore))| {
+                run(
+                    &format!("block{i}"),
+                    cycles(gas, add, sstore),
+                    gas,
+                    &[("ADD", add), ("SSTORE", sstore)],
+                )
+            })
+            .collect();
+        let report = CostModelReport::new(&loaded("reth/sp1", runs), DEFAULT_OUTLIER_THRESHOLD);
+
+        let model = &report.groups[0];
+        assert_eq!(model.fixtures, 5);
+        assert!((model.base_cycles - 1_000.0).abs() < 0.1);
+        assert!((model.cycles_per_gas - 2.0).abs() < 1e-6);
+        assert_eq!(model.opcode_costs[0].opcode, "SSTORE");
+        assert!((model.opcode_costs[0].cycles - 500.0).abs() < 0.01);
+        assert!((model.opcode_costs[1].cycles - 3.0).abs() < 0.01);
+        assert!(model.r_squared > 0.999_999);
+        assert!(model.outliers.is_empty());
+    }
+
+    #[test]
+    fn keeps_costs_non_negative_and_flags_outliers() {
+        // Blocks with more POP are cheaper, which least squares would fit with a negative cost.
+        let mut runs: Vec<_> = (0..12)
+            .map(|i| {
+                let gas = 10_000 * (i + 1);
+                run(
+                    &format!("block{i:02}"),
+                    3 * gas - 100 * i,
+                    gas,
+                    &[("POP", i)],
+                )
+            })
+            .collect();
+        runs.push(run("prover_killer", 5_000_000, 20_000, &[("POP", 0)]));
+        runs.push(run("crashed", 0, 0, &[]));
+        runs.last_mut().unwrap().execution = Some(ExecutionMetrics::Crashed(crate::CrashInfo {
+            reason: "boom".into(),
+        }));
+
+        let report = CostModelReport::new(&loaded("", runs), DEFAULT_OUTLIER_THRESHOLD);
+        let model = &report.groups[0];
+        assert_eq!(model.skipped, 1);
+        assert!(model.opcode_costs.iter().all(|cost| cost.cycles >= 0.0));
+        assert!(model.base_cycles >= 0.0 && model.cycles_per_gas >= 0.0);
+        assert_eq!(model.outliers, ["prover_killer"]);
+        assert_eq!(model.residuals[0].name, "prover_killer");
+
+        let markdown = report.to_markdown();
+        assert!(markdown.contains("## (root)"));
+        assert!(markdown.contains("| prover_killer |"));
+        assert!(
+            report
+                .to_csv()
+                .starts_with("group,term,cycles,fixtures\n,base,")
+        );
+    }
+
+    #[test]
+    fn nnls_matches_least_squares_when_unconstrained() {
+        let rows = vec![vec![1.0, 0.0], vec![0.0, 1.0], vec![1.0, 1.0]];
+        let x = nnls(&rows, &[1.0, 2.0, 3.0]);
+        assert!((x[0] - 1.0).abs() < 1e-6 && (x[1] - 2.0).abs() < 1e-6);
+
+        let x = nnls(&rows, &[-1.0, 2.0, 1.0]);
+        assert_eq!(x[0], 0.0);
+        assert!(x[1] > 0.0);
+    }
+}
@@ -0,0 +1,939 @@
+//! Columnar export of benchmark runs for analysis outside of Rust.
+//!
+//! [`ExportTables`] flattens runs into four tables:
+//!
+//! - `runs`: one row per run with its layout, fixture metadata, execution, proving,
+//!   verification, resource usage, gas normalised and hardware columns.
+//! - `region_cycles`: one row per run and cycle region of a successful execution.
+//! - `opcode_counts`: one row per run and opcode of the fixture's `opcode_count` metadata.
+//! - `export_info`: key-value rows describing the export, including its `schema_version`.
+//!
+//! The long-format tables reference `runs` through `run_id`. Adding, renaming, removing or
+//! retyping a table or column bumps [`EXPORT_SCHEMA_VERSION`].
+//!
+//! Tables are written as CSV files, as Parquet files with the `parquet` feature, or as a `SQLite`
+//! database with the `sqlite` feature.
+
+use crate::{
+    ExecutionMetrics, GasRatio, HardwareInfo, MetricsError, ProvingMetrics, VerificationMetrics,
+    compare::csv_field,
+    gas::gas_normalized_metrics,
+    results::{IndexedRun, ResultsIndex, RunAction, RunStatus},
+};
+use std::{
+    fmt::Write as _,
+    fs,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+/// Version of the exported tables, recorded in `export_info`, in the key-value metadata of
+/// Parquet files and as the `user_version` of `SQLite` databases.
+pub const EXPORT_SCHEMA_VERSION: u32 = 1;
+
+/// Key of the schema version in `export_info` and in Parquet key-value metadata.
+pub const SCHEMA_VERSION_KEY: &str = "schema_version";
+
+/// Values of one column, `None` for missing values.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ColumnValues {
+    /// 64-bit signed integers.
+    Integer(Vec<Option<i64>>),
+    /// 64-bit floats.
+    Real(Vec<Option<f64>>),
+    /// UTF-8 strings.
+    Text(Vec<Option<String>>),
+    /// Booleans.
+    Boolean(Vec<Option<bool>>),
+}
+
+impl ColumnValues {
+    /// Number of values.
+    pub const fn len(&self) -> usize {
+        match self {
+            Self::Integer(values) => values.len(),
+            Self::Real(values) => values.len(),
+            Self::Text(values) => values.len(),
+            Self::Boolean(values) => values.len(),
+        }
+    }
+
+    /// Whether the column has no values.
+    pub const fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Value of `row` as a CSV field, empty when missing.
+    fn csv_field(&self, row: usize) -> String {
+        match self {
+            Self::Integer(values) => values[row].map(|v| v.to_string()),
+            Self::Real(values) => values[row].map(|v| v.to_string()),
+            Self::Text(values) => values[row].as_deref().map(csv_field),
+            Self::Boolean(values) => values[row].map(|v| v.to_string()),
+        }
+        .unwrap_or_default()
+    }
+}
+
+/// A named column.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Column {
+    /// Column name.
+    pub name: &'static str,
+    /// Column values, one per row.
+    pub values: ColumnValues,
+}
+
+/// A named table of equally long columns.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Table {
+    /// Table name, used as file name stem and `SQLite` table name.
+    pub name: &'static str,
+    /// Columns in export order.
+    pub columns: Vec<Column>,
+}
+
+impl Table {
+    /// Number of rows.
+    pub fn num_rows(&self) -> usize {
+        self.columns.first().map_or(0, |column| column.values.len())
+    }
+
+    /// Values of the column called `name`.
+    pub fn column(&self, name: &str) -> Option<&ColumnValues> {
+        self.columns
+            .iter()
+            .find(|column| column.name == name)
+            .map(|column| &column.values)
+    }
+
+    /// Renders the table as CSV with a header row.
+    pub fn to_csv(&self) -> String {
+        let header: Vec<&str> = self.columns.iter().map(|column| column.name).collect();
+        let mut out = header.join(",");
+        out.push('\n');
+        for row in 0..self.num_rows() {
+            let fields: Vec<String> = self
+                .columns
+                .iter()
+                .map(|column| column.values.csv_field(row))
+                .collect();
+            let _ = writeln!(out, "{}", fields.join(","));
+        }
+        out
+    }
+}
+
+/// The `runs`, `region_cycles`, `opcode_counts` and `export_info` tables of a set of runs.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExportTables {
+    /// One row per run.
+    pub runs: Table,
+    /// One row per run and cycle region.
+    pub region_cycles: Table,
+    /// One row per run and executed opcode.
+    pub opcode_counts: Table,
+    /// Key-value description of the export.
+    pub export_info: Table,
+}
+
+impl ExportTables {
+    /// Flattens every run of `index`, with the hardware of its results root.
+    pub fn from_index(index: &ResultsIndex) -> Self {
+        Self::new(index.runs(), index.hardware())
+    }
+
+    /// Flattens `runs`, recorded on `hardware`. Run ids are positions in `runs`.
+    pub fn new<'a>(
+        runs: impl IntoIterator<Item = &'a IndexedRun>,
+        hardware: Option<&HardwareInfo>,
+    ) -> Self {
+        let runs: Vec<&IndexedRun> = runs.into_iter().collect();
+        Self {
+            runs: runs_table(&runs, hardware),
+            region_cycles: region_cycles_table(&runs),
+            opcode_counts: opcode_counts_table(&runs),
+            export_info: export_info_table(),
+        }
+    }
+
+    /// The tables in export order.
+    pub const fn tables(&self) -> [&Table; 4] {
+        [
+            &self.runs,
+            &self.region_cycles,
+            &self.opcode_counts,
+            &self.export_info,
+        ]
+    }
+
+    /// Writes one `<table>.csv` file per table into `dir`, creating it if needed.
+    ///
+    /// Returns the written files.
+    ///
+    /// # Errors
+    ///
+    /// Returns `MetricsError::Io` if the directory or a file cannot be written.
+    pub fn write_csv(&self, dir: &Path) -> Result<Vec<PathBuf>, MetricsError> {
+        fs::create_dir_all(dir)?;
+        self.tables()
+            .into_iter()
+            .map(|table| {
+                let path = dir.join(format!("{}.csv", table.name));
+                fs::write(&path, table.to_csv())?;
+                Ok(path)
+            })
+            .collect()
+    }
+
+    /// Writes one `<table>.parquet` file per table into `dir`, creating it if needed.
+    ///
+    /// Returns the written files.
+    ///
+    /// # Errors
+    ///
+    /// Returns `MetricsError::Io` if the directory or a file cannot be created.
+    /// Returns `MetricsError::Parquet` if encoding a file fails.
+    #[cfg(feature = "parquet")]
+    pub fn write_parquet(&self, dir: &Path) -> Result<Vec<PathBuf>, MetricsError> {
+        fs::create_dir_all(dir)?;
+        self.tables()
+            .into_iter()
+            .map(|table| {
+                let path = dir.join(format!("{}.parquet", table.name));
+                parquet_export::write_table(table, fs::File::create(&path)?)?;
+                Ok(path)
+            })
+            .collect()
+    }
+
+    /// Writes every table into a new `SQLite` database at `path`, replacing an existing file.
+    ///
+    /// The long-format tables are indexed by `run_id`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `MetricsError::Io` if an existing file cannot be removed.
+    /// Returns `MetricsError::Sqlite` if creating or filling the database fails.
+    #[cfg(feature = "sqlite")]
+    pub fn write_sqlite(&self, path: &Path) -> Result<(), MetricsError> {
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)?;
+        }
+        if path.exists() {
+            fs::remove_file(path)?;
+        }
+
+        let mut connection = rusqlite::Connection::open(path)?;
+        let transaction = connection.transaction()?;
+        for table in self.tables() {
+            sqlite_export::insert_table(&transaction, table)?;
+        }
+        transaction.execute_batch(&format!(
+            "CREATE INDEX region_cycles_run_id ON region_cycles (run_id);
+             CREATE INDEX opcode_counts_run_id ON opcode_counts (run_id);
+             PRAGMA user_version = {EXPORT_SCHEMA_VERSION};"
+        ))?;
+        transaction.commit()?;
+        Ok(())
+    }
+}
+
+/// Builds the columns of a table with one row per run.
+struct RunColumns<'a> {
+    runs: &'a [&'a IndexedRun],
+    columns: Vec<Column>,
+}
+
+impl<'a> RunColumns<'a> {
+    const fn new(runs: &'a [&'a IndexedRun]) -> Self {
+        Self {
+            runs,
+            columns: Vec::new(),
+        }
+    }
+
+    fn integer(mut self, name: &'static str, value: impl Fn(&IndexedRun) -> Option<i64>) -> Self {
+        let values = self.runs.iter().map(|run| value(run)).collect();
+        self.columns.push(Column {
+            name,
+            values: ColumnValues::Integer(values),
+        });
+        self
+    }
+
+    fn real(mut self, name: &'static str, value: impl Fn(&IndexedRun) -> Option<f64>) -> Self {
+        let values = self.runs.iter().map(|run| value(run)).collect();
+        self.columns.push(Column {
+            name,
+            values: ColumnValues::Real(values),
+        });
+        self
+    }
+
+    fn text(mut self, name: &'static str, value: impl Fn(&IndexedRun) -> Option<String>) -> Self {
+        let values = self.runs.iter().map(|run| value(run)).collect();
+        self.columns.push(Column {
+            name,
+            values: ColumnValues::Text(values),
+        });
+        self
+    }
+
+    fn boolean(mut self, name: &'static str, value: impl Fn(&IndexedRun) -> Option<bool>) -> Self {
+        let values = self.runs.iter().map(|run| value(run)).collect();
+        self.columns.push(Column {
+            name,
+            values: ColumnValues::Boolean(values),
+        });
+        self
+    }
+
+    fn build(self, name: &'static str) -> Table {
+        Table {
+            name,
+            columns: self.columns,
+        }
+    }
+}
+
+fn runs_table(runs: &[&IndexedRun], hardware: Option<&HardwareInfo>) -> Table {
+    let run_ids = (0..runs.len()).map(|id| i64::try_from(id).ok()).collect();
+    let mut table = RunColumns::new(runs)
+        .text("run_dir", |run| Some(run.group.clone()))
+        .text("el", |run| run.el.clone())
+        .text("el_version", |run| run.el_version.clone())
+        .text("zkvm", |run| run.zkvm.clone())
+        .text("sdk_version", |run| run.sdk_version.clone())
+        .text("fixture", |run| Some(run.run.name.clone()))
+        .text("timestamp_completed", |run| {
+            Some(run.run.timestamp_completed.to_rfc3339())
+        })
+        .text("action", |run| {
+            run.action().map(action_name).map(str::to_string)
+        })
+        .text("status", |run| {
+            run.status().map(status_name).map(str::to_string)
+        })
+        .text("fixture_format", |run| metadata_text(run, "fixture_format"))
+        .text("network", |run| metadata_text(run, "network"))
+        .integer("chain_id", |run| metadata_integer(run, "chain_id"))
+        .integer("block_index", |run| metadata_integer(run, "block_index"))
+        .integer("block_number", |run| metadata_integer(run, "block_number"))
+        .integer("block_used_gas", |run| {
+            metadata_integer(run, "block_used_gas")
+        })
+        .integer("opcode_total", |run| {
+            let counts = run.run.metadata.get("opcode_count")?.as_object()?;
+            counts
+                .values()
+                .map(|count| count.as_i64())
+                .sum::<Option<i64>>()
+                .filter(|_| !counts.is_empty())
+        })
+        .text("execution_status", |run| {
+            run.run.execution.as_ref().map(|execution| {
+                let crashed = matches!(execution, ExecutionMetrics::Crashed(_));
+                crashed_status(crashed)
+            })
+        })
+        .boolean("execution_output_matched", |run| match &run.run.execution {
+            Some(ExecutionMetrics::Success { output_matched, .. }) => Some(*output_matched),
+            _ => None,
+        })
+        .integer("total_num_cycles", |run| match &run.run.execution {
+            Some(ExecutionMetrics::Success {
+                total_num_cycles, ..
+            }) => integer(*total_num_cycles),
+            _ => None,
+        })
+        .real("execution_duration_ms", |run| match &run.run.execution {
+            Some(ExecutionMetrics::Success {
+                execution_duration, ..
+            }) => Some(millis(*execution_duration)),
+            _ => None,
+        })
+        .text("execution_crash_reason", |run| match &run.run.execution {
+            Some(ExecutionMetrics::Crashed(crash)) => Some(crash.reason.clone()),
+            _ => None,
+        })
+        .text("proving_status", |run| {
+            run.run.proving.as_ref().map(|proving| {
+                let crashed = matches!(proving, ProvingMetrics::Crashed(_));
+                crashed_status(crashed)
+            })
+        })
+        .boolean("proving_output_matched", |run| match &run.run.proving {
+            Some(ProvingMetrics::Success { output_matched, .. }) => Some(*output_matched),
+            _ => None,
+        })
+        .integer("proof_size", |run| match &run.run.proving {
+            Some(ProvingMetrics::Success { proof_size, .. }) => integer(*proof_size),
+            _ => None,
+        })
+        .integer("proving_time_ms", |run| match &run.run.proving {
+            Some(ProvingMetrics::Success {
+                proving_time_ms, ..
+            }) => integer(*proving_time_ms),
+            _ => None,
+        })
+        .integer("proving_verification_time_ms", |run| {
+            match &run.run.proving {
+                Some(ProvingMetrics::Success {
+                    verification_time_ms,
+                    ..
+                }) => integer(*verification_time_ms),
+                _ => None,
+            }
+        })
+        .text("proving_crash_reason", |run| match &run.run.proving {
+            Some(ProvingMetrics::Crashed(crash)) => Some(crash.reason.clone()),
+            _ => None,
+        })
+        .text("verification_status", |run| {
+            run.run.verification.as_ref().map(|verification| {
+                let crashed = matches!(verification, VerificationMetrics::Crashed(_));
+                crashed_status(crashed)
+            })
+        })
+        .integer("verification_proof_size", |run| {
+            match &run.run.verification {
+                Some(VerificationMetrics::Success { proof_size, .. }) => integer(*proof_size),
+                _ => None,
+            }
+        })
+        .integer("verification_time_ms", |run| match &run.run.verification {
+            Some(VerificationMetrics::Success {
+                verification_time_ms,
+                ..
+            }) => integer(*verification_time_ms),
+            _ => None,
+        })
+        .text("verification_crash_reason", |run| {
+            match &run.run.verification {
+                Some(VerificationMetrics::Crashed(crash)) => Some(crash.reason.clone()),
+                _ => None,
+            }
+        })
+        .real("cycles_per_gas", |run| {
+            gas_normalized_metrics(&run.run)?
+                .cycles_per_gas
+                .map(GasRatio::get)
+        })
+        .real("proving_ms_per_mgas", |run| {
+            gas_normalized_metrics(&run.run)?
+                .proving_ms_per_mgas
+                .map(GasRatio::get)
+        })
+        .real("proof_bytes_per_mgas", |run| {
+            gas_normalized_metrics(&run.run)?
+                .proof_bytes_per_mgas
+                .map(GasRatio::get)
+        })
+        .integer("peak_memory_bytes", |run| {
+            integer(run.run.resources.as_ref()?.peak_memory_bytes)
+        })
+        .real("wall_time_ms", |run| {
+            Some(millis(run.run.resources.as_ref()?.wall_time))
+        })
+        .real("cpu_time_ms", |run| {
+            Some(millis(run.run.resources.as_ref()?.cpu_time))
+        })
+        .integer("average_cpu_percent", |run| {
+            integer(run.run.resources.as_ref()?.average_cpu_percent)
+        })
+        .text("program_vk", |run| {
+            Some(run.run.provenance.as_ref()?.program_vk.clone())
+        })
+        .text("ere_image_tag", |run| {
+            Some(run.run.provenance.as_ref()?.ere_image_tag.clone())
+        })
+        .text("hardware_cpu_model", |_| {
+            hardware.map(|hardware| hardware.cpu_model.clone())
+        })
+        .integer("hardware_total_ram_gib", |_| {
+            integer(hardware?.total_ram_gib)
+        })
+        .integer("hardware_gpu_count", |_| integer(hardware?.gpus.len()))
+        .text("hardware_gpus", |_| {
+            let gpus: Vec<&str> = hardware?
+                .gpus
+                .iter()
+                .map(|gpu| gpu.model.as_str())
+                .collect();
+            Some(gpus.join("; "))
+        })
+        .build("runs");
+    table.columns.insert(
+        0,
+        Column {
+            name: "run_id",
+            values: ColumnValues::Integer(run_ids),
+        },
+    );
+    table
+}
+
+fn region_cycles_table(runs: &[&IndexedRun]) -> Table {
+    let mut run_ids = Vec::new();
+    let mut regions = Vec::new();
+    let mut cycles = Vec::new();
+    for (run_id, run) in runs.iter().enumerate() {
+        let Some(ExecutionMetrics::Success { region_cycles, .. }) = &run.run.execution else {
+            continue;
+        };
+        let mut region_cycles: Vec<(&String, &u64)> = region_cycles.iter().collect();
+        region_cycles.sort();
+        for (region, region_cycles) in region_cycles {
+            run_ids.push(integer(run_id));
+            regions.push(Some(region.clone()));
+            cycles.push(integer(*region_cycles));
+        }
+    }
+    long_table(
+        "region_cycles",
+        run_ids,
+        ("region", regions),
+        ("cycles", cycles),
+    )
+}
+
+fn opcode_counts_table(runs: &[&IndexedRun]) -> Table {
+    let mut run_ids = Vec::new();
+    let mut opcodes = Vec::new();
+    let mut counts = Vec::new();
+    for (run_id, run) in runs.iter().enumerate() {
+        let Some(opcode_count) = run
+            .run
+            .metadata
+            .get("opcode_count")
+            .and_then(|counts| counts.as_object())
+        else {
+            continue;
+        };
+        let mut opcode_count: Vec<_> = opcode_count.iter().collect();
+        opcode_count.sort_by(|a, b| a.0.cmp(b.0));
+        for (opcode, count) in opcode_count {
+            run_ids.push(integer(run_id));
+            opcodes.push(Some(opcode.clone()));
+            counts.push(count.as_i64());
+        }
+    }
+    long_table(
+        "opcode_counts",
+        run_ids,
+        ("opcode", opcodes),
+        ("count", counts),
+    )
+}
+
+/// A table of `run_id`, a text key and an integer value.
+fn long_table(
+    name: &'static str,
+    run_ids: Vec<Option<i64>>,
+    (key_name, keys): (&'static str, Vec<Option<String>>),
+    (value_name, values): (&'static str, Vec<Option<i64>>),
+) -> Table {
+    Table {
+        name,
+        columns: vec![
+            Column {
+                name: "run_id",
+                values: ColumnValues::Integer(run_ids),
+            },
+            Column {
+                name: key_name,
+                values: ColumnValues::Text(keys),
+            },
+            Column {
+                name: value_name,
+                values: ColumnValues::Integer(values),
+            },
+        ],
+    }
+}
+
+fn export_info_table() -> Table {
+    let info = [
+        (SCHEMA_VERSION_KEY, EXPORT_SCHEMA_VERSION.to_string()),
+        (
+            "zkevm_metrics_version",
+            env!("CARGO_PKG_VERSION").to_string(),
+        ),
+    ];
+    let (keys, values) = info
+        .into_iter()
+        .map(|(key, value)| (Some(key.to_string()), Some(value)))
+        .unzip();
+    Table {
+        name: "export_info",
+        columns: vec![
+            Column {
+                name: "key",
+                values: ColumnValues::Text(keys),
+            },
+            Column {
+                name: "value",
+                values: ColumnValues::Text(values),
+            },
+        ],
+    }
+}
+
+const fn action_name(action: RunAction) -> &'static str {
+    match action {
+        RunAction::Execute => "execute",
+        RunAction::Prove => "prove",
+        RunAction::Verify => "verify",
+    }
+}
+
+const fn status_name(status: RunStatus) -> &'static str {
+    match status {
+        RunStatus::Success => "success",
+        RunStatus::Crashed => "crashed",
+    }
+}
+
+fn crashed_status(crashed: bool) -> String {
+    let status = if crashed {
+        RunStatus::Crashed
+    } else {
+        RunStatus::Success
+    };
+    status_name(status).to_string()
+}
+
+fn integer<T: TryInto<i64>>(value: T) -> Option<i64> {
+    value.try_into().ok()
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1_000.0
+}
+
+fn metadata_integer(run: &IndexedRun, key: &str) -> Option<i64> {
+    run.run.metadata.get(key)?.as_i64()
+}
+
+fn metadata_text(run: &IndexedRun, key: &str) -> Option<String> {
+    run.run.metadata.get(key)?.as_str().map(str::to_string)
+}
+
+#[cfg(feature = "parquet")]
+mod parquet_export {
+    use super::{ColumnValues, EXPORT_SCHEMA_VERSION, SCHEMA_VERSION_KEY, Table};
+    use parquet::{
+        basic::Compression,
+        data_type::{BoolType, ByteArray, ByteArrayType, DataType, DoubleType, Int64Type},
+        errors::ParquetError,
+        file::{
+            properties::WriterProperties,
+            writer::{SerializedColumnWriter, SerializedFileWriter},
+        },
+        format::KeyValue,
+        schema::parser::parse_message_type,
+    };
+    use std::{fmt::Write as _, io::Write, sync::Arc};
+
+    /// Writes `table` as a single row group of optional columns.
+    pub(super) fn write_table<W: Write + Send>(table: &Table, out: W) -> Result<(), ParquetError> {
+        let mut schema = format!("message {} {{\n", table.name);
+        for column in &table.columns {
+            let column_type = match column.values {
+                ColumnValues::Integer(_) => "INT64",
+                ColumnValues::Real(_) => "DOUBLE",
+                ColumnValues::Text(_) => "BYTE_ARRAY",
+                ColumnValues::Boolean(_) => "BOOLEAN",
+            };
+            let annotation = if matches!(column.values, ColumnValues::Text(_)) {
+                " (UTF8)"
+            } else {
+                ""
+            };
+            let _ = writeln!(
+                schema,
+                "  OPTIONAL {column_type} {}{annotation};",
+                column.name
+            );
+        }
+        schema.push('}');
+
+        let properties = WriterProperties::builder()
+            .set_compression(Compression::SNAPPY)
+            .set_key_value_metadata(Some(vec![KeyValue::new(
+                SCHEMA_VERSION_KEY.to_string(),
+                EXPORT_SCHEMA_VERSION.to_string(),
+            )]))
+            .build();
+        let mut writer = SerializedFileWriter::new(
+            out,
+            Arc::new(parse_message_type(&schema)?),
+            Arc::new(properties),
+        )?;
+        let mut row_group = writer.next_row_group()?;
+        for column in &table.columns {
+            let mut column_writer = row_group.next_column()?.ok_or_else(|| {
+                ParquetError::General(format!("schema is missing column {}", column.name))
+            })?;
+            match &column.values {
+                ColumnValues::Integer(values) => {
+                    write_column::<Int64Type, _>(&mut column_writer, values, |v| *v)?;
+                }
+                ColumnValues::Real(values) => {
+                    write_column::<DoubleType, _>(&mut column_writer, values, |v| *v)?;
+                }
+                ColumnValues::Text(values) => {
+                    write_column::<ByteArrayType, _>(&mut column_writer, values, |v| {
+                        ByteArray::from(v.as_str())
+                    })?;
+                }
+                ColumnValues::Boolean(values) => {
+                    write_column::<BoolType, _>(&mut column_writer, values, |v| *v)?;
+                }
+            }
+            column_writer.close()?;
+        }
+        row_group.close()?;
+        writer.close()?;
+        Ok(())
+    }
+
+    fn write_column<T: DataType, V>(
+        writer: &mut SerializedColumnWriter<'_>,
+        values: &[Option<V>],
+        convert: impl Fn(&V) -> T::T,
+    ) -> Result<(), ParquetError> {
+        let definition_levels: Vec<i16> = values
+            .iter()
+            .map(|value| i16::from(value.is_some()))
+            .collect();
+        let present: Vec<T::T> = values.iter().flatten().map(convert).collect();
+        writer
+            .typed::<T>()
+            .write_batch(&present, Some(&definition_levels), None)?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "sqlite")]
+mod sqlite_export {
+    use super::{ColumnValues, Table};
+    use rusqlite::{Connection, params_from_iter, types::Value};
+
+    /// Creates `table` in `connection` and inserts its rows.
+    pub(super) fn insert_table(connection: &Connection, table: &Table) -> rusqlite::Result<()> {
+        let columns: Vec<String> = table
+            .columns
+            .iter()
+            .map(|column| {
+                let column_type = match column.values {
+                    ColumnValues::Integer(_) | ColumnValues::Boolean(_) => "INTEGER",
+                    ColumnValues::Real(_) => "REAL",
+                    ColumnValues::Text(_) => "TEXT",
+                };
+                format!("\"{}\" {column_type}", column.name)
+            })
+            .collect();
+        connection.execute_batch(&format!(
+            "CREATE TABLE \"{}\" ({});",
+            table.name,
+            columns.join(", ")
+        ))?;
+
+        let placeholders = vec!["?"; table.columns.len()].join(", ");
+        let mut statement = connection.prepare(&format!(
+            "INSERT INTO \"{}\" VALUES ({placeholders})",
+            table.name
+        ))?;
+        for row in 0..table.num_rows() {
+            statement.execute(params_from_iter(
+                table
+                    .columns
+                    .iter()
+                    .map(|column| value(&column.values, row)),
+            ))?;
+        }
+        Ok(())
+    }
+
+    fn value(values: &ColumnValues, row: usize) -> Value {
+        match values {
+            ColumnValues::Integer(values) => values[row].map(Value::Integer),
+            ColumnValues::Real(values) => values[row].map(Value::Real),
+            ColumnValues::Text(values) => values[row].clone().map(Value::Text),
+            ColumnValues::Boolean(values) => values[row].map(|v| Value::Integer(i64::from(v))),
+        }
+        .unwrap_or(Value::Null)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BenchmarkRun, CrashInfo, GpuInfo, ProvingMetrics};
+    use std::collections::HashMap;
+
+    fn index_with_runs(root: &Path) -> ResultsIndex {
+        let execution = BenchmarkRun {
+            name: "transfers".into(),
+            timestamp_completed: chrono::Utc::now(),
+            metadata: serde_json::json!({
+                "fixture_format": "eest",
+                "network": "Prague",
+                "chain_id": 1,
+                "block_number": 7,
+                "block_used_gas": 1_000_000,
+                "opcode_count": { "SSTORE": 2, "ADD": 40 },
+            }),
+            execution: Some(ExecutionMetrics::Success {
+                output_matched: true,
+                total_num_cycles: 50_000_000,
+                region_cycles: HashMap::from([("validation".into(), 30), ("setup".into(), 10)]),
+                execution_duration: Duration::from_millis(1_500),
+                statistics: None,
+            }),
+            proving: None,
+            verification: None,
+            resources: None,
+            provenance: None,
+            profile: None,
+            per_gas: None,
+        };
+        let crashed = BenchmarkRun {
+            name: "prover_killer, \"big\"".into(),
+            metadata: serde_json::json!({}),
+            execution: None,
+            proving: Some(ProvingMetrics::Crashed(CrashInfo {
+                reason: "out of memory".into(),
+            })),
+            ..execution.clone()
+        };
+        execution
+            .to_path(root.join("reth-v1.9.0/sp1-v5.2.0/transfers.json"))
+            .unwrap();
+        crashed
+            .to_path(root.join("reth-v1.9.0/sp1-v5.2.0/prover_killer.json"))
+            .unwrap();
+        HardwareInfo {
+            cpu_model: "cpu".into(),
+            total_ram_gib: 64,
+            gpus: vec![GpuInfo {
+                model: "RTX 4090".into(),
+            }],
+        }
+        .to_path(root.join("hardware.json"))
+        .unwrap();
+        ResultsIndex::open(root).unwrap()
+    }
+
+    #[test]
+    fn flattens_runs_into_wide_and_long_tables() {
+        let dir = tempfile::tempdir().unwrap();
+        let tables = ExportTables::from_index(&index_with_runs(dir.path()));
+
+        let runs = &tables.runs;
+        assert_eq!(runs.num_rows(), 2);
+        assert!(runs.columns.iter().all(|c| c.values.len() == 2));
+        assert_eq!(
+            runs.column("fixture"),
+            Some(&ColumnValues::Text(vec![
+                Some("prover_killer, \"big\"".into()),
+                Some("transfers".into())
+            ]))
+        );
+        assert_eq!(
+            runs.column("block_used_gas"),
+            Some(&ColumnValues::Integer(vec![None, Some(1_000_000)]))
+        );
+        assert_eq!(
+            runs.column("opcode_total"),
+            Some(&ColumnValues::Integer(vec![None, Some(42)]))
+        );
+        assert_eq!(
+            runs.column("cycles_per_gas"),
+            Some(&ColumnValues::Real(vec![None, Some(50.0)]))
+        );
+        assert_eq!(
+            runs.column("proving_status"),
+            Some(&ColumnValues::Text(vec![Some("crashed".into()), None]))
+        );
+        assert_eq!(
+            runs.column("hardware_gpus"),
+            Some(&ColumnValues::Text(vec![Some("RTX 4090".into()); 2]))
+        );
+
+        assert_eq!(
+            tables.region_cycles.to_csv(),
+            "run_id,region,cycles\n1,setup,10\n1,validation,30\n"
+        );
+        assert_eq!(
+            tables.opcode_counts.to_csv(),
+            "run_id,opcode,count\n1,ADD,40\n1,SSTORE,2\n"
+        );
+        let csv = runs.to_csv();
+        assert!(csv.starts_with("run_id,run_dir,el,el_version,zkvm,sdk_version,fixture,"));
+        assert!(csv.contains(
+            "\n0,reth-v1.9.0/sp1-v5.2.0,reth,v1.9.0,sp1,v5.2.0,\"prover_killer, \"\"big\"\"\","
+        ));
+
+        let files = tables.write_csv(&dir.path().join("csv")).unwrap();
+        assert_eq!(files.len(), 4);
+        let info = fs::read_to_string(dir.path().join("csv/export_info.csv")).unwrap();
+        assert!(info.contains(&format!("schema_version,{EXPORT_SCHEMA_VERSION}\n")));
+    }
+
+    #[cfg(feature = "parquet")]
+    #[test]
+    fn writes_parquet_files_with_schema_version() {
+        use parquet::file::reader::{FileReader, SerializedFileReader};
+
+        let dir = tempfile::tempdir().unwrap();
+        let tables = ExportTables::from_index(&index_with_runs(dir.path()));
+        tables.write_parquet(&dir.path().join("parquet")).unwrap();
+
+        let file = fs::File::open(dir.path().join("parquet/region_cycles.parquet")).unwrap();
+        let reader = SerializedFileReader::new(file).unwrap();
+        let metadata = reader.metadata().file_metadata();
+        assert_eq!(metadata.num_rows(), 2);
+        assert_eq!(metadata.schema_descr().num_columns(), 3);
+        let version = metadata
+            .key_value_metadata()
+            .and_then(|kv| kv.iter().find(|kv| kv.key == SCHEMA_VERSION_KEY))
+            .and_then(|kv| kv.value.clone());
+        assert_eq!(version, Some(EXPORT_SCHEMA_VERSION.to_string()));
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[test]
+    fn writes_sqlite_database_with_schema_version() {
+        let dir = tempfile::tempdir().unwrap();
+        let tables = ExportTables::from_index(&index_with_runs(dir.path()));
+        let path = dir.path().join("metrics.sqlite");
+        tables.write_sqlite(&path).unwrap();
+        // Exporting again replaces the database instead of failing on existing tables.
+        tables.write_sqlite(&path).unwrap();
+
+        let connection = rusqlite::Connection::open(&path).unwrap();
+        let version: u32 = connection
+            .query_row("PRAGMA user_version", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(version, EXPORT_SCHEMA_VERSION);
+        let cycles: i64 = connection
+            .query_row(
+                "SELECT SUM(cycles) FROM region_cycles JOIN runs USING (run_id) WHERE fixture = 'transfers'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(cycles, 40);
+        let matched: Option<bool> = connection
+            .query_row(
+                "SELECT execution_output_matched FROM runs WHERE run_id = 1",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(matched, Some(true));
+    }
+}
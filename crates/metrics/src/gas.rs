@@ -0,0 +1,402 @@
+//! Ranking of fixtures by their cost per unit of gas.
+//!
+//! A block whose cycles, proving time or proof size per gas is far above the rest of its group
+//! costs the prover much more than its gas suggests, which makes it a prover-killer candidate.
+//! Runs recorded before [`GasNormalizedMetrics`] existed are normalised from their
+//! `block_used_gas` metadata.
+
+use crate::{
+    BenchmarkRun, ExecutionMetrics, GasNormalizedMetrics, GasRatio, MetricsError, ProvingMetrics,
+    compare::{LoadedRuns, ReportFormat, csv_field},
+    markdown::escape_markdown,
+    stats::median,
+};
+use serde_derive::Serialize;
+use std::{collections::BTreeMap, fmt::Write as _};
+
+/// Gas per megagas.
+const GAS_PER_MGAS: f64 = 1_000_000.0;
+
+impl GasNormalizedMetrics {
+    /// Normalises the successful execution and proving metrics by `gas_used`.
+    ///
+    /// Returns `None` when `gas_used` is zero or neither workload succeeded.
+    pub fn new(
+        gas_used: u64,
+        execution: Option<&ExecutionMetrics>,
+        proving: Option<&ProvingMetrics>,
+    ) -> Option<Self> {
+        if gas_used == 0 {
+            return None;
+        }
+        let gas = gas_used as f64;
+        let mgas = gas / GAS_PER_MGAS;
+        let cycles_per_gas = match execution {
+            Some(ExecutionMetrics::Success {
+                total_num_cycles, ..
+            }) => GasRatio::new(*total_num_cycles as f64 / gas),
+            _ => None,
+        };
+        let (proving_ms_per_mgas, proof_bytes_per_mgas) = match proving {
+            Some(ProvingMetrics::Success {
+                proof_size,
+                proving_time_ms,
+                ..
+            }) => (
+                GasRatio::new(*proving_time_ms as f64 / mgas),
+                GasRatio::new(*proof_size as f64 / mgas),
+            ),
+            _ => (None, None),
+        };
+        if cycles_per_gas.is_none() && proving_ms_per_mgas.is_none() {
+            return None;
+        }
+
+        Some(Self {
+            gas_used,
+            cycles_per_gas,
+            proving_ms_per_mgas,
+            proof_bytes_per_mgas,
+        })
+    }
+}
+
+/// Cost per gas used to rank fixtures.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GasMetric {
+    /// Execution cycles per gas.
+    CyclesPerGas,
+    /// Proving milliseconds per million gas.
+    ProvingMsPerMgas,
+    /// Proof bytes per million gas.
+    ProofBytesPerMgas,
+}
+
+impl GasMetric {
+    /// Returns the value of this metric in `metrics`, if recorded.
+    pub fn value(self, metrics: &GasNormalizedMetrics) -> Option<f64> {
+        let value = match self {
+            Self::CyclesPerGas => metrics.cycles_per_gas,
+            Self::ProvingMsPerMgas => metrics.proving_ms_per_mgas,
+            Self::ProofBytesPerMgas => metrics.proof_bytes_per_mgas,
+        };
+        value.map(GasRatio::get)
+    }
+
+    /// Human-readable name of the metric.
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::CyclesPerGas => "cycles/gas",
+            Self::ProvingMsPerMgas => "proving ms/Mgas",
+            Self::ProofBytesPerMgas => "proof bytes/Mgas",
+        }
+    }
+}
+
+/// Fixtures of every group in a metrics folder, ranked by cost per gas.
+#[derive(Debug, Clone, Serialize)]
+pub struct GasCostReport {
+    /// Metric the fixtures are ranked by.
+    pub metric: GasMetric,
+    /// One ranking per group with at least one ranked fixture, sorted by group.
+    pub groups: Vec<GroupGasRanking>,
+}
+
+/// Fixtures of one group, costliest per gas first.
+#[derive(Debug, Clone, Serialize)]
+pub struct GroupGasRanking {
+    /// Directory of the runs relative to the metrics folder root.
+    pub group: String,
+    /// Number of fixtures with a value for the metric.
+    pub fixtures: usize,
+    /// Runs without a value for the metric, because gas used is unknown or the workload did not
+    /// succeed.
+    pub unranked: usize,
+    /// Median of the metric over the group.
+    pub median: f64,
+    /// Costliest fixtures, at most the requested number.
+    pub ranking: Vec<GasCostEntry>,
+}
+
+/// Cost per gas of one fixture.
+#[derive(Debug, Clone, Serialize)]
+pub struct GasCostEntry {
+    /// Fixture name.
+    pub name: String,
+    /// Value of the ranked metric.
+    pub value: f64,
+    /// Value of the ranked metric relative to the group median.
+    pub relative_to_median: f64,
+    /// All gas normalised metrics of the fixture.
+    pub per_gas: GasNormalizedMetrics,
+}
+
+/// Returns the recorded gas normalised metrics of `run`, or derives them from its
+/// `block_used_gas` metadata.
+pub fn gas_normalized_metrics(
+    run: &BenchmarkRun<serde_json::Value>,
+) -> Option<GasNormalizedMetrics> {
+    run.per_gas.or_else(|| {
+        let gas_used = run.metadata.get("block_used_gas")?.as_u64()?;
+        GasNormalizedMetrics::new(gas_used, run.execution.as_ref(), run.proving.as_ref())
+    })
+}
+
+impl GasCostReport {
+    /// Ranks the fixtures of each group of `runs` by `metric`, keeping the `top` costliest.
+    pub fn new(runs: &LoadedRuns, metric: GasMetric, top: usize) -> Self {
+        let mut groups: BTreeMap<&str, (Vec<GasCostEntry>, usize)> = BTreeMap::new();
+        for (key, run) in runs {
+            let (entries, unranked) = groups.entry(key.group.as_str()).or_default();
+            let per_gas = gas_normalized_metrics(run);
+            match per_gas.and_then(|per_gas| Some((metric.value(&per_gas)?, per_gas))) {
+                Some((value, per_gas)) => entries.push(GasCostEntry {
+                    name: run.name.clone(),
+                    value,
+                    relative_to_median: 0.0,
+                    per_gas,
+                }),
+                None => *unranked += 1,
+            }
+        }
+
+        let groups = groups
+            .into_iter()
+            .filter(|(_, (entries, _))| !entries.is_empty())
+            .map(|(group, (mut entries, unranked))| {
+                entries.sort_by(|a, b| b.value.total_cmp(&a.value).then(a.name.cmp(&b.name)));
+                let median = median(entries.iter().map(|entry| entry.value).collect())
+                    .expect("groups without ranked entries are filtered out");
+                let fixtures = entries.len();
+                entries.truncate(top);
+                for entry in &mut entries {
+                    entry.relative_to_median = if median > 0.0 {
+                        entry.value / median
+                    } else {
+                        0.0
+                    };
+                }
+                GroupGasRanking {
+                    group: group.to_string(),
+                    fixtures,
+                    unranked,
+                    median,
+                    ranking: entries,
+                }
+            })
+            .collect();
+        Self { metric, groups }
+    }
+
+    /// Renders the report in the requested format.
+    ///
+    /// # Errors
+    ///
+    /// Returns `MetricsError::Serde` if JSON serialization fails.
+    pub fn render(&self, format: ReportFormat) -> Result<String, MetricsError> {
+        Ok(match format {
+            ReportFormat::Markdown => self.to_markdown(),
+            ReportFormat::Json => serde_json::to_string_pretty(self)?,
+            ReportFormat::Csv => self.to_csv(),
+        })
+    }
+
+    /// Renders one table per group, costliest fixture first.
+    pub fn to_markdown(&self) -> String {
+        let label = self.metric.label();
+        let mut out = format!("# Costliest Fixtures by {label}\n\n");
+        if self.groups.is_empty() {
+            let _ = writeln!(out, "No run has a {label} value.");
+            return out;
+        }
+
+        for ranking in &self.groups {
+            let group = if ranking.group.is_empty() {
+                "(root)"
+            } else {
+                ranking.group.as_str()
+            };
+            let _ = writeln!(out, "## {group}\n");
+            let _ = writeln!(
+                out,
+                "{} ranked fixture(s), {} without a value, median {:.2} {label}.\n",
+                ranking.fixtures, ranking.unranked, ranking.median
+            );
+            let _ = writeln!(
+                out,
+                "| Rank | Fixture | Gas used | {label} | × median |\n|------|---------|----------|---|---|"
+            );
+            for (rank, entry) in ranking.ranking.iter().enumerate() {
+                let _ = writeln!(
+                    out,
+                    "| {} | {} | {} | {:.2} | {:.2} |",
+                    rank + 1,
+                    escape_markdown(&entry.name),
+                    entry.per_gas.gas_used,
+                    entry.value,
+                    entry.relative_to_median
+                );
+            }
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Renders one row per ranked fixture with all of its gas normalised metrics.
+    pub fn to_csv(&self) -> String {
+        let mut out = String::from(
+            "group,rank,name,gas_used,cycles_per_gas,proving_ms_per_mgas,proof_bytes_per_mgas\n",
+        );
+        let optional =
+            |value: Option<GasRatio>| value.map(|v| v.get().to_string()).unwrap_or_default();
+        for ranking in &self.groups {
+            for (rank, entry) in ranking.ranking.iter().enumerate() {
+                let _ = writeln!(
+                    out,
+                    "{},{},{},{},{},{},{}",
+                    csv_field(&ranking.group),
+                    rank + 1,
+                    csv_field(&entry.name),
+                    entry.per_gas.gas_used,
+                    optional(entry.per_gas.cycles_per_gas),
+                    optional(entry.per_gas.proving_ms_per_mgas),
+                    optional(entry.per_gas.proof_bytes_per_mgas)
+                );
+            }
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CrashInfo, compare::RunKey};
+    use std::{collections::HashMap, time::Duration};
+
+    fn run(
+        name: &str,
+        gas: Option<u64>,
+        cycles: u64,
+        proving_time_ms: Option<u128>,
+    ) -> (RunKey, BenchmarkRun<serde_json::Value>) {
+        let key = RunKey {
+            group: "reth-v1.9.0/zisk-v0.15.0".into(),
+            name: name.into(),
+        };
+        let run = BenchmarkRun {
+            name: name.into(),
+            timestamp_completed: chrono::Utc::now(),
+            metadata: serde_json::json!({ "block_used_gas": gas }),
+            execution: Some(ExecutionMetrics::Success {
+                output_matched: true,
+                total_num_cycles: cycles,
+                region_cycles: HashMap::new(),
+                execution_duration: Duration::from_millis(1),
+                statistics: None,
+            }),
+            proving: proving_time_ms.map(|proving_time_ms| ProvingMetrics::Success {
+                output_matched: true,
+                proof_size: 1_000,
+                proving_time_ms,
+                verification_time_ms: 1,
+                statistics: None,
+            }),
+            verification: None,
+            resources: None,
+            provenance: None,
+            profile: None,
+            per_gas: None,
+        };
+        (key, run)
+    }
+
+    #[test]
+    fn gas_normalized_metrics_use_successful_workloads() {
+        let execution = ExecutionMetrics::Success {
+            output_matched: true,
+            total_num_cycles: 3_000_000,
+            region_cycles: HashMap::new(),
+            execution_duration: Duration::from_millis(10),
+            statistics: None,
+        };
+        let proving = ProvingMetrics::Success {
+            output_matched: true,
+            proof_size: 1_500,
+            proving_time_ms: 9_000,
+            verification_time_ms: 10,
+            statistics: None,
+        };
+
+        let metrics = GasNormalizedMetrics::new(1_500_000, Some(&execution), Some(&proving))
+            .expect("gas is known");
+        assert_eq!(metrics.cycles_per_gas, GasRatio::new(2.0));
+        assert_eq!(metrics.proving_ms_per_mgas, GasRatio::new(6_000.0));
+        assert_eq!(metrics.proof_bytes_per_mgas, GasRatio::new(1_000.0));
+
+        let execute_only = GasNormalizedMetrics::new(1_500_000, Some(&execution), None)
+            .expect("execution succeeded");
+        assert_eq!(execute_only.proving_ms_per_mgas, None);
+        let value = serde_json::to_value(execute_only).expect("serialize gas metrics");
+        assert!(value.get("proving_ms_per_mgas").is_none());
+
+        let crashed = ExecutionMetrics::Crashed(CrashInfo {
+            reason: "boom".into(),
+        });
+        assert_eq!(
+            GasNormalizedMetrics::new(1_500_000, Some(&crashed), None),
+            None
+        );
+        assert_eq!(
+            GasNormalizedMetrics::new(0, Some(&execution), Some(&proving)),
+            None
+        );
+    }
+
+    #[test]
+    fn ranks_costliest_fixtures_per_gas_first() {
+        let mut stored = run("stored", Some(1_000_000), 0, None);
+        stored.1.per_gas = Some(GasNormalizedMetrics {
+            gas_used: 1_000_000,
+            cycles_per_gas: GasRatio::new(40.0),
+            proving_ms_per_mgas: None,
+            proof_bytes_per_mgas: None,
+        });
+        let runs: LoadedRuns = [
+            run("transfers", Some(30_000_000), 300_000_000, Some(3_000)),
+            run("keccak_heavy", Some(1_000_000), 100_000_000, Some(20_000)),
+            run("no_gas", None, 1_000, None),
+            stored,
+        ]
+        .into_iter()
+        .collect();
+
+        let report = GasCostReport::new(&runs, GasMetric::CyclesPerGas, 2);
+        let group = &report.groups[0];
+        assert_eq!(group.fixtures, 3);
+        assert_eq!(group.unranked, 1);
+        assert_eq!(group.median, 40.0);
+        let names: Vec<_> = group
+            .ranking
+            .iter()
+            .map(|entry| entry.name.as_str())
+            .collect();
+        assert_eq!(names, ["keccak_heavy", "stored"]);
+        assert_eq!(group.ranking[0].value, 100.0);
+        assert_eq!(group.ranking[0].relative_to_median, 2.5);
+
+        let report = GasCostReport::new(&runs, GasMetric::ProvingMsPerMgas, 10);
+        let group = &report.groups[0];
+        assert_eq!(group.unranked, 2);
+        assert_eq!(group.ranking[0].name, "keccak_heavy");
+        assert_eq!(group.ranking[0].value, 20_000.0);
+        assert_eq!(group.ranking[1].value, 100.0);
+
+        let markdown = report.to_markdown();
+        assert!(markdown.contains("# Costliest Fixtures by proving ms/Mgas"));
+        assert!(markdown.contains("| 1 | keccak_heavy | 1000000 | 20000.00 |"));
+        let csv = report.to_csv();
+        assert!(csv.contains("reth-v1.9.0/zisk-v0.15.0,1,keccak_heavy,1000000,100,20000,1000\n"));
+    }
+}
@@ -0,0 +1,692 @@
+//! Self-contained HTML dashboard of a metrics folder.
+//!
+//! The page needs no external assets: styles and the column sorting script are inlined and charts
+//! are inline SVG. Runs are grouped by execution client and zkVM from their
+//! `<el>-<version>/<zkvm>-<version>/` directory.
+
+use crate::{
+    BenchmarkRun, ExecutionMetrics, HardwareInfo, ProvingMetrics, VerificationMetrics,
+    compare::LoadedRuns, stats::median,
+};
+use std::{collections::BTreeMap, fmt::Write as _, time::Duration};
+
+const STYLE: &str = "body{font-family:system-ui,-apple-system,Segoe UI,sans-serif;line-height:1.5;margin:0;color:#1f2933;background:#f7f9fb}main{max-width:1280px;margin:0 auto;padding:32px 20px 48px}h1{font-size:32px;margin:0 0 8px}h2{font-size:22px;margin-top:40px}h3{font-size:18px}.summary{display:grid;grid-template-columns:repeat(auto-fit,minmax(160px,1fr));gap:12px;margin:24px 0}.metric{background:#fff;border:1px solid #d9e2ec;border-radius:8px;padding:14px}.metric strong{display:block;font-size:24px}.panel{background:#fff;border:1px solid #d9e2ec;border-radius:8px;padding:18px;margin:18px 0;overflow-x:auto}code{font-family:ui-monospace,SFMono-Regular,Menlo,Consolas,monospace}table{width:100%;border-collapse:collapse;background:#fff;border:1px solid #d9e2ec}th,td{text-align:left;border-bottom:1px solid #d9e2ec;padding:8px 10px;vertical-align:top}th{background:#eef2f7;white-space:nowrap}table.sortable th{cursor:pointer;user-select:none}table.sortable th[data-order=asc]::after{content:\" \\25B2\"}table.sortable th[data-order=desc]::after{content:\" \\25BC\"}td.num{text-align:right;white-space:nowrap;font-variant-numeric:tabular-nums}.muted{color:#627d98}.crashed{color:#c62828;font-weight:600}.mismatch{color:#b7791f;font-weight:600}.cell span{display:block}details{margin:8px 0}summary{cursor:pointer;font-weight:600}svg text{font-family:system-ui,-apple-system,Segoe UI,sans-serif;font-size:12px;fill:#1f2933}\n";
+
+const SORT_SCRIPT: &str = "document.querySelectorAll('table.sortable').forEach(function(table){var headers=table.tHead.rows[0].cells;Array.prototype.forEach.call(headers,function(th,column){th.addEventListener('click',function(){var descending=th.dataset.order!=='desc';Array.prototype.forEach.call(headers,function(h){delete h.dataset.order});th.dataset.order=descending?'desc':'asc';var key=function(row){var cell=row.cells[column];if(cell.dataset.value===undefined){return cell.textContent.trim()}return cell.dataset.value===''?null:Number(cell.dataset.value)};var body=table.tBodies[0];Array.prototype.slice.call(body.rows).sort(function(a,b){var x=key(a),y=key(b);if(x===null){return y===null?0:1}if(y===null){return -1}var order=typeof x==='number'&&typeof y==='number'?x-y:String(x).localeCompare(String(y));return descending?-order:order}).forEach(function(row){body.appendChild(row)})})})});\n";
+
+const CHART_WIDTH: f64 = 900.0;
+const CHART_LABEL_WIDTH: f64 = 260.0;
+const CHART_VALUE_WIDTH: f64 = 120.0;
+const CHART_BAR_HEIGHT: f64 = 22.0;
+const CHART_BAR_GAP: f64 = 6.0;
+/// Width available to the bars.
+const CHART_BARS_WIDTH: f64 = CHART_WIDTH - CHART_LABEL_WIDTH - CHART_VALUE_WIDTH;
+const CHART_COLORS: [&str; 6] = [
+    "#0967d2", "#27ab83", "#f0b429", "#e12d39", "#8719e0", "#3ebd93",
+];
+
+/// A crashed workload as `(fixture, workload, reason)`.
+type Crash<'a> = (&'a str, &'static str, &'a str);
+
+/// Runs of one execution client and zkVM pair.
+struct Pair<'a> {
+    el: &'a str,
+    zkvm: &'a str,
+    runs: Vec<&'a BenchmarkRun<serde_json::Value>>,
+}
+
+/// Per-pair counts and medians shown in the summary table and charts.
+#[derive(Default)]
+struct PairSummary {
+    executed: usize,
+    proved: usize,
+    crashed: usize,
+    output_mismatches: usize,
+    median_cycles: Option<u64>,
+    median_execution_time: Option<Duration>,
+    median_proving_ms: Option<u128>,
+}
+
+impl Pair<'_> {
+    fn label(&self) -> String {
+        format!("{} / {}", self.el, self.zkvm)
+    }
+
+    fn summary(&self) -> PairSummary {
+        let mut summary = PairSummary::default();
+        let mut cycles = Vec::new();
+        let mut execution_times = Vec::new();
+        let mut proving_times = Vec::new();
+        for run in &self.runs {
+            match &run.execution {
+                Some(ExecutionMetrics::Success {
+                    output_matched,
+                    total_num_cycles,
+                    execution_duration,
+                    ..
+                }) => {
+                    summary.executed += 1;
+                    summary.output_mismatches += usize::from(!output_matched);
+                    cycles.push(*total_num_cycles as f64);
+                    execution_times.push(execution_duration.as_secs_f64());
+                }
+                Some(ExecutionMetrics::Crashed(_)) => summary.crashed += 1,
+                None => {}
+            }
+            match &run.proving {
+                Some(ProvingMetrics::Success {
+                    output_matched,
+                    proving_time_ms,
+                    ..
+                }) => {
+                    summary.proved += 1;
+                    summary.output_mismatches += usize::from(!output_matched);
+                    proving_times.push(*proving_time_ms as f64);
+                }
+                Some(ProvingMetrics::Crashed(_)) => summary.crashed += 1,
+                None => {}
+            }
+            if matches!(run.verification, Some(VerificationMetrics::Crashed(_))) {
+                summary.crashed += 1;
+            }
+        }
+        summary.median_cycles = median(cycles).map(|cycles| cycles.round() as u64);
+        summary.median_execution_time = median(execution_times).map(Duration::from_secs_f64);
+        summary.median_proving_ms = median(proving_times).map(|ms| ms.round() as u128);
+        summary
+    }
+
+    fn crashes(&self) -> Vec<Crash<'_>> {
+        let mut crashes = Vec::new();
+        for run in &self.runs {
+            if let Some(ExecutionMetrics::Crashed(info)) = &run.execution {
+                crashes.push((run.name.as_str(), "execution", info.reason.as_str()));
+            }
+            if let Some(ProvingMetrics::Crashed(info)) = &run.proving {
+                crashes.push((run.name.as_str(), "proving", info.reason.as_str()));
+            }
+            if let Some(VerificationMetrics::Crashed(info)) = &run.verification {
+                crashes.push((run.name.as_str(), "verification", info.reason.as_str()));
+            }
+        }
+        crashes
+    }
+}
+
+/// Groups runs by the first directory level, the execution client, and the remaining levels, the
+/// zkVM. Runs outside an execution client directory are grouped under `unknown`.
+fn pairs(runs: &LoadedRuns) -> Vec<Pair<'_>> {
+    let mut pairs: BTreeMap<(&str, &str), Vec<_>> = BTreeMap::new();
+    for (key, run) in runs {
+        let (el, zkvm) = key
+            .group
+            .split_once('/')
+            .unwrap_or(("unknown", key.group.as_str()));
+        pairs.entry((el, zkvm)).or_default().push(run);
+    }
+    pairs
+        .into_iter()
+        .map(|((el, zkvm), runs)| Pair { el, zkvm, runs })
+        .collect()
+}
+
+/// Renders a self-contained HTML dashboard of `runs`, titled `title`.
+///
+/// `hardware` is shown when the metrics folder has a `hardware.json`.
+pub fn render_dashboard(
+    runs: &LoadedRuns,
+    hardware: Option<&HardwareInfo>,
+    title: &str,
+    generated_at: chrono::DateTime<chrono::Utc>,
+) -> String {
+    let pairs = pairs(runs);
+    let summaries: Vec<PairSummary> = pairs.iter().map(Pair::summary).collect();
+
+    let mut html = String::new();
+    html.push_str("<!doctype html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n");
+    html.push_str("<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n");
+    html.push_str("<title>");
+    push_escaped(&mut html, title);
+    html.push_str("</title>\n<style>\n");
+    html.push_str(STYLE);
+    html.push_str("</style>\n</head>\n<body>\n<main>\n<h1>");
+    push_escaped(&mut html, title);
+    html.push_str("</h1>\n<p class=\"muted\">Generated at ");
+    push_escaped(
+        &mut html,
+        &generated_at.format("%Y-%m-%d %H:%M:%S UTC").to_string(),
+    );
+    html.push_str(".</p>\n");
+
+    html.push_str("<section class=\"summary\">\n");
+    push_metric(&mut html, "zkVM/EL pairs", pairs.len());
+    push_metric(&mut html, "Results", runs.len());
+    push_metric(
+        &mut html,
+        "Crashes",
+        summaries.iter().map(|s| s.crashed).sum::<usize>(),
+    );
+    push_metric(
+        &mut html,
+        "Output mismatches",
+        summaries.iter().map(|s| s.output_mismatches).sum::<usize>(),
+    );
+    html.push_str("</section>\n");
+
+    push_hardware(&mut html, hardware);
+
+    if pairs.is_empty() {
+        html.push_str(
+            "<h2>No results</h2>\n<p>No benchmark results were found in the metrics folder.</p>\n",
+        );
+    } else {
+        push_summary_table(&mut html, &pairs, &summaries);
+        push_charts(&mut html, &pairs, &summaries);
+        push_el_sections(&mut html, &pairs);
+        push_crashes(&mut html, &pairs);
+    }
+
+    html.push_str("</main>\n<script>\n");
+    html.push_str(SORT_SCRIPT);
+    html.push_str("</script>\n</body>\n</html>\n");
+    html
+}
+
+fn push_hardware(html: &mut String, hardware: Option<&HardwareInfo>) {
+    html.push_str("<section class=\"panel\">\n<h2>Hardware</h2>\n");
+    let Some(hardware) = hardware else {
+        html.push_str("<p class=\"muted\">No <code>hardware.json</code> was found in the metrics folder.</p>\n</section>\n");
+        return;
+    };
+    html.push_str("<table>\n<tbody>\n<tr><th>CPU</th><td>");
+    push_escaped(html, &hardware.cpu_model);
+    html.push_str("</td></tr>\n<tr><th>RAM</th><td>");
+    push_escaped(html, &format!("{} GiB", hardware.total_ram_gib));
+    html.push_str("</td></tr>\n<tr><th>GPUs</th><td>");
+    if hardware.gpus.is_empty() {
+        html.push_str("None detected");
+    } else {
+        let gpus: Vec<&str> = hardware.gpus.iter().map(|gpu| gpu.model.as_str()).collect();
+        push_escaped(html, &gpus.join(", "));
+    }
+    html.push_str("</td></tr>\n</tbody>\n</table>\n</section>\n");
+}
+
+fn push_summary_table(html: &mut String, pairs: &[Pair<'_>], summaries: &[PairSummary]) {
+    html.push_str("<h2>Summary by zkVM and EL</h2>\n<div class=\"panel\">\n<table class=\"sortable\">\n<thead><tr><th>EL</th><th>zkVM</th><th>Results</th><th>Executed</th><th>Proved</th><th>Crashed</th><th>Output mismatches</th><th>Median cycles</th><th>Median execution time</th><th>Median proving time</th></tr></thead>\n<tbody>\n");
+    for (pair, summary) in pairs.iter().zip(summaries) {
+        html.push_str("<tr><td>");
+        push_escaped(html, pair.el);
+        html.push_str("</td><td><strong>");
+        push_escaped(html, pair.zkvm);
+        html.push_str("</strong></td>");
+        push_number_cell(
+            html,
+            Some(pair.runs.len() as f64),
+            &pair.runs.len().to_string(),
+        );
+        push_number_cell(
+            html,
+            Some(summary.executed as f64),
+            &summary.executed.to_string(),
+        );
+        push_number_cell(
+            html,
+            Some(summary.proved as f64),
+            &summary.proved.to_string(),
+        );
+        push_number_cell(
+            html,
+            Some(summary.crashed as f64),
+            &summary.crashed.to_string(),
+        );
+        push_number_cell(
+            html,
+            Some(summary.output_mismatches as f64),
+            &summary.output_mismatches.to_string(),
+        );
+        push_number_cell(
+            html,
+            summary.median_cycles.map(|c| c as f64),
+            &summary
+                .median_cycles
+                .map_or_else(|| "-".into(), format_count),
+        );
+        push_number_cell(
+            html,
+            summary.median_execution_time.map(|d| d.as_secs_f64()),
+            &summary
+                .median_execution_time
+                .map_or_else(|| "-".into(), format_duration),
+        );
+        push_number_cell(
+            html,
+            summary.median_proving_ms.map(|ms| ms as f64),
+            &summary
+                .median_proving_ms
+                .map_or_else(|| "-".into(), |ms| format_duration(millis(ms))),
+        );
+        html.push_str("</tr>\n");
+    }
+    html.push_str("</tbody>\n</table>\n</div>\n");
+}
+
+fn push_charts(html: &mut String, pairs: &[Pair<'_>], summaries: &[PairSummary]) {
+    let cycles: Vec<(String, f64, String)> = pairs
+        .iter()
+        .zip(summaries)
+        .filter_map(|(pair, summary)| {
+            let cycles = summary.median_cycles?;
+            Some((pair.label(), cycles as f64, format_count(cycles)))
+        })
+        .collect();
+    let proving: Vec<(String, f64, String)> = pairs
+        .iter()
+        .zip(summaries)
+        .filter_map(|(pair, summary)| {
+            let ms = summary.median_proving_ms?;
+            Some((pair.label(), ms as f64, format_duration(millis(ms))))
+        })
+        .collect();
+    if cycles.is_empty() && proving.is_empty() {
+        return;
+    }
+
+    html.push_str("<h2>Charts</h2>\n");
+    for (title, bars) in [
+        ("Median execution cycles", cycles),
+        ("Median proving time", proving),
+    ] {
+        if bars.is_empty() {
+            continue;
+        }
+        html.push_str("<div class=\"panel\">\n<h3>");
+        push_escaped(html, title);
+        html.push_str("</h3>\n");
+        push_bar_chart(html, title, &bars);
+        html.push_str("</div>\n");
+    }
+}
+
+/// Draws a horizontal bar chart of `(label, value, formatted value)` bars scaled to the largest
+/// value.
+fn push_bar_chart(html: &mut String, title: &str, bars: &[(String, f64, String)]) {
+    let max = bars.iter().map(|(_, value, _)| *value).fold(0.0, f64::max);
+    let height = (bars.len() as f64).mul_add(CHART_BAR_HEIGHT + CHART_BAR_GAP, CHART_BAR_GAP);
+    let _ = writeln!(
+        html,
+        "<svg role=\"img\" width=\"100%\" viewBox=\"0 0 {CHART_WIDTH} {height}\" xmlns=\"http://www.w3.org/2000/svg\">"
+    );
+    html.push_str("<title>");
+    push_escaped(html, title);
+    html.push_str("</title>\n");
+    for (index, (label, value, formatted)) in bars.iter().enumerate() {
+        let y = (index as f64).mul_add(CHART_BAR_HEIGHT + CHART_BAR_GAP, CHART_BAR_GAP);
+        let width = if max > 0.0 {
+            (value / max * CHART_BARS_WIDTH).max(1.0)
+        } else {
+            1.0
+        };
+        let text_y = y + CHART_BAR_HEIGHT / 2.0 + 4.0;
+        html.push_str("<g><title>");
+        push_escaped(html, &format!("{label}: {formatted}"));
+        let _ = write!(
+            html,
+            "</title><text x=\"{:.1}\" y=\"{text_y:.1}\" text-anchor=\"end\">",
+            CHART_LABEL_WIDTH - 8.0
+        );
+        push_escaped(html, label);
+        let _ = write!(
+            html,
+            "</text><rect x=\"{CHART_LABEL_WIDTH}\" y=\"{y:.1}\" width=\"{width:.1}\" height=\"{CHART_BAR_HEIGHT}\" rx=\"3\" fill=\"{}\"/><text x=\"{:.1}\" y=\"{text_y:.1}\">",
+            CHART_COLORS[index % CHART_COLORS.len()],
+            CHART_LABEL_WIDTH + width + 6.0
+        );
+        push_escaped(html, formatted);
+        html.push_str("</text></g>\n");
+    }
+    html.push_str("</svg>\n");
+}
+
+fn push_el_sections(html: &mut String, pairs: &[Pair<'_>]) {
+    html.push_str("<h2>Results by EL</h2>\n");
+    let mut by_el: BTreeMap<&str, Vec<&Pair<'_>>> = BTreeMap::new();
+    for pair in pairs {
+        by_el.entry(pair.el).or_default().push(pair);
+    }
+
+    for (el, pairs) in by_el {
+        let mut fixtures: BTreeMap<&str, Vec<Option<&BenchmarkRun<serde_json::Value>>>> =
+            BTreeMap::new();
+        for (column, pair) in pairs.iter().enumerate() {
+            for run in &pair.runs {
+                fixtures
+                    .entry(run.name.as_str())
+                    .or_insert_with(|| vec![None; pairs.len()])[column] = Some(run);
+            }
+        }
+
+        html.push_str("<details class=\"panel\" open>\n<summary>");
+        push_escaped(html, el);
+        let _ = write!(html, " ({} fixtures)", fixtures.len());
+        html.push_str("</summary>\n<table class=\"sortable\">\n<thead><tr><th>Fixture</th>");
+        for pair in &pairs {
+            html.push_str("<th>");
+            push_escaped(html, pair.zkvm);
+            html.push_str("</th>");
+        }
+        html.push_str("<th>Avg. execution time</th></tr></thead>\n<tbody>\n");
+
+        for (name, runs) in fixtures {
+            html.push_str("<tr><td><code>");
+            push_escaped(html, name);
+            html.push_str("</code></td>");
+            let mut execution_times = Vec::new();
+            for run in runs {
+                if let Some(ExecutionMetrics::Success {
+                    execution_duration, ..
+                }) = run.and_then(|run| run.execution.as_ref())
+                {
+                    execution_times.push(execution_duration.as_secs_f64());
+                }
+                push_run_cell(html, run);
+            }
+            let average = (!execution_times.is_empty())
+                .then(|| execution_times.iter().sum::<f64>() / execution_times.len() as f64);
+            push_number_cell(
+                html,
+                average,
+                &average.map_or_else(
+                    || "-".into(),
+                    |secs| format_duration(Duration::from_secs_f64(secs)),
+                ),
+            );
+            html.push_str("</tr>\n");
+        }
+        html.push_str("</tbody>\n</table>\n</details>\n");
+    }
+}
+
+/// Renders the cell of one fixture on one zkVM, sorted by cycles for execution results and by
+/// proving time for proving results. Crashes sort above every result.
+fn push_run_cell(html: &mut String, run: Option<&BenchmarkRun<serde_json::Value>>) {
+    let Some(run) = run else {
+        html.push_str("<td class=\"num muted\" data-value=\"\">-</td>");
+        return;
+    };
+
+    let crash = match (&run.execution, &run.proving, &run.verification) {
+        (Some(ExecutionMetrics::Crashed(info)), ..)
+        | (_, Some(ProvingMetrics::Crashed(info)), _)
+        | (.., Some(VerificationMetrics::Crashed(info))) => Some(info),
+        _ => None,
+    };
+    if let Some(info) = crash {
+        html.push_str("<td class=\"num crashed\" data-value=\"1e300\" title=\"");
+        push_escaped_attr(html, &info.reason);
+        html.push_str("\">Crashed</td>");
+        return;
+    }
+
+    let mut lines = Vec::new();
+    let mut sort_value = None;
+    let mut mismatch = false;
+    if let Some(ExecutionMetrics::Success {
+        output_matched,
+        total_num_cycles,
+        execution_duration,
+        ..
+    }) = &run.execution
+    {
+        sort_value = Some(*total_num_cycles as f64);
+        mismatch |= !output_matched;
+        lines.push(format!("{} cycles", format_count(*total_num_cycles)));
+        lines.push(format_duration(*execution_duration));
+    }
+    if let Some(ProvingMetrics::Success {
+        output_matched,
+        proof_size,
+        proving_time_ms,
+        ..
+    }) = &run.proving
+    {
+        sort_value = sort_value.or(Some(*proving_time_ms as f64));
+        mismatch |= !output_matched;
+        lines.push(format!(
+            "proved in {}",
+            format_duration(millis(*proving_time_ms))
+        ));
+        lines.push(format!("{} B proof", format_count(*proof_size as u64)));
+    }
+    if let Some(VerificationMetrics::Success {
+        verification_time_ms,
+        ..
+    }) = &run.verification
+    {
+        lines.push(format!(
+            "verified in {}",
+            format_duration(millis(*verification_time_ms))
+        ));
+    }
+
+    html.push_str("<td class=\"num cell\" data-value=\"");
+    if let Some(value) = sort_value {
+        let _ = write!(html, "{value}");
+    }
+    html.push_str("\">");
+    for line in lines {
+        html.push_str("<span>");
+        push_escaped(html, &line);
+        html.push_str("</span>");
+    }
+    if mismatch {
+        html.push_str("<span class=\"mismatch\">output mismatch</span>");
+    }
+    html.push_str("</td>");
+}
+
+fn push_crashes(html: &mut String, pairs: &[Pair<'_>]) {
+    html.push_str("<h2>Crashes</h2>\n");
+    let crashed: Vec<(&Pair<'_>, Vec<Crash<'_>>)> = pairs
+        .iter()
+        .map(|pair| (pair, pair.crashes()))
+        .filter(|(_, crashes)| !crashes.is_empty())
+        .collect();
+    if crashed.is_empty() {
+        html.push_str("<p>No workload crashed.</p>\n");
+        return;
+    }
+
+    for (pair, crashes) in crashed {
+        html.push_str("<details class=\"panel\">\n<summary>");
+        push_escaped(html, &pair.label());
+        let _ = write!(html, " ({} crashes)", crashes.len());
+        html.push_str("</summary>\n<table>\n<thead><tr><th>Fixture</th><th>Workload</th><th>Reason</th></tr></thead>\n<tbody>\n");
+        for (fixture, workload, reason) in crashes {
+            html.push_str("<tr><td><code>");
+            push_escaped(html, fixture);
+            html.push_str("</code></td><td>");
+            push_escaped(html, workload);
+            html.push_str("</td><td><code>");
+            push_escaped(html, reason);
+            html.push_str("</code></td></tr>\n");
+        }
+        html.push_str("</tbody>\n</table>\n</details>\n");
+    }
+}
+
+fn push_metric<T>(html: &mut String, label: &str, value: T)
+where
+    T: std::fmt::Display,
+{
+    html.push_str("<div class=\"metric\"><span>");
+    push_escaped(html, label);
+    html.push_str("</span><strong>");
+    push_escaped(html, &value.to_string());
+    html.push_str("</strong></div>\n");
+}
+
+/// Pushes a right-aligned cell sorted by `value`, with missing values sorted last.
+fn push_number_cell(html: &mut String, value: Option<f64>, text: &str) {
+    html.push_str("<td class=\"num\" data-value=\"");
+    if let Some(value) = value {
+        let _ = write!(html, "{value}");
+    }
+    html.push_str("\">");
+    push_escaped(html, text);
+    html.push_str("</td>");
+}
+
+fn push_escaped_attr(out: &mut String, input: &str) {
+    push_escaped(out, input);
+}
+
+fn push_escaped(out: &mut String, input: &str) {
+    for ch in input.chars() {
+        match ch {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(ch),
+        }
+    }
+}
+
+const fn millis(ms: u128) -> Duration {
+    Duration::from_millis(ms as u64)
+}
+
+/// Formats `value` with thousands separators.
+fn format_count(value: u64) -> String {
+    let digits = value.to_string();
+    let mut formatted = String::with_capacity(digits.len() + digits.len() / 3);
+    for (index, digit) in digits.chars().enumerate() {
+        if index > 0 && (digits.len() - index).is_multiple_of(3) {
+            formatted.push(',');
+        }
+        formatted.push(digit);
+    }
+    formatted
+}
+
+fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs_f64();
+    if seconds < 1.0 {
+        format!("{:.1}ms", seconds * 1000.0)
+    } else if seconds < 60.0 {
+        format!("{seconds:.2}s")
+    } else if seconds < 3600.0 {
+        format!("{:.1}m", seconds / 60.0)
+    } else {
+        format!("{:.1}h", seconds / 3600.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CrashInfo, GpuInfo, compare::RunKey};
+    use std::collections::HashMap;
+
+    fn run(
+        group: &str,
+        name: &str,
+        execution: ExecutionMetrics,
+    ) -> (RunKey, BenchmarkRun<serde_json::Value>) {
+        let key = RunKey {
+            group: group.into(),
+            name: name.into(),
+        };
+        let run = BenchmarkRun {
+            name: name.into(),
+            timestamp_completed: chrono::Utc::now(),
+            metadata: serde_json::Value::Null,
+            execution: Some(execution),
+            proving: None,
+            verification: None,
+            resources: None,
+            provenance: None,
+            profile: None,
+            per_gas: None,
+        };
+        (key, run)
+    }
+
+    fn success(cycles: u64, millis: u64) -> ExecutionMetrics {
+        ExecutionMetrics::Success {
+            output_matched: true,
+            total_num_cycles: cycles,
+            region_cycles: HashMap::new(),
+            execution_duration: Duration::from_millis(millis),
+            statistics: None,
+        }
+    }
+
+    #[test]
+    fn renders_tables_charts_hardware_and_crashes() {
+        let runs: LoadedRuns = [
+            run(
+                "reth-v1.9.0/sp1-v5.2.1",
+                "block_<1>",
+                success(1_234_567, 1_500),
+            ),
+            run("reth-v1.9.0/sp1-v5.2.1", "block_2", success(2_000_000, 500)),
+            run(
+                "reth-v1.9.0/zisk-v0.15.0",
+                "block_<1>",
+                success(900_000, 250),
+            ),
+            run(
+                "reth-v1.9.0/zisk-v0.15.0",
+                "block_2",
+                ExecutionMetrics::Crashed(CrashInfo {
+                    reason: "panicked at \"oom\"".into(),
+                }),
+            ),
+        ]
+        .into_iter()
+        .collect();
+        let hardware = HardwareInfo {
+            cpu_model: "AMD EPYC & co".into(),
+            total_ram_gib: 256,
+            gpus: vec![GpuInfo {
+                model: "NVIDIA RTX 5090".into(),
+            }],
+        };
+
+        let html = render_dashboard(&runs, Some(&hardware), "zkEVM <bench>", chrono::Utc::now());
+        assert!(html.starts_with("<!doctype html>"));
+        assert!(html.contains("<title>zkEVM &lt;bench&gt;</title>"));
+        assert!(html.contains("AMD EPYC &amp; co"));
+        assert!(html.contains("NVIDIA RTX 5090"));
+        assert!(html.contains("<code>block_&lt;1&gt;</code>"));
+        assert!(!html.contains("block_<1>"));
+        assert!(html.contains("1,234,567 cycles"));
+        assert!(html.contains("<th>sp1-v5.2.1</th><th>zisk-v0.15.0</th>"));
+        assert!(html.contains("title=\"panicked at &quot;oom&quot;\">Crashed</td>"));
+        assert!(html.contains("reth-v1.9.0 / zisk-v0.15.0 (1 crashes)"));
+        assert!(html.contains("<svg role=\"img\""));
+        assert!(html.contains("<title>reth-v1.9.0 / sp1-v5.2.1: 1,617,284</title>"));
+        assert!(html.contains("table class=\"sortable\""));
+        assert!(!html.contains("src="));
+        assert!(!html.contains("href="));
+
+        let empty = render_dashboard(&LoadedRuns::new(), None, "empty", chrono::Utc::now());
+        assert!(empty.contains("No benchmark results were found"));
+        assert!(empty.contains("No <code>hardware.json</code>"));
+    }
+
+    #[test]
+    fn formats_counts_and_durations() {
+        assert_eq!(format_count(0), "0");
+        assert_eq!(format_count(999), "999");
+        assert_eq!(format_count(1_000), "1,000");
+        assert_eq!(format_count(12_345_678), "12,345,678");
+        assert_eq!(format_duration(Duration::from_millis(250)), "250.0ms");
+        assert_eq!(format_duration(Duration::from_millis(12_340)), "12.34s");
+        assert_eq!(format_duration(Duration::from_secs(90)), "1.5m");
+        assert_eq!(format_duration(Duration::from_secs(5_400)), "1.5h");
+    }
+}
@@ -3,8 +3,25 @@
 
 pub use chrono;
 
+pub mod compare;
+pub mod cost_model;
+pub mod export;
+pub mod gas;
+pub mod html;
+pub mod markdown;
+pub mod prometheus;
+pub mod regression;
+pub mod results;
+pub mod stats;
+
 use serde_derive::{Deserialize, Serialize};
-use std::{collections::HashMap, fs, io, path::Path, time::Duration};
+use stats::percentile;
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs, io,
+    path::Path,
+    time::Duration,
+};
 use sysinfo::{CpuExt, System, SystemExt};
 use thiserror::Error;
 
@@ -27,6 +44,84 @@ pub struct BenchmarkRun<Metadata> {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(default)]
     pub verification: Option<VerificationMetrics>,
+    /// Resource usage sampled while the execution or proving workload ran.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub resources: Option<ResourceUsage>,
+    /// Guest program and tooling that produced the run.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub provenance: Option<Provenance>,
+    /// Cost profile of the execution, recorded when the run was profiled.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub profile: Option<ExecutionProfile>,
+    /// Execution and proving costs normalised by the gas used by the block, recorded when the
+    /// fixture's gas used is known.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub per_gas: Option<GasNormalizedMetrics>,
+}
+
+/// Costs of a run normalised by the gas used by the benchmarked block.
+///
+/// Fields are `None` when the corresponding workload did not run or did not succeed.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Copy)]
+pub struct GasNormalizedMetrics {
+    /// Gas used by the block.
+    pub gas_used: u64,
+    /// Execution cycles per unit of gas.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cycles_per_gas: Option<GasRatio>,
+    /// Proving time in milliseconds per million gas.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proving_ms_per_mgas: Option<GasRatio>,
+    /// Proof size in bytes per million gas.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proof_bytes_per_mgas: Option<GasRatio>,
+}
+
+/// A cost per unit of gas, serialized as a plain JSON number.
+///
+/// Ratios are finite and non-negative, with negative zero stored as zero, so equal ratios have
+/// equal bits and metrics holding them implement `Eq`.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Copy)]
+#[serde(into = "f64", try_from = "f64")]
+pub struct GasRatio(f64);
+
+impl GasRatio {
+    /// Returns the ratio `value`, or `None` if it is not finite or negative.
+    pub const fn new(value: f64) -> Option<Self> {
+        if value.is_finite() && value >= 0.0 {
+            // `abs` turns negative zero into zero.
+            Some(Self(value.abs()))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the ratio as a float.
+    pub const fn get(self) -> f64 {
+        self.0
+    }
+}
+
+// Ratios are never NaN, so float equality is reflexive.
+impl Eq for GasRatio {}
+
+impl TryFrom<f64> for GasRatio {
+    type Error = String;
+
+    fn try_from(value: f64) -> Result<Self, Self::Error> {
+        Self::new(value)
+            .ok_or_else(|| format!("gas ratio must be finite and non-negative, got {value}"))
+    }
+}
+
+impl From<GasRatio> for f64 {
+    fn from(ratio: GasRatio) -> Self {
+        ratio.get()
+    }
 }
 
 /// Hardware specs of the benchmark runner.
@@ -72,6 +167,17 @@ impl HardwareInfo {
         fs::write(path, json)?;
         Ok(())
     }
+
+    /// Reads hardware information from the JSON file at `path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `MetricsError::Io` if reading the file fails.
+    /// Returns `MetricsError::Serde` if JSON deserialization fails.
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self, MetricsError> {
+        let contents = fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&contents)?)
+    }
 }
 
 /// Detects available GPUs on the system.
@@ -98,6 +204,157 @@ fn detect_gpus() -> Vec<GpuInfo> {
     gpus
 }
 
+/// Guest program and tooling that produced a benchmark run.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
+pub struct Provenance {
+    /// zkVM name.
+    pub zkvm: String,
+    /// zkVM SDK version.
+    pub sdk_version: String,
+    /// SHA-256 of the guest ELF, hex encoded.
+    pub elf_sha256: String,
+    /// Program verifying key, hex encoded.
+    pub program_vk: String,
+    /// Where the guest program was resolved from.
+    pub guest_source: GuestSource,
+    /// Tag of the Ere Docker images.
+    pub ere_image_tag: String,
+    /// Git commit `ere-hosts` was built from, or `unknown`.
+    pub ere_hosts_commit: String,
+}
+
+impl Provenance {
+    /// Names of the fields that identify the guest program and differ between `self` and
+    /// `other`.
+    ///
+    /// The guest source and `ere-hosts` commit are not compared: the same guest may be resolved
+    /// from different sources, and harness changes do not change the guest.
+    pub fn guest_differences(&self, other: &Self) -> Vec<&'static str> {
+        [
+            ("zkvm", self.zkvm == other.zkvm),
+            ("sdk_version", self.sdk_version == other.sdk_version),
+            ("elf_sha256", self.elf_sha256 == other.elf_sha256),
+            ("program_vk", self.program_vk == other.program_vk),
+            ("ere_image_tag", self.ere_image_tag == other.ere_image_tag),
+        ]
+        .into_iter()
+        .filter(|(_, same)| !same)
+        .map(|(field, _)| field)
+        .collect()
+    }
+
+    /// Serializes the provenance to a JSON string in the provided path.
+    ///
+    /// # Errors
+    ///
+    /// Returns `MetricsError::Io` if any filesystem operation fails.
+    /// Returns `MetricsError::Serde` if JSON serialization fails.
+    pub fn to_path<P: AsRef<Path>>(&self, path: P) -> Result<(), MetricsError> {
+        let path = path.as_ref();
+        ensure_parent_dirs(path)?;
+        let json = serde_json::to_string_pretty(self)?;
+        fs::write(path, json)?;
+        Ok(())
+    }
+}
+
+/// Source a guest program was resolved from.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
+#[serde(rename_all = "snake_case")]
+pub enum GuestSource {
+    /// Release or workflow artifacts of the pinned `ere-guests` dependency.
+    EreGuests {
+        /// `tag` or `commit`.
+        kind: String,
+        /// The tag or commit SHA.
+        value: String,
+    },
+    /// A local directory.
+    LocalPath {
+        /// Directory the guest was read from.
+        path: String,
+    },
+    /// A remote artifact directory.
+    ArtifactBaseUrl {
+        /// Base URL the guest was downloaded from.
+        url: String,
+    },
+}
+
+/// Resource usage of a workload, sampled periodically while it ran.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
+pub struct ResourceUsage {
+    /// What was sampled.
+    pub source: ResourceSource,
+    /// Peak resident memory in bytes.
+    pub peak_memory_bytes: u64,
+    /// Wall-clock time covered by the measurement.
+    pub wall_time: Duration,
+    /// CPU time consumed across all cores.
+    pub cpu_time: Duration,
+    /// Average CPU utilization in percent of a single core, so 400 means four busy cores.
+    pub average_cpu_percent: u64,
+    /// Number of samples taken.
+    pub samples: u32,
+}
+
+impl ResourceUsage {
+    /// Creates a usage record, deriving the average CPU utilization from CPU and wall time.
+    pub fn new(
+        source: ResourceSource,
+        peak_memory_bytes: u64,
+        wall_time: Duration,
+        cpu_time: Duration,
+        samples: u32,
+    ) -> Self {
+        let average_cpu_percent = if wall_time.is_zero() {
+            0
+        } else {
+            (cpu_time.as_secs_f64() / wall_time.as_secs_f64() * 100.0).round() as u64
+        };
+        Self {
+            source,
+            peak_memory_bytes,
+            wall_time,
+            cpu_time,
+            average_cpu_percent,
+            samples,
+        }
+    }
+}
+
+/// Origin of sampled resource usage.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum ResourceSource {
+    /// The cgroup of the Docker container running the zkVM server.
+    DockerContainer,
+    /// The benchmark host process and its child processes.
+    HostProcess,
+}
+
+/// Cost profile of a guest execution as reported by the zkVM profiler.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Default)]
+pub struct ExecutionProfile {
+    /// Total cost of the execution.
+    pub total_cost: u64,
+    /// Cost per category, e.g. `MAIN`, `OPCODES`, `PRECOMPILES` and `MEMORY` for Zisk.
+    pub cost_distribution: BTreeMap<String, u64>,
+    /// Cost of each profiling scope marked in the guest, keyed by scope name.
+    pub scopes: BTreeMap<String, ScopeCost>,
+}
+
+/// Cost of one profiling scope, summed over all of its calls.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Copy, Default)]
+pub struct ScopeCost {
+    /// Total cost spent inside the scope.
+    pub cost: u64,
+    /// Total steps executed inside the scope.
+    pub steps: u64,
+    /// Number of times the scope was entered.
+    pub calls: u64,
+}
+
 /// Information about a crash that occurred during a workload.
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
 pub struct CrashInfo {
@@ -117,8 +374,12 @@ pub enum ExecutionMetrics {
         total_num_cycles: u64,
         /// Region-specific cycles, mapping region names (e.g., "setup", "compute") to their cycle counts.
         region_cycles: HashMap<String, u64>,
-        /// Execution duration.
+        /// Execution duration, the median over measured iterations when repeated.
         execution_duration: Duration,
+        /// Statistics over repeated measurements, present when warmup or multiple measured
+        /// iterations were configured.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        statistics: Option<Box<ExecutionStatistics>>,
     },
     /// Metrics for a crashed execution workload.
     Crashed(CrashInfo),
@@ -134,10 +395,14 @@ pub enum ProvingMetrics {
         output_matched: bool,
         /// Proof size in bytes.
         proof_size: usize,
-        /// Proving time in milliseconds.
+        /// Proving time in milliseconds, the median over measured iterations when repeated.
         proving_time_ms: u128,
-        /// Verification time in milliseconds.
+        /// Verification time in milliseconds, the median over measured iterations when repeated.
         verification_time_ms: u128,
+        /// Statistics over repeated measurements, present when warmup or multiple measured
+        /// iterations were configured.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        statistics: Option<Box<ProvingStatistics>>,
     },
     /// Metrics for a crashed proving workload.
     Crashed(CrashInfo),
@@ -151,13 +416,95 @@ pub enum VerificationMetrics {
     Success {
         /// Proof size in bytes.
         proof_size: usize,
-        /// Verification time in milliseconds.
+        /// Verification time in milliseconds, the median over measured iterations when repeated.
         verification_time_ms: u128,
+        /// Statistics over repeated measurements, present when warmup or multiple measured
+        /// iterations were configured.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        statistics: Option<Box<VerificationStatistics>>,
     },
     /// Metrics for a crashed verification workload.
     Crashed(CrashInfo),
 }
 
+/// Summary statistics over the measured samples of a repeated measurement.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
+pub struct DurationStats {
+    /// Measured samples, in measurement order.
+    pub samples: Vec<Duration>,
+    /// Smallest sample.
+    pub min: Duration,
+    /// Largest sample.
+    pub max: Duration,
+    /// Arithmetic mean.
+    pub mean: Duration,
+    /// Median.
+    pub median: Duration,
+    /// Sample standard deviation, zero for a single sample.
+    pub stddev: Duration,
+    /// 95th percentile, linearly interpolated between the closest ranks.
+    pub p95: Duration,
+}
+
+impl DurationStats {
+    /// Computes statistics over `samples`, returning `None` if there are none.
+    pub fn from_samples(samples: Vec<Duration>) -> Option<Self> {
+        let mut sorted: Vec<f64> = samples.iter().map(|d| d.as_nanos() as f64).collect();
+        sorted.sort_by(f64::total_cmp);
+        let (&min, &max) = (sorted.first()?, sorted.last()?);
+        let count = sorted.len() as f64;
+        let mean = sorted.iter().sum::<f64>() / count;
+        let variance = if sorted.len() > 1 {
+            sorted.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (count - 1.0)
+        } else {
+            0.0
+        };
+
+        Some(Self {
+            min: nanos_to_duration(min),
+            max: nanos_to_duration(max),
+            mean: nanos_to_duration(mean),
+            median: nanos_to_duration(percentile(&sorted, 0.5)),
+            stddev: nanos_to_duration(variance.sqrt()),
+            p95: nanos_to_duration(percentile(&sorted, 0.95)),
+            samples,
+        })
+    }
+}
+
+const fn nanos_to_duration(nanos: f64) -> Duration {
+    Duration::from_nanos(nanos.round() as u64)
+}
+
+/// Repeated measurement statistics for an execution workload.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
+pub struct ExecutionStatistics {
+    /// Number of discarded warmup iterations run before measuring.
+    pub warmup_iterations: u32,
+    /// Execution duration statistics.
+    pub execution_duration: DurationStats,
+}
+
+/// Repeated measurement statistics for a proving workload.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
+pub struct ProvingStatistics {
+    /// Number of discarded warmup iterations run before measuring.
+    pub warmup_iterations: u32,
+    /// Proving time statistics.
+    pub proving_time: DurationStats,
+    /// Verification time statistics.
+    pub verification_time: DurationStats,
+}
+
+/// Repeated measurement statistics for a standalone verification workload.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
+pub struct VerificationStatistics {
+    /// Number of discarded warmup iterations run before measuring.
+    pub warmup_iterations: u32,
+    /// Verification time statistics.
+    pub verification_time: DurationStats,
+}
+
 /// Errors that can occur during metrics processing.
 #[derive(Error, Debug)]
 pub enum MetricsError {
@@ -168,6 +515,16 @@ pub enum MetricsError {
     /// Error during file system I/O operations.
     #[error("I/O error: {0}")]
     Io(#[from] io::Error),
+
+    /// Error while writing Parquet files.
+    #[cfg(feature = "parquet")]
+    #[error("Parquet error: {0}")]
+    Parquet(#[from] parquet::errors::ParquetError),
+
+    /// Error while writing a `SQLite` database.
+    #[cfg(feature = "sqlite")]
+    #[error("SQLite error: {0}")]
+    Sqlite(#[from] rusqlite::Error),
 }
 
 impl MetricsError {
@@ -175,7 +532,7 @@ impl MetricsError {
     fn into_serde_err(self) -> serde_json::Error {
         match self {
             Self::Serde(e) => e,
-            Self::Io(e) => panic!("unexpected IO error in test: {e}"),
+            other => panic!("unexpected error in test: {other}"),
         }
     }
 }
@@ -264,9 +621,28 @@ mod tests {
                         ("teardown".to_string(), 100),
                     ]),
                     execution_duration: Duration::from_millis(150),
+                    statistics: None,
                 }),
                 proving: None,
                 verification: None,
+                resources: None,
+                provenance: None,
+                profile: Some(ExecutionProfile {
+                    total_cost: 5_000,
+                    cost_distribution: BTreeMap::from_iter([
+                        ("MAIN".to_string(), 3_000),
+                        ("OPCODES".to_string(), 2_000),
+                    ]),
+                    scopes: BTreeMap::from_iter([(
+                        "stf".to_string(),
+                        ScopeCost {
+                            cost: 4_000,
+                            steps: 40,
+                            calls: 1,
+                        },
+                    )]),
+                }),
+                per_gas: None,
             },
             BenchmarkRun {
                 name: "aes_bench".into(),
@@ -283,14 +659,20 @@ mod tests {
                         ("final".to_string(), 200),
                     ]),
                     execution_duration: Duration::from_millis(300),
+                    statistics: None,
                 }),
                 proving: Some(ProvingMetrics::Success {
                     output_matched: true,
                     proof_size: 256,
                     proving_time_ms: 2_000,
                     verification_time_ms: 200,
+                    statistics: None,
                 }),
                 verification: None,
+                resources: None,
+                provenance: None,
+                profile: None,
+                per_gas: None,
             },
             BenchmarkRun {
                 name: "proving_bench".into(),
@@ -304,12 +686,61 @@ mod tests {
                     proof_size: 512,
                     proving_time_ms: 5_000,
                     verification_time_ms: 500,
+                    statistics: None,
                 }),
                 verification: None,
+                resources: Some(ResourceUsage::new(
+                    ResourceSource::DockerContainer,
+                    8 * 1024 * 1024 * 1024,
+                    Duration::from_secs(5),
+                    Duration::from_secs(20),
+                    26,
+                )),
+                provenance: Some(provenance()),
+                profile: None,
+                per_gas: None,
             },
         ]
     }
 
+    fn provenance() -> Provenance {
+        Provenance {
+            zkvm: "zisk".into(),
+            sdk_version: "0.15.0".into(),
+            elf_sha256: "ab".repeat(32),
+            program_vk: "cafe".into(),
+            guest_source: GuestSource::EreGuests {
+                kind: "tag".into(),
+                value: "v0.6.0".into(),
+            },
+            ere_image_tag: "58ca85b".into(),
+            ere_hosts_commit: "0123abc".into(),
+        }
+    }
+
+    #[test]
+    fn provenance_compares_guest_identity_only() {
+        let baseline = provenance();
+        let candidate = Provenance {
+            guest_source: GuestSource::LocalPath {
+                path: "/guests".into(),
+            },
+            ere_hosts_commit: "4567def".into(),
+            ..provenance()
+        };
+        assert!(baseline.guest_differences(&candidate).is_empty());
+
+        let candidate = Provenance {
+            elf_sha256: "cd".repeat(32),
+            program_vk: "beef".into(),
+            ..provenance()
+        };
+        assert_eq!(
+            baseline.guest_differences(&candidate),
+            ["elf_sha256", "program_vk"]
+        );
+    }
+
     #[test]
     fn round_trip_json() {
         let runs = sample();
@@ -351,9 +782,14 @@ mod tests {
                 total_num_cycles: 1000,
                 region_cycles: HashMap::new(),
                 execution_duration: Duration::from_millis(150),
+                statistics: None,
             }),
             proving: None,
             verification: None,
+            resources: None,
+            provenance: None,
+            profile: None,
+            per_gas: None,
         };
 
         assert_eq!(benchmark_run.name, "test_benchmark");
@@ -376,14 +812,20 @@ mod tests {
                     ("teardown".to_string(), 50),
                 ]),
                 execution_duration: Duration::from_millis(100),
+                statistics: None,
             }),
             proving: Some(ProvingMetrics::Success {
                 output_matched: true,
                 proof_size: 128,
                 proving_time_ms: 1500,
                 verification_time_ms: 150,
+                statistics: None,
             }),
             verification: None,
+            resources: None,
+            provenance: None,
+            profile: None,
+            per_gas: None,
         };
         let json = BenchmarkRun::to_json(std::slice::from_ref(&bench)).expect("serialize mixed");
         let parsed = BenchmarkRun::from_json(&json).expect("deserialize mixed");
@@ -398,6 +840,7 @@ mod tests {
                 total_num_cycles: 42,
                 region_cycles: HashMap::new(),
                 execution_duration: Duration::from_millis(7),
+                statistics: None,
             };
 
             let value = serde_json::to_value(&metrics).expect("serialize execution metrics");
@@ -420,6 +863,7 @@ mod tests {
                 proof_size: 256,
                 proving_time_ms: 2_000,
                 verification_time_ms: 200,
+                statistics: None,
             };
 
             let value = serde_json::to_value(&metrics).expect("serialize proving metrics");
@@ -433,4 +877,113 @@ mod tests {
             assert_eq!(metrics, parsed);
         }
     }
+
+    #[test]
+    fn duration_stats_summarize_samples() {
+        let stats = DurationStats::from_samples(
+            [40, 10, 30, 20, 50]
+                .into_iter()
+                .map(Duration::from_millis)
+                .collect(),
+        )
+        .expect("non-empty samples");
+
+        assert_eq!(stats.samples[0], Duration::from_millis(40));
+        assert_eq!(stats.min, Duration::from_millis(10));
+        assert_eq!(stats.max, Duration::from_millis(50));
+        assert_eq!(stats.mean, Duration::from_millis(30));
+        assert_eq!(stats.median, Duration::from_millis(30));
+        assert_eq!(stats.p95, Duration::from_millis(48));
+        assert_eq!(stats.stddev.as_micros(), 15_811);
+
+        let single = DurationStats::from_samples(vec![Duration::from_millis(7)]).unwrap();
+        assert_eq!(single.stddev, Duration::ZERO);
+        assert_eq!(single.p95, Duration::from_millis(7));
+        assert!(DurationStats::from_samples(Vec::new()).is_none());
+    }
+
+    #[test]
+    fn statistics_are_optional_when_deserializing() {
+        let metrics: ProvingMetrics = serde_json::from_str(
+            r#"{"success":{"output_matched":true,"proof_size":1,"proving_time_ms":2,"verification_time_ms":3}}"#,
+        )
+        .expect("deserialize proving metrics without statistics");
+
+        assert!(matches!(
+            metrics,
+            ProvingMetrics::Success {
+                statistics: None,
+                ..
+            }
+        ));
+        assert!(
+            !serde_json::to_string(&metrics)
+                .expect("serialize proving metrics")
+                .contains("statistics")
+        );
+    }
+
+    #[test]
+    fn resource_usage_derives_average_cpu_percent() {
+        let usage = ResourceUsage::new(
+            ResourceSource::HostProcess,
+            1024,
+            Duration::from_secs(4),
+            Duration::from_secs(10),
+            21,
+        );
+        assert_eq!(usage.average_cpu_percent, 250);
+
+        let idle = ResourceUsage::new(
+            ResourceSource::HostProcess,
+            0,
+            Duration::ZERO,
+            Duration::ZERO,
+            1,
+        );
+        assert_eq!(idle.average_cpu_percent, 0);
+
+        let value = serde_json::to_value(&usage).expect("serialize resource usage");
+        assert_eq!(value["source"], "host_process");
+    }
+
+    #[test]
+    fn per_gas_metrics_round_trip_as_plain_numbers() -> Result<(), MetricsError> {
+        let mut run = sample().swap_remove(0);
+        run.per_gas = Some(GasNormalizedMetrics {
+            gas_used: 12_345,
+            cycles_per_gas: GasRatio::new(0.5),
+            proving_ms_per_mgas: None,
+            proof_bytes_per_mgas: GasRatio::new(3_770.75),
+        });
+
+        let json = serde_json::to_value(&run)?;
+        assert_eq!(
+            json["per_gas"],
+            serde_json::json!({
+                "gas_used": 12_345,
+                "cycles_per_gas": 0.5,
+                "proof_bytes_per_mgas": 3_770.75,
+            })
+        );
+        let parsed: BenchmarkRun<Metadata> = serde_json::from_value(json)?;
+        assert_eq!(parsed, run);
+
+        Ok(())
+    }
+
+    #[test]
+    fn gas_ratios_are_finite_and_non_negative() {
+        assert_eq!(GasRatio::new(f64::NAN), None);
+        assert_eq!(GasRatio::new(f64::INFINITY), None);
+        assert_eq!(GasRatio::new(-1.0), None);
+        assert_eq!(GasRatio::new(-0.0), GasRatio::new(0.0));
+        assert!(GasRatio::new(-0.0).unwrap().get().is_sign_positive());
+        assert_ne!(GasRatio::new(1.0), GasRatio::new(1.5));
+        assert_eq!(GasRatio::new(2.5).map(GasRatio::get), Some(2.5));
+
+        let parsed: Result<GasNormalizedMetrics, _> =
+            serde_json::from_str(r#"{"gas_used":1,"cycles_per_gas":-2.0}"#);
+        assert!(parsed.is_err());
+    }
 }
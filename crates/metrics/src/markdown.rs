@@ -0,0 +1,33 @@
+//! Formatting helpers shared by the Markdown reports of this crate and of `benchmark-runner`.
+
+/// Escapes `|` so `value` can be placed in a Markdown table cell.
+pub fn escape_markdown(value: &str) -> String {
+    value.replace('|', "\\|")
+}
+
+/// Formats a count with a `K`, `M` or `B` suffix, e.g. `5.7B`.
+pub fn format_number(value: f64) -> String {
+    let magnitude = value.abs();
+    if magnitude >= 1e9 {
+        format!("{:.1}B", value / 1e9)
+    } else if magnitude >= 1e6 {
+        format!("{:.1}M", value / 1e6)
+    } else if magnitude >= 1e3 {
+        format!("{:.1}K", value / 1e3)
+    } else {
+        format!("{value:.0}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_table_cells() {
+        assert_eq!(escape_markdown("a|b"), "a\\|b");
+        assert_eq!(format_number(5_712_000_000.0), "5.7B");
+        assert_eq!(format_number(-2_500.0), "-2.5K");
+        assert_eq!(format_number(42.4), "42");
+    }
+}
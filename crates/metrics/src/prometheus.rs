@@ -0,0 +1,466 @@
+//! Prometheus exposition of progress metrics.
+//!
+//! A [`Registry`] holds counters, gauges and histograms keyed by metric name and label values,
+//! and renders them in the Prometheus text format, version 0.0.4. Prometheus and `OpenMetrics`
+//! scrapers accept this format, and the node exporter's textfile collector reads it, so long
+//! running processes can either [`serve`] a registry at `/metrics` or write it with
+//! [`Registry::write_textfile`] after every update.
+
+use crate::MetricsError;
+use std::{
+    collections::BTreeMap,
+    fmt::Write as _,
+    fs,
+    io::{self, BufRead, BufReader, Write},
+    net::{SocketAddr, TcpListener, TcpStream},
+    path::Path,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+    thread,
+    time::Duration,
+};
+
+/// Content type of the rendered registry.
+pub const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// Label names and values of one series, in the order given to updates.
+type Labels = Vec<(String, String)>;
+
+/// Type of a metric family.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MetricKind {
+    /// Monotonically increasing count.
+    Counter,
+    /// Value that can go up and down.
+    Gauge,
+    /// Distribution of observations over cumulative buckets.
+    Histogram,
+}
+
+impl MetricKind {
+    const fn as_str(self) -> &'static str {
+        match self {
+            Self::Counter => "counter",
+            Self::Gauge => "gauge",
+            Self::Histogram => "histogram",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Series {
+    Value(f64),
+    Histogram {
+        bucket_counts: Vec<u64>,
+        sum: f64,
+        count: u64,
+    },
+}
+
+#[derive(Debug, Clone)]
+struct Family {
+    help: String,
+    kind: MetricKind,
+    buckets: Vec<f64>,
+    series: BTreeMap<Labels, Series>,
+}
+
+/// Metric families, safe to update from several threads.
+///
+/// Updates of metrics that were not registered, or were registered with another kind, are
+/// ignored.
+#[derive(Debug, Default)]
+pub struct Registry {
+    families: Mutex<BTreeMap<String, Family>>,
+    /// Held while a textfile is written, so a stale render never replaces a newer one.
+    textfile: Mutex<()>,
+}
+
+impl Registry {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a counter. Counter names should end in `_total`.
+    pub fn register_counter(&self, name: &str, help: &str) {
+        self.register(name, help, MetricKind::Counter, Vec::new());
+    }
+
+    /// Registers a gauge.
+    pub fn register_gauge(&self, name: &str, help: &str) {
+        self.register(name, help, MetricKind::Gauge, Vec::new());
+    }
+
+    /// Registers a histogram with the given bucket upper bounds; a `+Inf` bucket is implied.
+    pub fn register_histogram(&self, name: &str, help: &str, buckets: &[f64]) {
+        let mut buckets = buckets.to_vec();
+        buckets.sort_by(f64::total_cmp);
+        buckets.dedup();
+        self.register(name, help, MetricKind::Histogram, buckets);
+    }
+
+    fn register(&self, name: &str, help: &str, kind: MetricKind, buckets: Vec<f64>) {
+        self.lock()
+            .entry(name.to_string())
+            .or_insert_with(|| Family {
+                help: help.to_string(),
+                kind,
+                buckets,
+                series: BTreeMap::new(),
+            });
+    }
+
+    /// Increments a counter by one.
+    pub fn inc_counter(&self, name: &str, labels: &[(&str, &str)]) {
+        self.add_counter(name, labels, 1.0);
+    }
+
+    /// Increments a counter by `value`. Adding zero exposes the series before its first event.
+    pub fn add_counter(&self, name: &str, labels: &[(&str, &str)], value: f64) {
+        self.update(name, MetricKind::Counter, labels, |_, series| {
+            if let Series::Value(current) = series {
+                *current += value;
+            }
+        });
+    }
+
+    /// Sets a gauge.
+    pub fn set_gauge(&self, name: &str, labels: &[(&str, &str)], value: f64) {
+        self.update(name, MetricKind::Gauge, labels, |_, series| {
+            *series = Series::Value(value);
+        });
+    }
+
+    /// Records an observation in a histogram.
+    pub fn observe(&self, name: &str, labels: &[(&str, &str)], value: f64) {
+        self.update(name, MetricKind::Histogram, labels, |buckets, series| {
+            if let Series::Histogram {
+                bucket_counts,
+                sum,
+                count,
+            } = series
+            {
+                for (bound, bucket_count) in buckets.iter().zip(bucket_counts.iter_mut()) {
+                    if value <= *bound {
+                        *bucket_count += 1;
+                    }
+                }
+                *sum += value;
+                *count += 1;
+            }
+        });
+    }
+
+    /// Removes one series of a metric, such as a gauge for work that finished.
+    pub fn remove(&self, name: &str, labels: &[(&str, &str)]) {
+        if let Some(family) = self.lock().get_mut(name) {
+            family.series.remove(&owned_labels(labels));
+        }
+    }
+
+    /// Returns the value of a counter or gauge series, if it exists.
+    pub fn value(&self, name: &str, labels: &[(&str, &str)]) -> Option<f64> {
+        match self.lock().get(name)?.series.get(&owned_labels(labels))? {
+            Series::Value(value) => Some(*value),
+            Series::Histogram { .. } => None,
+        }
+    }
+
+    fn update(
+        &self,
+        name: &str,
+        kind: MetricKind,
+        labels: &[(&str, &str)],
+        apply: impl FnOnce(&[f64], &mut Series),
+    ) {
+        let mut families = self.lock();
+        let Some(family) = families.get_mut(name).filter(|family| family.kind == kind) else {
+            return;
+        };
+        let series = family
+            .series
+            .entry(owned_labels(labels))
+            .or_insert_with(|| match kind {
+                MetricKind::Counter | MetricKind::Gauge => Series::Value(0.0),
+                MetricKind::Histogram => Series::Histogram {
+                    bucket_counts: vec![0; family.buckets.len()],
+                    sum: 0.0,
+                    count: 0,
+                },
+            });
+        apply(&family.buckets, series);
+    }
+
+    /// Renders every family in the Prometheus text format.
+    pub fn render(&self) -> String {
+        let families = self.lock();
+        let mut out = String::new();
+        for (name, family) in families.iter() {
+            let _ = writeln!(out, "# HELP {name} {}", escape_help(&family.help));
+            let _ = writeln!(out, "# TYPE {name} {}", family.kind.as_str());
+            for (labels, series) in &family.series {
+                match series {
+                    Series::Value(value) => {
+                        let _ = writeln!(out, "{name}{} {}", render_labels(labels, None), value);
+                    }
+                    Series::Histogram {
+                        bucket_counts,
+                        sum,
+                        count,
+                    } => {
+                        for (bound, bucket_count) in family.buckets.iter().zip(bucket_counts) {
+                            let le = bound.to_string();
+                            let _ = writeln!(
+                                out,
+                                "{name}_bucket{} {bucket_count}",
+                                render_labels(labels, Some(&le))
+                            );
+                        }
+                        let _ = writeln!(
+                            out,
+                            "{name}_bucket{} {count}",
+                            render_labels(labels, Some("+Inf"))
+                        );
+                        let _ = writeln!(out, "{name}_sum{} {sum}", render_labels(labels, None));
+                        let _ =
+                            writeln!(out, "{name}_count{} {count}", render_labels(labels, None));
+                    }
+                }
+            }
+        }
+        out
+    }
+
+    /// Atomically writes the rendered registry to `path`, for the node exporter's textfile
+    /// collector. The file name should end in `.prom`.
+    ///
+    /// Concurrent writers of one registry take turns, and the temporary file is named after
+    /// the process, so processes sharing a textfile directory do not clobber each other.
+    ///
+    /// # Errors
+    ///
+    /// Returns `MetricsError::Io` if the file cannot be written.
+    pub fn write_textfile(&self, path: &Path) -> Result<(), MetricsError> {
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)?;
+        }
+        let mut file_name = path.file_name().unwrap_or_default().to_os_string();
+        file_name.push(format!(".{}.tmp", std::process::id()));
+        let tmp_path = path.with_file_name(file_name);
+
+        let _writing = self.textfile.lock().unwrap_or_else(PoisonError::into_inner);
+        fs::write(&tmp_path, self.render())?;
+        fs::rename(&tmp_path, path)?;
+        Ok(())
+    }
+
+    fn lock(&self) -> MutexGuard<'_, BTreeMap<String, Family>> {
+        self.families.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Returns `count` bucket bounds starting at `start`, each `factor` times the previous one.
+pub fn exponential_buckets(start: f64, factor: f64, count: usize) -> Vec<f64> {
+    std::iter::successors(Some(start), |bound| Some(bound * factor))
+        .take(count)
+        .collect()
+}
+
+fn owned_labels(labels: &[(&str, &str)]) -> Labels {
+    labels
+        .iter()
+        .map(|(name, value)| ((*name).to_string(), (*value).to_string()))
+        .collect()
+}
+
+fn render_labels(labels: &Labels, le: Option<&str>) -> String {
+    let mut pairs: Vec<String> = labels
+        .iter()
+        .map(|(name, value)| format!("{name}=\"{}\"", escape_label_value(value)))
+        .collect();
+    if let Some(le) = le {
+        pairs.push(format!("le=\"{le}\""));
+    }
+    if pairs.is_empty() {
+        String::new()
+    } else {
+        format!("{{{}}}", pairs.join(","))
+    }
+}
+
+fn escape_label_value(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+fn escape_help(help: &str) -> String {
+    help.replace('\\', "\\\\").replace('\n', "\\n")
+}
+
+/// HTTP endpoint serving a registry, see [`serve`].
+#[derive(Debug)]
+pub struct MetricsServer {
+    local_addr: SocketAddr,
+}
+
+impl MetricsServer {
+    /// Address the server listens on, with the port resolved when binding port 0.
+    pub const fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+}
+
+/// Serves `registry` at `GET /metrics` on `addr` from a background thread for the rest of the
+/// process lifetime.
+///
+/// # Errors
+///
+/// Returns `MetricsError::Io` if the address cannot be bound or the thread cannot be spawned.
+pub fn serve(registry: Arc<Registry>, addr: SocketAddr) -> Result<MetricsServer, MetricsError> {
+    let listener = TcpListener::bind(addr)?;
+    let local_addr = listener.local_addr()?;
+    thread::Builder::new()
+        .name("metrics-server".to_string())
+        .spawn(move || {
+            for stream in listener.incoming().flatten() {
+                // A failed scrape only affects that client.
+                let _ = respond(&registry, stream);
+            }
+        })?;
+    Ok(MetricsServer { local_addr })
+}
+
+fn respond(registry: &Registry, mut stream: TcpStream) -> io::Result<()> {
+    stream.set_read_timeout(Some(Duration::from_secs(5)))?;
+    let mut reader = BufReader::new(&stream);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header)? == 0 || header.trim().is_empty() {
+            break;
+        }
+    }
+
+    let mut parts = request_line.split_whitespace();
+    let (method, target) = (parts.next(), parts.next().unwrap_or_default());
+    let path = target.split('?').next().unwrap_or_default();
+    let (status, content_type, body) = if method == Some("GET") && path == "/metrics" {
+        ("200 OK", CONTENT_TYPE, registry.render())
+    } else {
+        (
+            "404 Not Found",
+            "text/plain; charset=utf-8",
+            "metrics are served at /metrics\n".to_string(),
+        )
+    };
+    write!(
+        stream,
+        "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    )?;
+    stream.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    fn registry() -> Registry {
+        let registry = Registry::new();
+        registry.register_counter("fixtures_total", "Fixtures by outcome");
+        registry.register_gauge("current_fixture", "Fixture being run");
+        registry.register_histogram("cycles", "Execution cycles", &[100.0, 10.0]);
+        registry
+    }
+
+    #[test]
+    fn renders_counters_gauges_and_histograms() {
+        let registry = registry();
+        let sp1 = [("zkvm", "sp1"), ("outcome", "crashed")];
+        registry.add_counter("fixtures_total", &sp1, 0.0);
+        registry.inc_counter("fixtures_total", &sp1);
+        registry.inc_counter("fixtures_total", &sp1);
+        registry.set_gauge("current_fixture", &[("fixture", "a \"quoted\"\nname")], 1.0);
+        registry.set_gauge("current_fixture", &[("fixture", "done")], 1.0);
+        registry.remove("current_fixture", &[("fixture", "done")]);
+        for cycles in [5.0, 50.0, 500.0] {
+            registry.observe("cycles", &[("zkvm", "sp1")], cycles);
+        }
+        // Unregistered metrics and kind mismatches are ignored.
+        registry.inc_counter("unknown_total", &[]);
+        registry.set_gauge("fixtures_total", &sp1, 10.0);
+
+        assert_eq!(registry.value("fixtures_total", &sp1), Some(2.0));
+        assert_eq!(
+            registry.render(),
+            "# HELP current_fixture Fixture being run\n\
+             # TYPE current_fixture gauge\n\
+             current_fixture{fixture=\"a \\\"quoted\\\"\\nname\"} 1\n\
+             # HELP cycles Execution cycles\n\
+             # TYPE cycles histogram\n\
+             cycles_bucket{zkvm=\"sp1\",le=\"10\"} 1\n\
+             cycles_bucket{zkvm=\"sp1\",le=\"100\"} 2\n\
+             cycles_bucket{zkvm=\"sp1\",le=\"+Inf\"} 3\n\
+             cycles_sum{zkvm=\"sp1\"} 555\n\
+             cycles_count{zkvm=\"sp1\"} 3\n\
+             # HELP fixtures_total Fixtures by outcome\n\
+             # TYPE fixtures_total counter\n\
+             fixtures_total{zkvm=\"sp1\",outcome=\"crashed\"} 2\n"
+        );
+        assert_eq!(exponential_buckets(1.0, 10.0, 3), [1.0, 10.0, 100.0]);
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("textfile/zkevm.prom");
+        registry.write_textfile(&path).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), registry.render());
+    }
+
+    #[test]
+    fn concurrent_textfile_writes_leave_a_complete_file() {
+        let registry = Arc::new(registry());
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("zkevm.prom");
+
+        thread::scope(|scope| {
+            for _ in 0..8 {
+                scope.spawn(|| {
+                    for _ in 0..50 {
+                        registry.inc_counter("fixtures_total", &[("outcome", "success")]);
+                        registry.write_textfile(&path).unwrap();
+                    }
+                });
+            }
+        });
+
+        // The last write rendered the final state, and no temporary file is left behind.
+        assert_eq!(fs::read_to_string(&path).unwrap(), registry.render());
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn serves_metrics_to_a_local_scrape() {
+        let registry = Arc::new(registry());
+        registry.inc_counter("fixtures_total", &[("outcome", "success")]);
+        let server = serve(Arc::clone(&registry), "127.0.0.1:0".parse().unwrap()).unwrap();
+
+        let scrape = |path: &str| {
+            let mut stream = TcpStream::connect(server.local_addr()).unwrap();
+            write!(stream, "GET {path} HTTP/1.1\r\nHost: localhost\r\n\r\n").unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).unwrap();
+            response
+        };
+        let response = scrape("/metrics");
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.contains(&format!("Content-Type: {CONTENT_TYPE}\r\n")));
+        assert!(response.ends_with("fixtures_total{outcome=\"success\"} 1\n"));
+
+        registry.inc_counter("fixtures_total", &[("outcome", "success")]);
+        assert!(scrape("/metrics?x=1").ends_with("fixtures_total{outcome=\"success\"} 2\n"));
+        assert!(scrape("/").starts_with("HTTP/1.1 404 Not Found\r\n"));
+    }
+}
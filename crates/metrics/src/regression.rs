@@ -0,0 +1,635 @@
+//! Threshold-based regression checks between a baseline and a candidate metrics folder.
+//!
+//! A [`RegressionPolicy`] assigns optional relative and absolute thresholds to each
+//! [`RegressionMetric`]. A metric regresses when its increase exceeds every configured threshold,
+//! so `relative = 0.05` with `absolute = 1000` tolerates either a 5% or a 1000 unit increase.
+//! Metrics without thresholds are not checked. Success to crash flips and matched to mismatched
+//! outputs fail independently of the thresholds.
+
+use crate::{
+    BenchmarkRun, ExecutionMetrics, MetricsError, ProvingMetrics, VerificationMetrics,
+    compare::{LoadedRuns, MetricDelta, ReportFormat, RunKey, csv_field, load_runs},
+};
+use serde_derive::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fmt::Write as _, path::Path};
+
+/// Metrics that can be gated by a [`RegressionPolicy`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RegressionMetric {
+    /// `ExecutionMetrics::Success::total_num_cycles`.
+    TotalNumCycles,
+    /// `ProvingMetrics::Success::proving_time_ms`.
+    ProvingTimeMs,
+    /// Proof size from proving, or from standalone verification when no proving metrics exist.
+    ProofSize,
+    /// Verification time from proving, or from standalone verification when no proving metrics
+    /// exist.
+    VerificationTimeMs,
+}
+
+impl RegressionMetric {
+    /// Returns the metric name used in policies and reports.
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::TotalNumCycles => "total_num_cycles",
+            Self::ProvingTimeMs => "proving_time_ms",
+            Self::ProofSize => "proof_size",
+            Self::VerificationTimeMs => "verification_time_ms",
+        }
+    }
+
+    const fn value(self, run: &BenchmarkRun<serde_json::Value>) -> Option<f64> {
+        match self {
+            Self::TotalNumCycles => match &run.execution {
+                Some(ExecutionMetrics::Success {
+                    total_num_cycles, ..
+                }) => Some(*total_num_cycles as f64),
+                _ => None,
+            },
+            Self::ProvingTimeMs => match &run.proving {
+                Some(ProvingMetrics::Success {
+                    proving_time_ms, ..
+                }) => Some(*proving_time_ms as f64),
+                _ => None,
+            },
+            Self::ProofSize => match (&run.proving, &run.verification) {
+                (Some(ProvingMetrics::Success { proof_size, .. }), _)
+                | (None, Some(VerificationMetrics::Success { proof_size, .. })) => {
+                    Some(*proof_size as f64)
+                }
+                _ => None,
+            },
+            Self::VerificationTimeMs => match (&run.proving, &run.verification) {
+                (
+                    Some(ProvingMetrics::Success {
+                        verification_time_ms,
+                        ..
+                    }),
+                    _,
+                )
+                | (
+                    None,
+                    Some(VerificationMetrics::Success {
+                        verification_time_ms,
+                        ..
+                    }),
+                ) => Some(*verification_time_ms as f64),
+                _ => None,
+            },
+        }
+    }
+}
+
+/// Tolerated increase of a single metric.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Threshold {
+    /// Tolerated relative increase, e.g. `0.05` for 5%.
+    pub relative: Option<f64>,
+    /// Tolerated absolute increase, in the metric's unit.
+    pub absolute: Option<f64>,
+}
+
+impl Threshold {
+    /// Returns whether `delta` exceeds every configured tolerance.
+    ///
+    /// Increases from a zero baseline exceed any relative tolerance.
+    pub fn is_exceeded_by(&self, delta: &MetricDelta) -> bool {
+        if delta.delta <= 0.0 {
+            return false;
+        }
+        let exceeds_absolute = self.absolute.is_none_or(|absolute| delta.delta > absolute);
+        let exceeds_relative = self.relative.is_none_or(|relative| {
+            delta
+                .relative
+                .is_none_or(|observed_relative| observed_relative > relative)
+        });
+        exceeds_absolute && exceeds_relative
+    }
+}
+
+/// Regression policy, usually loaded from a TOML file.
+///
+/// ```toml
+/// fail_on_missing = false
+///
+/// [thresholds.total_num_cycles]
+/// relative = 0.01
+///
+/// [thresholds.proving_time_ms]
+/// relative = 0.10
+/// absolute = 2000
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RegressionPolicy {
+    /// Fail when a workload succeeded in the baseline and crashed in the candidate.
+    #[serde(default = "default_true")]
+    pub fail_on_crash: bool,
+    /// Fail when a workload's output matched in the baseline and mismatched in the candidate.
+    #[serde(default = "default_true")]
+    pub fail_on_output_mismatch: bool,
+    /// Fail when a baseline fixture is missing from the candidate.
+    #[serde(default)]
+    pub fail_on_missing: bool,
+    /// Per-metric thresholds. Metrics without an entry are not checked.
+    #[serde(default)]
+    pub thresholds: BTreeMap<RegressionMetric, Threshold>,
+}
+
+impl Default for RegressionPolicy {
+    fn default() -> Self {
+        Self {
+            fail_on_crash: true,
+            fail_on_output_mismatch: true,
+            fail_on_missing: false,
+            thresholds: BTreeMap::new(),
+        }
+    }
+}
+
+const fn default_true() -> bool {
+    true
+}
+
+/// Workload whose outcome is checked for crashes and output mismatches.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Workload {
+    /// Execution metrics.
+    Execution,
+    /// Proving metrics.
+    Proving,
+    /// Standalone verification metrics.
+    Verification,
+}
+
+impl Workload {
+    const fn as_str(self) -> &'static str {
+        match self {
+            Self::Execution => "execution",
+            Self::Proving => "proving",
+            Self::Verification => "verification",
+        }
+    }
+}
+
+/// Reason a fixture failed the regression check.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RegressionKind {
+    /// A metric increased past its threshold.
+    Threshold {
+        /// Regressed metric.
+        metric: RegressionMetric,
+        /// Baseline and candidate values.
+        delta: MetricDelta,
+    },
+    /// The workload succeeded in the baseline and crashed in the candidate.
+    Crashed {
+        /// Affected workload.
+        workload: Workload,
+        /// Candidate crash reason.
+        reason: String,
+    },
+    /// The workload's output matched in the baseline and mismatched in the candidate.
+    OutputMismatch {
+        /// Affected workload.
+        workload: Workload,
+    },
+    /// The fixture exists in the baseline but not in the candidate.
+    Missing,
+}
+
+/// A single regression finding.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Regression {
+    /// Relative directory and fixture name.
+    pub key: RunKey,
+    /// What regressed.
+    pub kind: RegressionKind,
+}
+
+/// Result of checking a candidate folder against a baseline folder.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RegressionReport {
+    /// Number of fixtures present in both folders.
+    pub checked: usize,
+    /// Number of baseline fixtures missing from the candidate.
+    pub missing: usize,
+    /// Regressions, ordered by fixture.
+    pub regressions: Vec<Regression>,
+}
+
+impl RegressionReport {
+    /// Loads both folders and checks the candidate against the baseline.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if either folder cannot be loaded, see [`load_runs`].
+    pub fn from_paths<P: AsRef<Path>, Q: AsRef<Path>>(
+        baseline: P,
+        candidate: Q,
+        policy: &RegressionPolicy,
+    ) -> Result<Self, MetricsError> {
+        Ok(Self::new(
+            &load_runs(baseline)?,
+            &load_runs(candidate)?,
+            policy,
+        ))
+    }
+
+    /// Checks already loaded runs.
+    pub fn new(baseline: &LoadedRuns, candidate: &LoadedRuns, policy: &RegressionPolicy) -> Self {
+        let mut report = Self {
+            checked: 0,
+            missing: 0,
+            regressions: Vec::new(),
+        };
+
+        for (key, baseline_run) in baseline {
+            let Some(candidate_run) = candidate.get(key) else {
+                report.missing += 1;
+                if policy.fail_on_missing {
+                    report.push(key, RegressionKind::Missing);
+                }
+                continue;
+            };
+            report.checked += 1;
+
+            for (workload, baseline_outcome, candidate_outcome) in [
+                (
+                    Workload::Execution,
+                    execution_outcome(baseline_run),
+                    execution_outcome(candidate_run),
+                ),
+                (
+                    Workload::Proving,
+                    proving_outcome(baseline_run),
+                    proving_outcome(candidate_run),
+                ),
+                (
+                    Workload::Verification,
+                    verification_outcome(baseline_run),
+                    verification_outcome(candidate_run),
+                ),
+            ] {
+                match (baseline_outcome, candidate_outcome) {
+                    (Some(Outcome::Success { .. }), Some(Outcome::Crashed(reason)))
+                        if policy.fail_on_crash =>
+                    {
+                        report.push(
+                            key,
+                            RegressionKind::Crashed {
+                                workload,
+                                reason: reason.to_string(),
+                            },
+                        );
+                    }
+                    (
+                        Some(Outcome::Success {
+                            output_matched: Some(true),
+                        }),
+                        Some(Outcome::Success {
+                            output_matched: Some(false),
+                        }),
+                    ) if policy.fail_on_output_mismatch => {
+                        report.push(key, RegressionKind::OutputMismatch { workload });
+                    }
+                    _ => {}
+                }
+            }
+
+            for (metric, threshold) in &policy.thresholds {
+                let (Some(baseline_value), Some(candidate_value)) =
+                    (metric.value(baseline_run), metric.value(candidate_run))
+                else {
+                    continue;
+                };
+                let delta = MetricDelta::new(baseline_value, candidate_value);
+                if threshold.is_exceeded_by(&delta) {
+                    report.push(
+                        key,
+                        RegressionKind::Threshold {
+                            metric: *metric,
+                            delta,
+                        },
+                    );
+                }
+            }
+        }
+
+        report
+    }
+
+    fn push(&mut self, key: &RunKey, kind: RegressionKind) {
+        self.regressions.push(Regression {
+            key: key.clone(),
+            kind,
+        });
+    }
+
+    /// Returns whether the check passed.
+    pub const fn passed(&self) -> bool {
+        self.regressions.is_empty()
+    }
+
+    /// Renders the report in the requested format.
+    ///
+    /// # Errors
+    ///
+    /// Returns `MetricsError::Serde` if JSON serialization fails.
+    pub fn render(&self, format: ReportFormat) -> Result<String, MetricsError> {
+        Ok(match format {
+            ReportFormat::Markdown => self.to_markdown(),
+            ReportFormat::Json => serde_json::to_string_pretty(self)?,
+            ReportFormat::Csv => self.to_csv(),
+        })
+    }
+
+    /// Renders a Markdown summary followed by one line per regression.
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::from("# Regression Check\n\n");
+        let _ = writeln!(
+            out,
+            "- **Result:** {}",
+            if self.passed() { "passed" } else { "failed" }
+        );
+        let _ = writeln!(out, "- **Checked fixtures:** {}", self.checked);
+        let _ = writeln!(out, "- **Missing from candidate:** {}", self.missing);
+        let _ = writeln!(out, "- **Regressions:** {}", self.regressions.len());
+
+        if !self.regressions.is_empty() {
+            out.push_str("\n## Regressions\n\n");
+            for regression in &self.regressions {
+                let _ = writeln!(
+                    out,
+                    "- `{}`: {}",
+                    regression.key.label(),
+                    regression.kind.describe()
+                );
+            }
+        }
+
+        out
+    }
+
+    /// Renders one CSV row per regression.
+    pub fn to_csv(&self) -> String {
+        let mut out = String::from("group,name,kind,subject,baseline,candidate,delta,relative\n");
+        for regression in &self.regressions {
+            let (kind, subject, delta) = match &regression.kind {
+                RegressionKind::Threshold { metric, delta } => {
+                    ("threshold", metric.as_str(), Some(delta))
+                }
+                RegressionKind::Crashed { workload, .. } => ("crashed", workload.as_str(), None),
+                RegressionKind::OutputMismatch { workload } => {
+                    ("output_mismatch", workload.as_str(), None)
+                }
+                RegressionKind::Missing => ("missing", "", None),
+            };
+            let values = delta.map_or_else(
+                || ",,,".to_string(),
+                |d| {
+                    format!(
+                        "{},{},{},{}",
+                        d.baseline,
+                        d.candidate,
+                        d.delta,
+                        d.relative.map(|r| r.to_string()).unwrap_or_default()
+                    )
+                },
+            );
+            let _ = writeln!(
+                out,
+                "{},{},{kind},{subject},{values}",
+                csv_field(&regression.key.group),
+                csv_field(&regression.key.name),
+            );
+        }
+        out
+    }
+}
+
+impl RegressionKind {
+    fn describe(&self) -> String {
+        match self {
+            Self::Threshold { metric, delta } => format!(
+                "{} regressed from {} to {}{}",
+                metric.as_str(),
+                delta.baseline,
+                delta.candidate,
+                delta
+                    .relative
+                    .map(|r| format!(" ({:+.2}%)", r * 100.0))
+                    .unwrap_or_default()
+            ),
+            Self::Crashed { workload, reason } => {
+                format!("{} crashed: {reason}", workload.as_str())
+            }
+            Self::OutputMismatch { workload } => {
+                format!("{} output no longer matches", workload.as_str())
+            }
+            Self::Missing => "missing from candidate".to_string(),
+        }
+    }
+}
+
+enum Outcome<'a> {
+    Success { output_matched: Option<bool> },
+    Crashed(&'a str),
+}
+
+fn execution_outcome(run: &BenchmarkRun<serde_json::Value>) -> Option<Outcome<'_>> {
+    run.execution.as_ref().map(|metrics| match metrics {
+        ExecutionMetrics::Success { output_matched, .. } => Outcome::Success {
+            output_matched: Some(*output_matched),
+        },
+        ExecutionMetrics::Crashed(crash) => Outcome::Crashed(&crash.reason),
+    })
+}
+
+fn proving_outcome(run: &BenchmarkRun<serde_json::Value>) -> Option<Outcome<'_>> {
+    run.proving.as_ref().map(|metrics| match metrics {
+        ProvingMetrics::Success { output_matched, .. } => Outcome::Success {
+            output_matched: Some(*output_matched),
+        },
+        ProvingMetrics::Crashed(crash) => Outcome::Crashed(&crash.reason),
+    })
+}
+
+fn verification_outcome(run: &BenchmarkRun<serde_json::Value>) -> Option<Outcome<'_>> {
+    run.verification.as_ref().map(|metrics| match metrics {
+        VerificationMetrics::Success { .. } => Outcome::Success {
+            output_matched: None,
+        },
+        VerificationMetrics::Crashed(crash) => Outcome::Crashed(&crash.reason),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CrashInfo;
+    use std::{collections::HashMap, time::Duration};
+
+    fn run(name: &str, cycles: u64, proving_time_ms: u128) -> BenchmarkRun<serde_json::Value> {
+        BenchmarkRun {
+            name: name.into(),
+            timestamp_completed: chrono::Utc::now(),
+            metadata: serde_json::Value::Null,
+            execution: Some(ExecutionMetrics::Success {
+                output_matched: true,
+                total_num_cycles: cycles,
+                region_cycles: HashMap::new(),
+                execution_duration: Duration::from_millis(10),
+                statistics: None,
+            }),
+            proving: Some(ProvingMetrics::Success {
+                output_matched: true,
+                proof_size: 1_000,
+                proving_time_ms,
+                verification_time_ms: 10,
+                statistics: None,
+            }),
+            verification: None,
+            resources: None,
+            provenance: None,
+            profile: None,
+            per_gas: None,
+        }
+    }
+
+    fn loaded(runs: impl IntoIterator<Item = BenchmarkRun<serde_json::Value>>) -> LoadedRuns {
+        runs.into_iter()
+            .map(|run| {
+                let key = RunKey {
+                    group: String::new(),
+                    name: run.name.clone(),
+                };
+                (key, run)
+            })
+            .collect()
+    }
+
+    fn policy(toml: &str) -> RegressionPolicy {
+        toml::from_str(toml).expect("valid policy")
+    }
+
+    #[test]
+    fn threshold_requires_every_tolerance_to_be_exceeded() {
+        let threshold = Threshold {
+            relative: Some(0.05),
+            absolute: Some(100.0),
+        };
+
+        assert!(!threshold.is_exceeded_by(&MetricDelta::new(1_000.0, 1_090.0)));
+        assert!(!threshold.is_exceeded_by(&MetricDelta::new(10_000.0, 10_200.0)));
+        assert!(threshold.is_exceeded_by(&MetricDelta::new(1_000.0, 1_200.0)));
+        assert!(!threshold.is_exceeded_by(&MetricDelta::new(1_000.0, 500.0)));
+        assert!(threshold.is_exceeded_by(&MetricDelta::new(0.0, 200.0)));
+        assert!(Threshold::default().is_exceeded_by(&MetricDelta::new(1.0, 2.0)));
+    }
+
+    #[test]
+    fn flags_threshold_crash_and_output_regressions() {
+        let baseline = loaded([
+            run("a", 1_000, 1_000),
+            run("b", 1_000, 1_000),
+            run("c", 1, 1),
+        ]);
+        let mut crashed = run("b", 1_000, 1_000);
+        crashed.proving = Some(ProvingMetrics::Crashed(CrashInfo {
+            reason: "timeout".into(),
+        }));
+        let mut mismatched = run("c", 1, 1);
+        mismatched.execution = Some(ExecutionMetrics::Success {
+            output_matched: false,
+            total_num_cycles: 1,
+            region_cycles: HashMap::new(),
+            execution_duration: Duration::from_millis(10),
+            statistics: None,
+        });
+        let candidate = loaded([run("a", 1_005, 1_500), crashed, mismatched]);
+
+        let report = RegressionReport::new(
+            &baseline,
+            &candidate,
+            &policy(
+                r#"
+                [thresholds.total_num_cycles]
+                relative = 0.01
+
+                [thresholds.proving_time_ms]
+                relative = 0.10
+                absolute = 100
+                "#,
+            ),
+        );
+
+        assert!(!report.passed());
+        assert_eq!(report.checked, 3);
+        let kinds: Vec<_> = report
+            .regressions
+            .iter()
+            .map(|regression| (regression.key.name.as_str(), &regression.kind))
+            .collect();
+        assert_eq!(kinds.len(), 3);
+        assert!(matches!(
+            kinds[0],
+            (
+                "a",
+                RegressionKind::Threshold {
+                    metric: RegressionMetric::ProvingTimeMs,
+                    ..
+                }
+            )
+        ));
+        assert!(matches!(
+            kinds[1],
+            (
+                "b",
+                RegressionKind::Crashed {
+                    workload: Workload::Proving,
+                    ..
+                }
+            )
+        ));
+        assert!(matches!(
+            kinds[2],
+            (
+                "c",
+                RegressionKind::OutputMismatch {
+                    workload: Workload::Execution
+                }
+            )
+        ));
+        assert!(
+            report
+                .to_markdown()
+                .contains("- `b`: proving crashed: timeout")
+        );
+    }
+
+    #[test]
+    fn missing_fixtures_only_fail_when_configured() {
+        let baseline = loaded([run("a", 1, 1)]);
+        let candidate = loaded([]);
+
+        let lenient = RegressionReport::new(&baseline, &candidate, &RegressionPolicy::default());
+        assert!(lenient.passed());
+        assert_eq!(lenient.missing, 1);
+
+        let strict =
+            RegressionReport::new(&baseline, &candidate, &policy("fail_on_missing = true"));
+        assert!(!strict.passed());
+    }
+
+    #[test]
+    fn policy_rejects_unknown_metrics() {
+        let err = toml::from_str::<RegressionPolicy>("[thresholds.total_cycles]\nrelative = 0.1")
+            .unwrap_err();
+        assert!(err.to_string().contains("total_cycles"));
+    }
+}
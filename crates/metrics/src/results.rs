@@ -0,0 +1,481 @@
+//! Indexed access to the results root written by `ere-hosts`.
+//!
+//! A results root has this layout:
+//!
+//! ```text
+//! <root>/
+//!   hardware.json
+//!   <el>-<el-version>/
+//!     <zkvm>-<sdk-version>/
+//!       <fixture>.json
+//! ```
+//!
+//! Guest programs that are not tied to an execution client write their zkVM directories directly
+//! below the root. [`ResultsIndex`] loads every run below a root once and answers queries by
+//! execution client, zkVM, their versions, fixture, action and outcome.
+
+use crate::{
+    BenchmarkRun, ExecutionMetrics, HardwareInfo, MetricsError, ProvingMetrics,
+    VerificationMetrics,
+    compare::{LoadedRuns, RunKey},
+};
+use std::{
+    collections::BTreeSet,
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Name of the hardware description written at the results root.
+pub const HARDWARE_FILE_NAME: &str = "hardware.json";
+
+/// Files written next to benchmark runs that are not `BenchmarkRun` JSON.
+const NON_RUN_FILES: &[&str] = &[HARDWARE_FILE_NAME, "run.json"];
+
+/// Returns the directory name of a versioned component, `<name>-<version>`.
+pub fn versioned_dir_name(name: &str, version: &str) -> String {
+    format!("{name}-{version}")
+}
+
+/// Splits a `<name>-<version>` directory name at its first `-`.
+///
+/// Returns no version for names without a `-`.
+pub fn split_versioned_dir_name(dir_name: &str) -> (&str, Option<&str>) {
+    match dir_name.split_once('-') {
+        Some((name, version)) => (name, Some(version)),
+        None => (dir_name, None),
+    }
+}
+
+/// Returns the directory holding the runs of one zkVM, below the execution client directory
+/// `el_dir` when given.
+pub fn run_dir(root: &Path, el_dir: Option<&str>, zkvm_dir: &str) -> PathBuf {
+    root.join(el_dir.unwrap_or("")).join(zkvm_dir)
+}
+
+/// Returns the path of the run of `fixture`, see [`run_dir`].
+pub fn run_path(root: &Path, el_dir: Option<&str>, zkvm_dir: &str, fixture: &str) -> PathBuf {
+    run_dir(root, el_dir, zkvm_dir).join(format!("{fixture}.json"))
+}
+
+/// Returns the path of the hardware description of a results root.
+pub fn hardware_path(root: &Path) -> PathBuf {
+    root.join(HARDWARE_FILE_NAME)
+}
+
+/// Workload a run recorded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum RunAction {
+    /// zkVM execution.
+    Execute,
+    /// Proving, including verification of the fresh proof.
+    Prove,
+    /// Standalone verification of a proof loaded from disk.
+    Verify,
+}
+
+/// Outcome of the workload a run recorded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum RunStatus {
+    /// The workload completed.
+    Success,
+    /// The workload crashed.
+    Crashed,
+}
+
+/// A benchmark run and where it sits in the results root.
+#[derive(Debug, Clone)]
+pub struct IndexedRun {
+    /// Path of the run file.
+    pub path: PathBuf,
+    /// Directory of the run relative to the results root, using `/` separators.
+    pub group: String,
+    /// Execution client, from the `<el>-<el-version>` directory.
+    pub el: Option<String>,
+    /// Execution client version.
+    pub el_version: Option<String>,
+    /// zkVM, from the `<zkvm>-<sdk-version>` directory.
+    pub zkvm: Option<String>,
+    /// zkVM SDK version.
+    pub sdk_version: Option<String>,
+    /// The run.
+    pub run: BenchmarkRun<serde_json::Value>,
+}
+
+impl IndexedRun {
+    fn new(path: PathBuf, group: &str, run: BenchmarkRun<serde_json::Value>) -> Self {
+        let dirs: Vec<&str> = group.split('/').filter(|dir| !dir.is_empty()).collect();
+        let (el_dir, zkvm_dir) = match dirs.as_slice() {
+            [] => (None, None),
+            [zkvm] => (None, Some(*zkvm)),
+            [el, .., zkvm] => (Some(*el), Some(*zkvm)),
+        };
+        let split = |dir: Option<&str>| {
+            dir.map(split_versioned_dir_name)
+                .map_or((None, None), |(name, version)| {
+                    (Some(name.to_string()), version.map(str::to_string))
+                })
+        };
+        let (el, el_version) = split(el_dir);
+        let (zkvm, sdk_version) = split(zkvm_dir);
+
+        Self {
+            path,
+            group: group.to_string(),
+            el,
+            el_version,
+            zkvm,
+            sdk_version,
+            run,
+        }
+    }
+
+    /// Fixture name, as stored in [`BenchmarkRun::name`].
+    pub fn fixture(&self) -> &str {
+        &self.run.name
+    }
+
+    /// Key of the run in [`LoadedRuns`].
+    pub fn key(&self) -> RunKey {
+        RunKey {
+            group: self.group.clone(),
+            name: self.run.name.clone(),
+        }
+    }
+
+    /// Workload the run recorded, preferring proving over execution over verification when a run
+    /// holds several.
+    pub const fn action(&self) -> Option<RunAction> {
+        if self.run.proving.is_some() {
+            Some(RunAction::Prove)
+        } else if self.run.execution.is_some() {
+            Some(RunAction::Execute)
+        } else if self.run.verification.is_some() {
+            Some(RunAction::Verify)
+        } else {
+            None
+        }
+    }
+
+    /// Outcome of the workload of [`Self::action`].
+    pub const fn status(&self) -> Option<RunStatus> {
+        let crashed = match (
+            &self.run.proving,
+            &self.run.execution,
+            &self.run.verification,
+        ) {
+            (Some(proving), ..) => matches!(proving, ProvingMetrics::Crashed(_)),
+            (None, Some(execution), _) => matches!(execution, ExecutionMetrics::Crashed(_)),
+            (None, None, Some(verification)) => {
+                matches!(verification, VerificationMetrics::Crashed(_))
+            }
+            (None, None, None) => return None,
+        };
+        Some(if crashed {
+            RunStatus::Crashed
+        } else {
+            RunStatus::Success
+        })
+    }
+
+    /// Whether the public output matched the fixture's expected output, for successful
+    /// execution and proving runs.
+    pub const fn output_matched(&self) -> Option<bool> {
+        match (&self.run.proving, &self.run.execution) {
+            (Some(ProvingMetrics::Success { output_matched, .. }), _)
+            | (None, Some(ExecutionMetrics::Success { output_matched, .. })) => {
+                Some(*output_matched)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Criteria a run must meet to be returned by [`ResultsIndex::filter`].
+///
+/// Unset criteria match every run. Names and versions are compared exactly.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RunFilter {
+    /// Execution client name, such as `reth`.
+    pub el: Option<String>,
+    /// Execution client version.
+    pub el_version: Option<String>,
+    /// zkVM name, such as `sp1`.
+    pub zkvm: Option<String>,
+    /// zkVM SDK version.
+    pub sdk_version: Option<String>,
+    /// Fixture name.
+    pub fixture: Option<String>,
+    /// Recorded workload.
+    pub action: Option<RunAction>,
+    /// Outcome of the recorded workload.
+    pub status: Option<RunStatus>,
+    /// Whether the public output matched.
+    pub output_matched: Option<bool>,
+}
+
+impl RunFilter {
+    /// Returns whether `run` meets every set criterion.
+    pub fn matches(&self, run: &IndexedRun) -> bool {
+        fn same(wanted: Option<&String>, actual: Option<&String>) -> bool {
+            wanted.is_none_or(|wanted| actual == Some(wanted))
+        }
+        same(self.el.as_ref(), run.el.as_ref())
+            && same(self.el_version.as_ref(), run.el_version.as_ref())
+            && same(self.zkvm.as_ref(), run.zkvm.as_ref())
+            && same(self.sdk_version.as_ref(), run.sdk_version.as_ref())
+            && self
+                .fixture
+                .as_ref()
+                .is_none_or(|fixture| fixture == &run.run.name)
+            && self
+                .action
+                .is_none_or(|action| run.action() == Some(action))
+            && self
+                .status
+                .is_none_or(|status| run.status() == Some(status))
+            && self
+                .output_matched
+                .is_none_or(|matched| run.output_matched() == Some(matched))
+    }
+}
+
+/// Every run below a results root, with its hardware description.
+#[derive(Debug, Clone)]
+pub struct ResultsIndex {
+    root: PathBuf,
+    hardware: Option<HardwareInfo>,
+    runs: Vec<IndexedRun>,
+}
+
+impl ResultsIndex {
+    /// Loads every run below `root` and its `hardware.json`, if any.
+    ///
+    /// Runs are ordered by directory and fixture name.
+    ///
+    /// # Errors
+    ///
+    /// Returns `MetricsError::Io` if the folder cannot be walked or a file cannot be read.
+    /// Returns `MetricsError::Serde` if a JSON file is not a valid `BenchmarkRun` or the hardware
+    /// description is invalid.
+    pub fn open<P: AsRef<Path>>(root: P) -> Result<Self, MetricsError> {
+        let root = root.as_ref();
+        let hardware_path = hardware_path(root);
+        let hardware = if hardware_path.is_file() {
+            Some(HardwareInfo::from_path(hardware_path)?)
+        } else {
+            None
+        };
+        Ok(Self {
+            root: root.to_path_buf(),
+            hardware,
+            runs: index_runs(root)?,
+        })
+    }
+
+    /// The results root.
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Hardware the runs were recorded on, if the root has a `hardware.json`.
+    pub const fn hardware(&self) -> Option<&HardwareInfo> {
+        self.hardware.as_ref()
+    }
+
+    /// Every run, ordered by directory and fixture name.
+    pub fn runs(&self) -> &[IndexedRun] {
+        &self.runs
+    }
+
+    /// Runs meeting `filter`, in index order.
+    pub fn filter<'a>(&'a self, filter: &'a RunFilter) -> impl Iterator<Item = &'a IndexedRun> {
+        self.runs.iter().filter(|run| filter.matches(run))
+    }
+
+    /// Execution clients with at least one run.
+    pub fn els(&self) -> BTreeSet<&str> {
+        self.runs
+            .iter()
+            .filter_map(|run| run.el.as_deref())
+            .collect()
+    }
+
+    /// zkVMs with at least one run.
+    pub fn zkvms(&self) -> BTreeSet<&str> {
+        self.runs
+            .iter()
+            .filter_map(|run| run.zkvm.as_deref())
+            .collect()
+    }
+
+    /// Fixtures with at least one run.
+    pub fn fixtures(&self) -> BTreeSet<&str> {
+        self.runs.iter().map(IndexedRun::fixture).collect()
+    }
+
+    /// Runs keyed by relative directory and fixture name, as used by comparisons and reports.
+    pub fn into_loaded_runs(self) -> LoadedRuns {
+        self.runs
+            .into_iter()
+            .map(|indexed| (indexed.key(), indexed.run))
+            .collect()
+    }
+}
+
+/// Loads every run below `root`, ordered by directory and fixture name.
+pub(crate) fn index_runs(root: &Path) -> Result<Vec<IndexedRun>, MetricsError> {
+    let mut runs = Vec::new();
+    index_dir(root, "", &mut runs)?;
+    runs.sort_by(|a, b| (&a.group, &a.run.name).cmp(&(&b.group, &b.run.name)));
+    Ok(runs)
+}
+
+fn index_dir(dir: &Path, group: &str, runs: &mut Vec<IndexedRun>) -> Result<(), MetricsError> {
+    let mut entries = fs::read_dir(dir)?.collect::<Result<Vec<_>, _>>()?;
+    entries.sort_by_key(|entry| entry.file_name());
+
+    for entry in entries {
+        let path = entry.path();
+        let file_name = entry.file_name().to_string_lossy().into_owned();
+        if entry.file_type()?.is_dir() {
+            let child_group = if group.is_empty() {
+                file_name
+            } else {
+                format!("{group}/{file_name}")
+            };
+            index_dir(&path, &child_group, runs)?;
+        } else if path.extension().is_some_and(|ext| ext == "json")
+            && !NON_RUN_FILES.contains(&file_name.as_str())
+        {
+            let run = BenchmarkRun::<serde_json::Value>::from_path(&path)?;
+            runs.push(IndexedRun::new(path, group, run));
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CrashInfo, ProvingMetrics};
+    use std::{collections::HashMap, time::Duration};
+
+    fn run(name: &str) -> BenchmarkRun<serde_json::Value> {
+        BenchmarkRun {
+            name: name.into(),
+            timestamp_completed: chrono::Utc::now(),
+            metadata: serde_json::Value::Null,
+            execution: Some(ExecutionMetrics::Success {
+                output_matched: true,
+                total_num_cycles: 10,
+                region_cycles: HashMap::new(),
+                execution_duration: Duration::from_millis(1),
+                statistics: None,
+            }),
+            proving: None,
+            verification: None,
+            resources: None,
+            provenance: None,
+            profile: None,
+            per_gas: None,
+        }
+    }
+
+    #[test]
+    fn indexes_runs_by_layout_and_filters_them() -> Result<(), MetricsError> {
+        let dir = tempfile::tempdir()?;
+        let root = dir.path();
+        run("a").to_path(run_path(root, Some("reth-v1.9.0"), "sp1-v5.2.1", "a"))?;
+        let mut crashed = run("b");
+        crashed.execution = Some(ExecutionMetrics::Crashed(CrashInfo {
+            reason: "oom".into(),
+        }));
+        crashed.to_path(run_path(root, Some("reth-v1.9.0"), "sp1-v5.2.1", "b"))?;
+        let mut proved = run("a");
+        proved.execution = None;
+        proved.proving = Some(ProvingMetrics::Success {
+            output_matched: false,
+            proof_size: 1,
+            proving_time_ms: 1,
+            verification_time_ms: 1,
+            statistics: None,
+        });
+        proved.to_path(run_path(
+            root,
+            Some("ethrex-v9.0.0-rc1"),
+            "zisk-v0.15.0",
+            "a",
+        ))?;
+        run("guest").to_path(run_path(root, None, "risc0-v3.0.3", "guest"))?;
+        fs::write(
+            run_dir(root, Some("reth-v1.9.0"), "sp1-v5.2.1").join("run.json"),
+            "{}",
+        )?;
+        HardwareInfo {
+            cpu_model: "cpu".into(),
+            total_ram_gib: 1,
+            gpus: Vec::new(),
+        }
+        .to_path(hardware_path(root))?;
+
+        let index = ResultsIndex::open(root)?;
+        assert_eq!(index.hardware().map(|h| h.cpu_model.as_str()), Some("cpu"));
+        assert_eq!(index.runs().len(), 4);
+        assert_eq!(index.els(), BTreeSet::from(["ethrex", "reth"]));
+        assert_eq!(index.zkvms(), BTreeSet::from(["risc0", "sp1", "zisk"]));
+        assert_eq!(index.fixtures(), BTreeSet::from(["a", "b", "guest"]));
+
+        let ethrex = &index.runs()[0];
+        assert_eq!(ethrex.el.as_deref(), Some("ethrex"));
+        assert_eq!(ethrex.el_version.as_deref(), Some("v9.0.0-rc1"));
+        assert_eq!(ethrex.sdk_version.as_deref(), Some("v0.15.0"));
+        assert_eq!(ethrex.action(), Some(RunAction::Prove));
+        assert_eq!(ethrex.output_matched(), Some(false));
+
+        let risc0 = RunFilter {
+            zkvm: Some("risc0".into()),
+            ..RunFilter::default()
+        };
+        let guest = index.filter(&risc0).next().expect("risc0 run");
+        assert_eq!(guest.el, None);
+        assert_eq!(guest.sdk_version.as_deref(), Some("v3.0.3"));
+
+        let crashed = RunFilter {
+            el: Some("reth".into()),
+            status: Some(RunStatus::Crashed),
+            ..RunFilter::default()
+        };
+        let names: Vec<_> = index.filter(&crashed).map(IndexedRun::fixture).collect();
+        assert_eq!(names, ["b"]);
+
+        let matched = RunFilter {
+            action: Some(RunAction::Execute),
+            output_matched: Some(true),
+            ..RunFilter::default()
+        };
+        assert_eq!(index.filter(&matched).count(), 2);
+
+        let loaded = index.into_loaded_runs();
+        assert!(loaded.contains_key(&RunKey {
+            group: "reth-v1.9.0/sp1-v5.2.1".into(),
+            name: "b".into(),
+        }));
+
+        Ok(())
+    }
+
+    #[test]
+    fn splits_versioned_directory_names() {
+        assert_eq!(
+            split_versioned_dir_name("reth-v1.9.0"),
+            ("reth", Some("v1.9.0"))
+        );
+        assert_eq!(
+            split_versioned_dir_name("zesu-bal-devnet-7"),
+            ("zesu", Some("bal-devnet-7"))
+        );
+        assert_eq!(split_versioned_dir_name("sp1"), ("sp1", None));
+        assert_eq!(versioned_dir_name("sp1", "5.2.1"), "sp1-5.2.1");
+    }
+}
@@ -0,0 +1,32 @@
+//! Order statistics shared by the reports of this crate and of `benchmark-runner`.
+
+/// Linear interpolation between the closest ranks of a sorted, non-empty slice.
+pub fn percentile(sorted: &[f64], fraction: f64) -> f64 {
+    let rank = fraction * (sorted.len() - 1) as f64;
+    let lower = rank.floor() as usize;
+    let upper = (lower + 1).min(sorted.len() - 1);
+    (rank - lower as f64).mul_add(sorted[upper] - sorted[lower], sorted[lower])
+}
+
+/// Median of `values`, the mean of the two middle values for an even count, or `None` if there
+/// are none.
+pub fn median(mut values: Vec<f64>) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    values.sort_by(f64::total_cmp);
+    Some(percentile(&values, 0.5))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn median_averages_the_middle_values() {
+        assert_eq!(median(vec![3.0, 1.0, 2.0]), Some(2.0));
+        assert_eq!(median(vec![4.0, 1.0, 3.0, 2.0]), Some(2.5));
+        assert_eq!(median(Vec::new()), None);
+        assert_eq!(percentile(&[10.0, 20.0], 0.95), 19.5);
+    }
+}
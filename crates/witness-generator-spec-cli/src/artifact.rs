@@ -115,6 +115,16 @@ pub(crate) struct ArtifactIndexEntry {
     pub(crate) collected_at: String,
     pub(crate) stateless_input_byte_length: usize,
     pub(crate) path: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) chain_status: Option<ChainStatus>,
+}
+
+/// Whether an artifact's block is on the canonical chain, as last checked by the collector.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum ChainStatus {
+    Canonical,
+    Orphaned,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -359,10 +369,21 @@ impl StatelessInputArtifact {
             collected_at: self.collected_at.clone(),
             stateless_input_byte_length: self.stateless_input_byte_length,
             path: path_to_slash_string(path),
+            chain_status: None,
         }
     }
 }
 
+impl ArtifactIndexEntry {
+    /// Returns whether both entries describe the same artifact, ignoring its chain status.
+    pub(crate) fn same_artifact(&self, other: &Self) -> bool {
+        Self {
+            chain_status: other.chain_status,
+            ..self.clone()
+        } == *other
+    }
+}
+
 pub(crate) fn one_shot_fixture_json(generated: &GeneratedInput) -> anyhow::Result<Vec<u8>> {
     EestFixture::from_generated(
         generated,
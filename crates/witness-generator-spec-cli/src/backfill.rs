@@ -0,0 +1,309 @@
+use std::{collections::BTreeSet, iter, num::NonZeroUsize, ops::RangeInclusive};
+
+use anyhow::Context;
+use tokio::task::JoinSet;
+use tracing::{info, warn};
+use witness_generator_spec_cli::BlockSelector;
+
+use crate::{
+    collector::{network_client, persist_generated},
+    config::CollectorConfig,
+    export,
+};
+
+const COLLECTION_MODE: &str = "backfill";
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct BackfillSummary {
+    pub(crate) collected: usize,
+    pub(crate) skipped: usize,
+    pub(crate) failed: Vec<u64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct BatchGaps {
+    pub(crate) start: u64,
+    pub(crate) end: u64,
+    pub(crate) missing: Vec<u64>,
+}
+
+/// Collects every block of `blocks` that is not in `index.jsonl`, fetching up to `concurrency`
+/// blocks at a time. Blocks that fail are reported in the summary instead of aborting the run.
+pub(crate) async fn backfill(
+    config: &CollectorConfig,
+    blocks: RangeInclusive<u64>,
+    concurrency: NonZeroUsize,
+) -> anyhow::Result<BackfillSummary> {
+    let indexed = export::indexed_block_numbers(config)?;
+    let mut summary = BackfillSummary {
+        skipped: indexed.range(blocks.clone()).count(),
+        ..BackfillSummary::default()
+    };
+    info!(
+        from = *blocks.start(),
+        to = *blocks.end(),
+        skipped = summary.skipped,
+        concurrency = concurrency.get(),
+        "backfilling block range"
+    );
+
+    let client = network_client(config)?;
+    let mut pending = blocks.filter(|number| !indexed.contains(number));
+    let mut tasks = JoinSet::new();
+    loop {
+        while tasks.len() < concurrency.get() {
+            let Some(number) = pending.next() else {
+                break;
+            };
+            let client = client.clone();
+            tasks.spawn(async move {
+                let generated = client
+                    .stateless_input_bytes(BlockSelector::ExecutionBlockNumber(number))
+                    .await;
+                (number, generated)
+            });
+        }
+        let Some(joined) = tasks.join_next().await else {
+            break;
+        };
+
+        // Artifacts are written from this task only, so index appends never interleave.
+        let (number, generated) = joined.context("backfill task failed")?;
+        let persisted =
+            generated.and_then(|generated| persist_generated(config, COLLECTION_MODE, &generated));
+        match persisted {
+            Ok(persisted) => {
+                summary.collected += 1;
+                info!(
+                    block_number = number,
+                    block_hash = persisted.artifact.block_hash,
+                    path = %persisted.write.path.display(),
+                    "backfilled stateless EEST fixture",
+                );
+            }
+            Err(error) => {
+                warn!(block_number = number, ?error, "failed to backfill block");
+                summary.failed.push(number);
+            }
+        }
+    }
+
+    summary.failed.sort_unstable();
+    Ok(summary)
+}
+
+/// Lists the missing blocks of every incomplete batch overlapping `blocks`.
+///
+/// The range is widened to whole batches because export only packages complete batches.
+pub(crate) fn gap_report(
+    config: &CollectorConfig,
+    blocks: &RangeInclusive<u64>,
+) -> anyhow::Result<Vec<BatchGaps>> {
+    let available = export::available_block_numbers(config)?;
+    Ok(batch_gaps(&available, blocks, config.batch_size))
+}
+
+fn batch_gaps(
+    available: &BTreeSet<u64>,
+    blocks: &RangeInclusive<u64>,
+    batch_size: u64,
+) -> Vec<BatchGaps> {
+    let first = (blocks.start() / batch_size) * batch_size;
+    let last = (blocks.end() / batch_size) * batch_size;
+    iter::successors(Some(first), |start| {
+        start.checked_add(batch_size).filter(|next| *next <= last)
+    })
+    .filter_map(|start| {
+        let end = start.saturating_add(batch_size - 1);
+        let missing = (start..=end)
+            .filter(|number| !available.contains(number))
+            .collect::<Vec<_>>();
+        (!missing.is_empty()).then_some(BatchGaps {
+            start,
+            end,
+            missing,
+        })
+    })
+    .collect()
+}
+
+/// Renders one line per incomplete batch, such as `500-999: 3 missing: 512, 620-621`.
+pub(crate) fn render_gap_report(gaps: &[BatchGaps]) -> String {
+    gaps.iter()
+        .map(|gap| {
+            format!(
+                "{}-{}: {} missing: {}\n",
+                gap.start,
+                gap.end,
+                gap.missing.len(),
+                format_block_ranges(&gap.missing)
+            )
+        })
+        .collect()
+}
+
+/// Formats sorted block numbers as comma separated runs, such as `2, 6-7, 9`.
+pub(crate) fn format_block_ranges(blocks: &[u64]) -> String {
+    let mut runs: Vec<(u64, u64)> = Vec::new();
+    for &number in blocks {
+        match runs.last_mut() {
+            Some((_, end)) if end.checked_add(1) == Some(number) => *end = number,
+            _ => runs.push((number, number)),
+        }
+    }
+    runs.iter()
+        .map(|&(start, end)| {
+            if start == end {
+                start.to_string()
+            } else {
+                format!("{start}-{end}")
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use alloy_primitives::B256;
+
+    use crate::artifact::{
+        ArtifactIndexEntry, ChainStatus, append_index_entry, read_artifact, test_generated_input,
+    };
+
+    use super::*;
+
+    #[test]
+    fn batch_gaps_cover_whole_overlapping_batches() {
+        let available = BTreeSet::from([0, 1, 3, 4, 5, 8, 9, 10, 11]);
+
+        let gaps = batch_gaps(&available, &(2..=6), 4);
+
+        assert_eq!(
+            gaps,
+            vec![
+                BatchGaps {
+                    start: 0,
+                    end: 3,
+                    missing: vec![2],
+                },
+                BatchGaps {
+                    start: 4,
+                    end: 7,
+                    missing: vec![6, 7],
+                },
+            ]
+        );
+        assert_eq!(
+            render_gap_report(&gaps),
+            "0-3: 1 missing: 2\n4-7: 2 missing: 6-7\n"
+        );
+        assert!(batch_gaps(&available, &(8..=11), 4).is_empty());
+    }
+
+    #[test]
+    fn formats_block_ranges() {
+        assert_eq!(format_block_ranges(&[]), "");
+        assert_eq!(format_block_ranges(&[2, 6, 7, 9]), "2, 6-7, 9");
+        assert_eq!(
+            format_block_ranges(&[u64::MAX - 1, u64::MAX]),
+            format!("{}-{}", u64::MAX - 1, u64::MAX)
+        );
+    }
+
+    #[test]
+    fn backfilled_artifacts_are_indexed_and_close_gaps() {
+        let config = test_config("gaps");
+        for number in [0, 1, 3] {
+            persist_generated(
+                &config,
+                COLLECTION_MODE,
+                &test_generated_input(number, B256::repeat_byte(0xaa)),
+            )
+            .unwrap();
+        }
+
+        assert_eq!(
+            export::indexed_block_numbers(&config).unwrap(),
+            BTreeSet::from([0, 1, 3])
+        );
+        let gaps = gap_report(&config, &(0..=3)).unwrap();
+        assert_eq!(gaps.len(), 1);
+        assert_eq!(gaps[0].missing, vec![2]);
+
+        let persisted = persist_generated(
+            &config,
+            COLLECTION_MODE,
+            &test_generated_input(2, B256::repeat_byte(0xbb)),
+        )
+        .unwrap();
+        assert_eq!(
+            read_artifact(&persisted.write.path)
+                .unwrap()
+                .collection_mode,
+            COLLECTION_MODE
+        );
+        assert!(gap_report(&config, &(0..=3)).unwrap().is_empty());
+    }
+
+    #[test]
+    fn orphan_only_heights_are_left_for_backfill() {
+        let config = test_config("orphans");
+        for number in 0..4 {
+            persist_generated(
+                &config,
+                COLLECTION_MODE,
+                &test_generated_input(number, B256::repeat_byte(0xaa)),
+            )
+            .unwrap();
+        }
+        let orphan = export::index_entries(&config)
+            .unwrap()
+            .into_iter()
+            .find(|entry| entry.block_number == 2)
+            .unwrap();
+        append_index_entry(
+            &config.index_path(),
+            &ArtifactIndexEntry {
+                chain_status: Some(ChainStatus::Orphaned),
+                ..orphan
+            },
+        )
+        .unwrap();
+
+        assert_eq!(
+            export::indexed_block_numbers(&config).unwrap(),
+            BTreeSet::from([0, 1, 3])
+        );
+        let gaps = gap_report(&config, &(0..=3)).unwrap();
+        assert_eq!(gaps.len(), 1);
+        assert_eq!(gaps[0].missing, vec![2]);
+    }
+
+    fn test_config(name: &str) -> CollectorConfig {
+        let out_root = std::env::temp_dir().join(format!(
+            "witness-generator-spec-cli-backfill-{name}-{}",
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&out_root);
+        CollectorConfig {
+            network: "glamsterdam-devnet-5".to_owned(),
+            cl_endpoints: vec![witness_generator_spec_cli::Endpoint::new("http://cl")],
+            el_endpoints: vec![witness_generator_spec_cli::Endpoint::new("http://el")],
+            out_root,
+            poll_interval: std::time::Duration::from_secs(4),
+            request_timeout: std::time::Duration::from_secs(30),
+            rpc_retry: witness_generator_spec_cli::RetryConfig::default(),
+            batch_size: 4,
+            r2: None,
+            max_catch_up_blocks: 64,
+            reorg_check_depth: 32,
+            head_source: crate::config::HeadSource::Poll,
+            head_event_timeout: std::time::Duration::from_secs(30),
+            metrics_listen: None,
+            metrics_textfile: None,
+        }
+    }
+}
@@ -649,13 +649,20 @@ mod tests {
         let _ = fs::remove_dir_all(&out_root);
         CollectorConfig {
             network: "glamsterdam-devnet-5".to_owned(),
-            cl_url: "http://cl".to_owned(),
-            el_url: "http://el".to_owned(),
+            cl_endpoints: vec![witness_generator_spec_cli::Endpoint::new("http://cl")],
+            el_endpoints: vec![witness_generator_spec_cli::Endpoint::new("http://el")],
             out_root,
             poll_interval: Duration::from_secs(4),
             request_timeout: Duration::from_secs(30),
+            rpc_retry: witness_generator_spec_cli::RetryConfig::default(),
             batch_size,
             r2: None,
+            max_catch_up_blocks: 64,
+            reorg_check_depth: 32,
+            head_source: crate::config::HeadSource::Poll,
+            head_event_timeout: Duration::from_secs(30),
+            metrics_listen: None,
+            metrics_textfile: None,
         }
     }
 }
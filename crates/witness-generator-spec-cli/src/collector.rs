@@ -1,20 +1,25 @@
-use std::{fs, path::PathBuf};
+use std::{collections::BTreeMap, fs, ops::RangeInclusive, path::PathBuf, sync::Arc};
 
 use anyhow::Context;
 use serde::{Deserialize, Serialize};
 use tokio::time;
-use tracing::{info, warn};
+use tracing::{debug, info, warn};
 use witness_generator_spec_cli::{
-    BlockSelector, GeneratedInput, NetworkWitnessClient, NetworkWitnessConfig,
+    BlockSelector, GeneratedInput, HeadEventStream, NetworkWitnessClient, NetworkWitnessConfig,
 };
+use zkevm_metrics::prometheus::{self, Registry};
 
 use crate::{
     artifact::{
-        self, ArtifactWriteResult, StatelessInputArtifact, append_index_entry, write_json_atomic,
+        self, ArtifactIndexEntry, ArtifactWriteResult, ChainStatus, StatelessInputArtifact,
+        append_index_entry, write_json_atomic,
     },
-    config::CollectorConfig,
+    config::{CollectorConfig, HeadSource},
+    export,
 };
 
+const CATCH_UP_COLLECTION_MODE: &str = "catch-up";
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub(crate) struct PersistedArtifact {
     pub(crate) artifact: StatelessInputArtifact,
@@ -30,21 +35,84 @@ struct CollectorState {
     updated_at: String,
 }
 
+/// Head block number seen by one collection, and the artifact stored if the head was new.
+#[derive(Debug)]
+struct HeadCollection {
+    head_block_number: u64,
+    persisted: Option<PersistedArtifact>,
+}
+
+/// Hash and number of the last block recorded in `state.json`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct LastCollected {
+    block_hash: String,
+    block_number: u64,
+}
+
+/// Blocks a head skipped past since the last collected block.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct CatchUp {
+    /// Blocks fetched by number before the head is recorded, possibly empty.
+    blocks: RangeInclusive<u64>,
+    /// Older blocks beyond `max_catch_up_blocks`, left for `backfill`.
+    abandoned: Option<RangeInclusive<u64>>,
+}
+
+const COLLECTED_TOTAL: &str = "witness_collector_collected_total";
+const UNCHANGED_HEAD_TOTAL: &str = "witness_collector_unchanged_head_total";
+const FAILURES_TOTAL: &str = "witness_collector_failures_total";
+const LAST_COLLECTED_BLOCK: &str = "witness_collector_last_collected_block";
+const HEAD_BLOCK: &str = "witness_collector_head_block";
+const LAG_BLOCKS: &str = "witness_collector_lag_blocks";
+
+/// Index entries of recent heights whose chain status is re-checked after every new head.
+#[derive(Debug)]
+struct ReorgTracker {
+    depth: u64,
+    entries: BTreeMap<u64, Vec<ArtifactIndexEntry>>,
+}
+
+/// Prometheus metrics of the collect loop, labelled by network.
+#[derive(Debug)]
+struct CollectorMetrics {
+    registry: Arc<Registry>,
+    textfile: Option<PathBuf>,
+    network: String,
+    last_collected: Option<u64>,
+    head: Option<u64>,
+}
+
 pub(crate) async fn collect(config: CollectorConfig, once: bool) -> anyhow::Result<()> {
-    let mut network_config =
-        NetworkWitnessConfig::new(config.cl_url.clone(), config.el_url.clone());
-    network_config.timeout = config.request_timeout;
-    let client = NetworkWitnessClient::new(network_config)?;
-    let mut last_head_hash = read_state(&config.state_path())?.map(|state| state.last_head_hash);
+    let client = network_client(&config)?;
+    let state = read_state(&config.state_path())?;
+    let mut metrics =
+        CollectorMetrics::from_config(&config, state.as_ref().map(|s| s.last_block_number))?;
+    let mut tracker = ReorgTracker::load(&config, state.as_ref().map(|s| s.last_block_number))?;
+    let mut last = state.map(LastCollected::from);
 
     if once {
-        collect_head_once(&client, &config, &mut last_head_hash).await?;
+        let result = collect_head_once(&client, &config, &mut last, &mut tracker).await;
+        if let Some(metrics) = &mut metrics {
+            metrics.record(&result);
+        }
+        result?;
         return Ok(());
     }
 
+    let mut events = match config.head_source {
+        HeadSource::Poll => None,
+        HeadSource::BeaconEvents => Some(client.head_events()?),
+    };
     loop {
-        match collect_head_once(&client, &config, &mut last_head_hash).await {
-            Ok(Some(persisted)) => {
+        let result = collect_head_once(&client, &config, &mut last, &mut tracker).await;
+        if let Some(metrics) = &mut metrics {
+            metrics.record(&result);
+        }
+        match result {
+            Ok(HeadCollection {
+                persisted: Some(persisted),
+                ..
+            }) => {
                 info!(
                     block_number = persisted.artifact.block_number,
                     block_hash = persisted.artifact.block_hash,
@@ -52,29 +120,169 @@ pub(crate) async fn collect(config: CollectorConfig, once: bool) -> anyhow::Resu
                     "collected stateless EEST fixture",
                 );
             }
-            Ok(None) => {}
+            Ok(_) => {}
             Err(error) => {
                 warn!(?error, "failed to collect stateless EEST fixture");
             }
         }
+        wait_for_next_head(&config, events.as_mut()).await;
+    }
+}
+
+/// Waits for the next head event, or for one poll interval without an event stream.
+///
+/// While the stream cannot connect the collector polls, and every wait retries connecting. A
+/// stream that stays silent for `head_event_timeout` is treated as stale: the head is collected
+/// anyway and the stream reconnects on the next wait.
+async fn wait_for_next_head(config: &CollectorConfig, events: Option<&mut HeadEventStream>) {
+    let Some(events) = events else {
         time::sleep(config.poll_interval).await;
+        return;
+    };
+    match time::timeout(config.head_event_timeout, events.next()).await {
+        Ok(Ok(event)) => {
+            debug!(slot = event.slot, topic = ?event.topic, "received head event");
+        }
+        Ok(Err(error)) => {
+            warn!(
+                ?error,
+                "head event stream failed; polling until it reconnects"
+            );
+            time::sleep(config.poll_interval).await;
+        }
+        Err(_) => {
+            warn!(
+                timeout = ?config.head_event_timeout,
+                "no head event received in time; reconnecting head event stream",
+            );
+            events.disconnect();
+        }
     }
 }
 
+/// Builds a network client for the configured endpoints, request timeout and retry policy.
+pub(crate) fn network_client(config: &CollectorConfig) -> anyhow::Result<NetworkWitnessClient> {
+    NetworkWitnessClient::new(NetworkWitnessConfig {
+        cl_endpoints: config.cl_endpoints.clone(),
+        el_endpoints: config.el_endpoints.clone(),
+        timeout: config.request_timeout,
+        retry: config.rpc_retry,
+    })
+}
+
 async fn collect_head_once(
     client: &NetworkWitnessClient,
     config: &CollectorConfig,
-    last_head_hash: &mut Option<String>,
-) -> anyhow::Result<Option<PersistedArtifact>> {
+    last: &mut Option<LastCollected>,
+    tracker: &mut ReorgTracker,
+) -> anyhow::Result<HeadCollection> {
+    // Duplicate head events and unchanged polls only cost one payload envelope request.
+    if let Some(last) = last.as_ref() {
+        let head_hash = client
+            .head_block_hash()
+            .await
+            .context("failed to fetch head block hash")?;
+        if last.block_hash == head_hash.to_string() {
+            return Ok(HeadCollection {
+                head_block_number: last.block_number,
+                persisted: None,
+            });
+        }
+    }
+
     let generated = client
         .stateless_input_bytes(BlockSelector::Head)
         .await
         .context("failed to generate stateless input bytes for head")?;
-    let persisted = collect_generated(config, generated, last_head_hash.as_deref())?;
+    let head_changed = last
+        .as_ref()
+        .is_none_or(|last| last.block_hash != generated.block_hash.to_string());
+    let plan = last.as_ref().and_then(|last| {
+        catch_up_plan(
+            last.block_number,
+            generated.block_number,
+            config.max_catch_up_blocks,
+        )
+    });
+    if let Some(plan) = plan.filter(|_| head_changed) {
+        catch_up(client, config, plan, last, tracker).await?;
+    }
+
+    let head_block_number = generated.block_number;
+    let last_head_hash = last.as_ref().map(|last| last.block_hash.as_str());
+    let persisted = collect_generated(config, generated, last_head_hash)?;
     if let Some(persisted) = &persisted {
-        *last_head_hash = Some(persisted.artifact.block_hash.clone());
+        *last = Some(LastCollected::from(&persisted.artifact));
+        tracker.track(persisted);
+        tracker
+            .check(client, config, persisted.artifact.block_number)
+            .await;
+    }
+    Ok(HeadCollection {
+        head_block_number,
+        persisted,
+    })
+}
+
+/// Plans fetching the blocks between the last collected block and a new head, limited to the
+/// `max_blocks` blocks right below the head.
+fn catch_up_plan(
+    last_block_number: u64,
+    head_block_number: u64,
+    max_blocks: u64,
+) -> Option<CatchUp> {
+    let first = last_block_number.checked_add(1)?;
+    let last = head_block_number.checked_sub(1)?;
+    if first > last {
+        return None;
+    }
+    let window_start = match max_blocks {
+        0 => head_block_number,
+        max_blocks => first.max(head_block_number.saturating_sub(max_blocks)),
+    };
+    Some(CatchUp {
+        blocks: window_start..=last,
+        abandoned: (window_start > first).then(|| first..=window_start - 1),
+    })
+}
+
+/// Collects the planned blocks by execution number, advancing the state after each block so an
+/// interrupted catch-up resumes where it stopped.
+async fn catch_up(
+    client: &NetworkWitnessClient,
+    config: &CollectorConfig,
+    plan: CatchUp,
+    last: &mut Option<LastCollected>,
+    tracker: &mut ReorgTracker,
+) -> anyhow::Result<()> {
+    if let Some(abandoned) = &plan.abandoned {
+        warn!(
+            from = *abandoned.start(),
+            to = *abandoned.end(),
+            max_catch_up_blocks = config.max_catch_up_blocks,
+            "head skipped more blocks than the catch-up window; run backfill for the older blocks",
+        );
+    }
+
+    for number in plan.blocks {
+        // The client retries transient RPC failures, so a skipped block that still fails stops
+        // the catch-up until the next poll.
+        let generated = client
+            .stateless_input_bytes(BlockSelector::ExecutionBlockNumber(number))
+            .await
+            .with_context(|| format!("failed to catch up skipped block #{number}"))?;
+        let persisted = persist_generated(config, CATCH_UP_COLLECTION_MODE, &generated)?;
+        write_state(config, &persisted.artifact)?;
+        *last = Some(LastCollected::from(&persisted.artifact));
+        tracker.track(&persisted);
+        info!(
+            block_number = persisted.artifact.block_number,
+            block_hash = persisted.artifact.block_hash,
+            path = %persisted.write.path.display(),
+            "caught up skipped stateless EEST fixture",
+        );
     }
-    Ok(persisted)
+    Ok(())
 }
 
 pub(crate) fn collect_generated(
@@ -87,15 +295,265 @@ pub(crate) fn collect_generated(
         return Ok(None);
     }
 
-    let artifact = StatelessInputArtifact::from_generated(&config.network, "head", &generated)?;
+    let persisted = persist_generated(config, "head", &generated)?;
+    write_state(config, &persisted.artifact)?;
+
+    Ok(Some(persisted))
+}
+
+/// Writes the artifact of `generated` and indexes it when it was not stored before.
+pub(crate) fn persist_generated(
+    config: &CollectorConfig,
+    collection_mode: &str,
+    generated: &GeneratedInput,
+) -> anyhow::Result<PersistedArtifact> {
+    let artifact =
+        StatelessInputArtifact::from_generated(&config.network, collection_mode, generated)?;
     let write = artifact::write_artifact_atomic(&config.blocks_root(), &artifact)?;
     if write.created {
         let index_entry = artifact.index_entry(&PathBuf::from("blocks").join(&write.relative_path));
         append_index_entry(&config.index_path(), &index_entry)?;
     }
-    write_state(config, &artifact)?;
 
-    Ok(Some(PersistedArtifact { artifact, write }))
+    Ok(PersistedArtifact { artifact, write })
+}
+
+impl From<CollectorState> for LastCollected {
+    fn from(state: CollectorState) -> Self {
+        Self {
+            block_hash: state.last_head_hash,
+            block_number: state.last_block_number,
+        }
+    }
+}
+
+impl From<&StatelessInputArtifact> for LastCollected {
+    fn from(artifact: &StatelessInputArtifact) -> Self {
+        Self {
+            block_hash: artifact.block_hash.clone(),
+            block_number: artifact.block_number,
+        }
+    }
+}
+
+impl ReorgTracker {
+    /// Loads the index entries within `reorg_check_depth` blocks of the last collected block.
+    fn load(config: &CollectorConfig, last_block_number: Option<u64>) -> anyhow::Result<Self> {
+        let mut tracker = Self {
+            depth: config.reorg_check_depth,
+            entries: BTreeMap::new(),
+        };
+        let Some(last_block_number) = last_block_number.filter(|_| tracker.depth > 0) else {
+            return Ok(tracker);
+        };
+        let floor = last_block_number.saturating_sub(tracker.depth);
+        for entry in export::index_entries(config)? {
+            if entry.block_number >= floor {
+                tracker.insert(entry);
+            }
+        }
+        Ok(tracker)
+    }
+
+    /// Tracks a newly stored artifact. Artifacts that were already stored keep the entry
+    /// loaded from the index.
+    fn track(&mut self, persisted: &PersistedArtifact) {
+        if self.depth > 0 && persisted.write.created {
+            let path = PathBuf::from("blocks").join(&persisted.write.relative_path);
+            self.insert(persisted.artifact.index_entry(&path));
+        }
+    }
+
+    fn insert(&mut self, entry: ArtifactIndexEntry) {
+        let entries = self.entries.entry(entry.block_number).or_default();
+        match entries
+            .iter_mut()
+            .find(|tracked| tracked.path == entry.path)
+        {
+            Some(tracked) => *tracked = entry,
+            None => entries.push(entry),
+        }
+    }
+
+    /// Compares the tracked heights within `depth` blocks of the head against the canonical
+    /// chain. Heights the EL cannot serve are retried after the next head.
+    async fn check(
+        &mut self,
+        client: &NetworkWitnessClient,
+        config: &CollectorConfig,
+        head_block_number: u64,
+    ) {
+        if self.depth == 0 {
+            return;
+        }
+        self.entries = self
+            .entries
+            .split_off(&head_block_number.saturating_sub(self.depth));
+
+        let mut canonical_hashes = BTreeMap::new();
+        for &block_number in self.entries.keys() {
+            match client.block_hash_by_number(block_number).await {
+                Ok(hash) => {
+                    canonical_hashes.insert(block_number, hash.to_string());
+                }
+                Err(error) => {
+                    warn!(block_number, ?error, "failed to fetch canonical block hash");
+                }
+            }
+        }
+        self.apply_canonical_hashes(config, &canonical_hashes);
+    }
+
+    /// Appends an index entry for every tracked artifact whose chain status changed.
+    fn apply_canonical_hashes(
+        &mut self,
+        config: &CollectorConfig,
+        canonical_hashes: &BTreeMap<u64, String>,
+    ) {
+        for (block_number, entries) in &mut self.entries {
+            let Some(canonical_hash) = canonical_hashes.get(block_number) else {
+                continue;
+            };
+            for entry in entries.iter_mut() {
+                let status = if &entry.block_hash == canonical_hash {
+                    ChainStatus::Canonical
+                } else {
+                    ChainStatus::Orphaned
+                };
+                if entry.chain_status == Some(status) {
+                    continue;
+                }
+                let update = ArtifactIndexEntry {
+                    chain_status: Some(status),
+                    ..entry.clone()
+                };
+                match append_index_entry(&config.index_path(), &update) {
+                    Ok(()) => {
+                        info!(
+                            block_number,
+                            block_hash = update.block_hash,
+                            ?status,
+                            "updated artifact chain status",
+                        );
+                        *entry = update;
+                    }
+                    Err(error) => {
+                        warn!(
+                            block_number,
+                            ?error,
+                            "failed to record artifact chain status"
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl CollectorMetrics {
+    /// Builds the collector metrics when `metrics_listen` or `metrics_textfile` is configured.
+    fn from_config(
+        config: &CollectorConfig,
+        last_collected: Option<u64>,
+    ) -> anyhow::Result<Option<Self>> {
+        if config.metrics_listen.is_none() && config.metrics_textfile.is_none() {
+            return Ok(None);
+        }
+        let metrics = Self::new(
+            Arc::new(Registry::new()),
+            config.metrics_textfile.clone(),
+            &config.network,
+            last_collected,
+        );
+        if let Some(addr) = config.metrics_listen {
+            let server = prometheus::serve(Arc::clone(&metrics.registry), addr)
+                .with_context(|| format!("failed to serve metrics on {addr}"))?;
+            info!(addr = %server.local_addr(), "serving collector metrics at /metrics");
+        }
+        Ok(Some(metrics))
+    }
+
+    fn new(
+        registry: Arc<Registry>,
+        textfile: Option<PathBuf>,
+        network: &str,
+        last_collected: Option<u64>,
+    ) -> Self {
+        registry.register_counter(COLLECTED_TOTAL, "Head blocks collected");
+        registry.register_counter(
+            UNCHANGED_HEAD_TOTAL,
+            "Polls skipped because the head was already collected",
+        );
+        registry.register_counter(FAILURES_TOTAL, "Polls that failed to collect the head");
+        registry.register_gauge(LAST_COLLECTED_BLOCK, "Number of the last collected block");
+        registry.register_gauge(HEAD_BLOCK, "Number of the latest head block seen");
+        registry.register_gauge(
+            LAG_BLOCKS,
+            "Blocks between the head and the last collected block",
+        );
+        let mut metrics = Self {
+            registry,
+            textfile,
+            network: network.to_owned(),
+            last_collected: None,
+            head: None,
+        };
+        if let Some(number) = last_collected {
+            metrics.set_last_collected(number);
+        }
+        metrics
+    }
+
+    /// Records the outcome of one poll and the head it saw, then flushes the textfile.
+    fn record(&mut self, result: &anyhow::Result<HeadCollection>) {
+        let labels = [("network", self.network.as_str())];
+        match result {
+            Ok(collection) => {
+                match &collection.persisted {
+                    Some(persisted) => {
+                        self.registry.inc_counter(COLLECTED_TOTAL, &labels);
+                        self.set_last_collected(persisted.artifact.block_number);
+                    }
+                    None => self.registry.inc_counter(UNCHANGED_HEAD_TOTAL, &labels),
+                }
+                self.set_head(collection.head_block_number);
+            }
+            Err(_) => self.registry.inc_counter(FAILURES_TOTAL, &labels),
+        }
+        self.flush();
+    }
+
+    fn set_last_collected(&mut self, number: u64) {
+        self.last_collected = Some(number);
+        self.set(LAST_COLLECTED_BLOCK, number);
+        self.update_lag();
+    }
+
+    fn set_head(&mut self, number: u64) {
+        self.head = Some(number);
+        self.set(HEAD_BLOCK, number);
+        self.update_lag();
+    }
+
+    fn update_lag(&self) {
+        if let (Some(head), Some(last_collected)) = (self.head, self.last_collected) {
+            self.set(LAG_BLOCKS, head.saturating_sub(last_collected));
+        }
+    }
+
+    fn set(&self, name: &str, value: u64) {
+        self.registry
+            .set_gauge(name, &[("network", self.network.as_str())], value as f64);
+    }
+
+    fn flush(&self) {
+        let Some(path) = &self.textfile else {
+            return;
+        };
+        if let Err(error) = self.registry.write_textfile(path) {
+            warn!(?error, path = %path.display(), "failed to write metrics textfile");
+        }
+    }
 }
 
 fn read_state(path: &std::path::Path) -> anyhow::Result<Option<CollectorState>> {
@@ -159,6 +617,113 @@ mod tests {
         assert!(second.write.path.exists());
     }
 
+    #[test]
+    fn reorg_tracker_marks_canonical_and_orphaned_variants() {
+        let config = test_config("reorg_tracker");
+        let first = collect_generated(&config, generated_input(42, B256::repeat_byte(0xaa)), None)
+            .unwrap()
+            .unwrap();
+        let second = collect_generated(
+            &config,
+            generated_input(42, B256::repeat_byte(0xbb)),
+            Some(&first.artifact.block_hash),
+        )
+        .unwrap()
+        .unwrap();
+        let mut tracker = ReorgTracker::load(&config, Some(42)).unwrap();
+        assert_eq!(tracker.entries[&42].len(), 2);
+
+        let canonical = BTreeMap::from([(42, second.artifact.block_hash.clone())]);
+        tracker.apply_canonical_hashes(&config, &canonical);
+        tracker.apply_canonical_hashes(&config, &canonical);
+
+        let status = |hash: &str| {
+            export::index_entries(&config)
+                .unwrap()
+                .into_iter()
+                .find(|entry| entry.block_hash == hash)
+                .unwrap()
+                .chain_status
+        };
+        assert_eq!(
+            status(&first.artifact.block_hash),
+            Some(ChainStatus::Orphaned)
+        );
+        assert_eq!(
+            status(&second.artifact.block_hash),
+            Some(ChainStatus::Canonical)
+        );
+        let index = fs::read_to_string(config.index_path()).unwrap();
+        assert_eq!(index.lines().count(), 4);
+    }
+
+    #[test]
+    fn catch_up_plan_fetches_skipped_blocks_within_window() {
+        assert_eq!(catch_up_plan(41, 42, 64), None);
+        assert_eq!(catch_up_plan(42, 42, 64), None);
+        assert_eq!(catch_up_plan(45, 42, 64), None);
+        assert_eq!(
+            catch_up_plan(42, 45, 64),
+            Some(CatchUp {
+                blocks: 43..=44,
+                abandoned: None,
+            })
+        );
+        assert_eq!(
+            catch_up_plan(42, 50, 3),
+            Some(CatchUp {
+                blocks: 47..=49,
+                abandoned: Some(43..=46),
+            })
+        );
+        let disabled = catch_up_plan(42, 45, 0).unwrap();
+        assert!(disabled.blocks.is_empty());
+        assert_eq!(disabled.abandoned, Some(43..=44));
+    }
+
+    #[test]
+    fn collector_metrics_track_outcomes_and_lag() {
+        let config = test_config("metrics");
+        let textfile = config.out_root.join("collector.prom");
+        let mut metrics = CollectorMetrics::new(
+            Arc::new(Registry::new()),
+            Some(textfile.clone()),
+            &config.network,
+            Some(40),
+        );
+        let persisted =
+            collect_generated(&config, generated_input(42, B256::repeat_byte(0xaa)), None).unwrap();
+
+        metrics.set_head(45);
+        assert_eq!(metrics_value(&metrics, LAG_BLOCKS), Some(5.0));
+        metrics.record(&Ok(HeadCollection {
+            head_block_number: 42,
+            persisted,
+        }));
+        assert_eq!(metrics_value(&metrics, LAG_BLOCKS), Some(0.0));
+        metrics.record(&Err(anyhow::anyhow!("EL unavailable")));
+        metrics.record(&Ok(HeadCollection {
+            head_block_number: 46,
+            persisted: None,
+        }));
+
+        assert_eq!(metrics_value(&metrics, COLLECTED_TOTAL), Some(1.0));
+        assert_eq!(metrics_value(&metrics, UNCHANGED_HEAD_TOTAL), Some(1.0));
+        assert_eq!(metrics_value(&metrics, FAILURES_TOTAL), Some(1.0));
+        assert_eq!(metrics_value(&metrics, LAST_COLLECTED_BLOCK), Some(42.0));
+        assert_eq!(metrics_value(&metrics, LAG_BLOCKS), Some(4.0));
+        let rendered = fs::read_to_string(textfile).unwrap();
+        assert!(
+            rendered.contains("witness_collector_lag_blocks{network=\"glamsterdam-devnet-5\"} 4\n")
+        );
+    }
+
+    fn metrics_value(metrics: &CollectorMetrics, name: &str) -> Option<f64> {
+        metrics
+            .registry
+            .value(name, &[("network", "glamsterdam-devnet-5")])
+    }
+
     fn test_config(name: &str) -> CollectorConfig {
         let out_root = std::env::temp_dir().join(format!(
             "witness-generator-spec-cli-collector-{name}-{}",
@@ -167,13 +732,20 @@ mod tests {
         let _ = fs::remove_dir_all(&out_root);
         CollectorConfig {
             network: "glamsterdam-devnet-5".to_owned(),
-            cl_url: "http://cl".to_owned(),
-            el_url: "http://el".to_owned(),
+            cl_endpoints: vec![witness_generator_spec_cli::Endpoint::new("http://cl")],
+            el_endpoints: vec![witness_generator_spec_cli::Endpoint::new("http://el")],
             out_root,
             poll_interval: std::time::Duration::from_secs(4),
             request_timeout: std::time::Duration::from_secs(30),
+            rpc_retry: witness_generator_spec_cli::RetryConfig::default(),
             batch_size: 500,
             r2: None,
+            max_catch_up_blocks: 64,
+            reorg_check_depth: 32,
+            head_source: HeadSource::Poll,
+            head_event_timeout: std::time::Duration::from_secs(30),
+            metrics_listen: None,
+            metrics_textfile: None,
         }
     }
 
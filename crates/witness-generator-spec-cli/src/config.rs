@@ -1,23 +1,46 @@
-use std::{env, fs, path::PathBuf, time::Duration};
+use std::{collections::BTreeMap, env, fs, net::SocketAddr, path::PathBuf, time::Duration};
 
 use anyhow::{Context, ensure};
 use serde::Deserialize;
+use witness_generator_spec_cli::{Endpoint, RetryConfig};
 
 const DEFAULT_OUT_ROOT: &str = "/var/lib/stateless-inputs";
 const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(4);
 const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
 const DEFAULT_BATCH_SIZE: u64 = 500;
+const DEFAULT_MAX_CATCH_UP_BLOCKS: u64 = 64;
+const DEFAULT_REORG_CHECK_DEPTH: u64 = 32;
+const DEFAULT_HEAD_EVENT_TIMEOUT: Duration = Duration::from_secs(30);
 
 #[derive(Debug, Clone)]
 pub(crate) struct CollectorConfig {
     pub(crate) network: String,
-    pub(crate) cl_url: String,
-    pub(crate) el_url: String,
+    pub(crate) cl_endpoints: Vec<Endpoint>,
+    pub(crate) el_endpoints: Vec<Endpoint>,
     pub(crate) out_root: PathBuf,
     pub(crate) poll_interval: Duration,
     pub(crate) request_timeout: Duration,
+    pub(crate) rpc_retry: RetryConfig,
     pub(crate) batch_size: u64,
     pub(crate) r2: Option<R2PublishConfig>,
+    pub(crate) max_catch_up_blocks: u64,
+    pub(crate) reorg_check_depth: u64,
+    pub(crate) head_source: HeadSource,
+    pub(crate) head_event_timeout: Duration,
+    pub(crate) metrics_listen: Option<SocketAddr>,
+    pub(crate) metrics_textfile: Option<PathBuf>,
+}
+
+/// What triggers the next collection of the head.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum HeadSource {
+    /// Collect every `poll_interval`.
+    #[default]
+    Poll,
+    /// Collect on every `head` and `execution_payload` event of the Beacon API event stream,
+    /// polling while the stream is down.
+    BeaconEvents,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
@@ -31,13 +54,41 @@ pub(crate) struct R2PublishConfig {
 #[derive(Debug, Deserialize)]
 struct ConfigFile {
     network: String,
-    cl_url: Option<String>,
-    el_url: Option<String>,
+    cl_url: Option<EndpointsFile>,
+    el_url: Option<EndpointsFile>,
     out_root: Option<PathBuf>,
     poll_interval: Option<String>,
     request_timeout: Option<String>,
+    rpc_retries: Option<u32>,
+    rpc_backoff: Option<String>,
+    rpc_max_backoff: Option<String>,
     batch_size: Option<u64>,
     r2: Option<R2PublishConfig>,
+    max_catch_up_blocks: Option<u64>,
+    reorg_check_depth: Option<u64>,
+    head_source: Option<HeadSource>,
+    head_event_timeout: Option<String>,
+    metrics_listen: Option<SocketAddr>,
+    metrics_textfile: Option<PathBuf>,
+}
+
+/// One endpoint URL, or a list of URLs and `{ url, headers }` tables in order of preference.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum EndpointsFile {
+    One(String),
+    Many(Vec<EndpointFile>),
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum EndpointFile {
+    Url(String),
+    WithHeaders {
+        url: String,
+        #[serde(default)]
+        headers: BTreeMap<String, String>,
+    },
 }
 
 impl CollectorConfig {
@@ -53,9 +104,9 @@ impl CollectorConfig {
         let file: ConfigFile = toml::from_str(contents)?;
         ensure!(!file.network.trim().is_empty(), "network must not be empty");
 
-        let cl_url = endpoint_from_config_or_env(file.cl_url, "CL_RPC_URL")
+        let cl_endpoints = endpoints_from_config_or_env(file.cl_url, "CL_RPC_URL")
             .context("cl_url is required in config or CL_RPC_URL")?;
-        let el_url = endpoint_from_config_or_env(file.el_url, "EL_RPC_URL")
+        let el_endpoints = endpoints_from_config_or_env(file.el_url, "EL_RPC_URL")
             .context("el_url is required in config or EL_RPC_URL")?;
 
         let poll_interval = parse_duration_or_default(
@@ -68,20 +119,48 @@ impl CollectorConfig {
             DEFAULT_REQUEST_TIMEOUT,
             "request_timeout",
         )?;
+        let default_retry = RetryConfig::default();
+        let rpc_retry = RetryConfig {
+            max_retries: file.rpc_retries.unwrap_or(default_retry.max_retries),
+            initial_backoff: parse_duration_or_default(
+                file.rpc_backoff.as_deref(),
+                default_retry.initial_backoff,
+                "rpc_backoff",
+            )?,
+            max_backoff: parse_duration_or_default(
+                file.rpc_max_backoff.as_deref(),
+                default_retry.max_backoff,
+                "rpc_max_backoff",
+            )?,
+        };
+        let head_event_timeout = parse_duration_or_default(
+            file.head_event_timeout.as_deref(),
+            DEFAULT_HEAD_EVENT_TIMEOUT,
+            "head_event_timeout",
+        )?;
         let batch_size = file.batch_size.unwrap_or(DEFAULT_BATCH_SIZE);
         ensure!(batch_size > 0, "batch_size must be greater than zero");
 
         Ok(Self {
             network: file.network,
-            cl_url,
-            el_url,
+            cl_endpoints,
+            el_endpoints,
             out_root: file
                 .out_root
                 .unwrap_or_else(|| PathBuf::from(DEFAULT_OUT_ROOT)),
             poll_interval,
             request_timeout,
+            rpc_retry,
             batch_size,
             r2: file.r2.map(R2PublishConfig::normalize).transpose()?,
+            max_catch_up_blocks: file
+                .max_catch_up_blocks
+                .unwrap_or(DEFAULT_MAX_CATCH_UP_BLOCKS),
+            reorg_check_depth: file.reorg_check_depth.unwrap_or(DEFAULT_REORG_CHECK_DEPTH),
+            head_source: file.head_source.unwrap_or_default(),
+            head_event_timeout,
+            metrics_listen: file.metrics_listen,
+            metrics_textfile: file.metrics_textfile,
         })
     }
 
@@ -132,6 +211,46 @@ impl R2PublishConfig {
     }
 }
 
+fn endpoints_from_config_or_env(
+    file_value: Option<EndpointsFile>,
+    env_name: &str,
+) -> anyhow::Result<Vec<Endpoint>> {
+    let endpoints = match file_value {
+        Some(EndpointsFile::Many(endpoints)) if !endpoints.is_empty() => endpoints
+            .into_iter()
+            .map(EndpointFile::into_endpoint)
+            .collect::<Vec<_>>(),
+        Some(EndpointsFile::One(url)) => endpoint_from_config_or_env(Some(url), env_name)
+            .map(Endpoint::new)
+            .into_iter()
+            .collect(),
+        Some(EndpointsFile::Many(_)) | None => endpoint_from_config_or_env(None, env_name)
+            .map(Endpoint::new)
+            .into_iter()
+            .collect(),
+    };
+    ensure!(!endpoints.is_empty(), "no endpoint configured");
+    ensure!(
+        endpoints
+            .iter()
+            .all(|endpoint| !endpoint.url.trim().is_empty()),
+        "endpoint URLs must not be empty"
+    );
+    Ok(endpoints)
+}
+
+impl EndpointFile {
+    fn into_endpoint(self) -> Endpoint {
+        match self {
+            Self::Url(url) => Endpoint::new(url),
+            Self::WithHeaders { url, headers } => Endpoint {
+                url,
+                headers: headers.into_iter().collect(),
+            },
+        }
+    }
+}
+
 fn endpoint_from_config_or_env(file_value: Option<String>, env_name: &str) -> Option<String> {
     file_value
         .filter(|value| !value.trim().is_empty())
@@ -178,8 +297,16 @@ account_id = "abc123"
 
         assert_eq!(config.network, "glamsterdam-devnet-5");
         assert_eq!(config.out_root, PathBuf::from(DEFAULT_OUT_ROOT));
+        assert_eq!(config.cl_endpoints, vec![Endpoint::new("http://cl")]);
+        assert_eq!(config.el_endpoints, vec![Endpoint::new("http://el")]);
         assert_eq!(config.poll_interval, DEFAULT_POLL_INTERVAL);
+        assert_eq!(config.rpc_retry, RetryConfig::default());
         assert_eq!(config.batch_size, DEFAULT_BATCH_SIZE);
+        assert_eq!(config.max_catch_up_blocks, DEFAULT_MAX_CATCH_UP_BLOCKS);
+        assert_eq!(config.reorg_check_depth, DEFAULT_REORG_CHECK_DEPTH);
+        assert_eq!(config.head_source, HeadSource::Poll);
+        assert_eq!(config.head_event_timeout, DEFAULT_HEAD_EVENT_TIMEOUT);
+        assert_eq!(config.metrics_listen, None);
         let r2 = config.r2.unwrap();
         assert_eq!(r2.bucket, "stateless-inputs");
         assert_eq!(r2.prefix, "devnets");
@@ -197,7 +324,15 @@ el_url = "http://el"
 out_root = "/tmp/stateless"
 poll_interval = "10s"
 request_timeout = "45s"
+rpc_retries = 5
+rpc_backoff = "100ms"
+rpc_max_backoff = "2s"
 batch_size = 100
+max_catch_up_blocks = 0
+head_source = "beacon_events"
+head_event_timeout = "1m"
+metrics_listen = "127.0.0.1:9464"
+metrics_textfile = "/var/lib/node_exporter/witness-collector.prom"
 "#,
         )
         .unwrap();
@@ -205,7 +340,71 @@ batch_size = 100
         assert_eq!(config.out_root, PathBuf::from("/tmp/stateless"));
         assert_eq!(config.poll_interval, Duration::from_secs(10));
         assert_eq!(config.request_timeout, Duration::from_secs(45));
+        assert_eq!(
+            config.rpc_retry,
+            RetryConfig {
+                max_retries: 5,
+                initial_backoff: Duration::from_millis(100),
+                max_backoff: Duration::from_secs(2),
+            }
+        );
         assert_eq!(config.batch_size, 100);
+        assert_eq!(config.max_catch_up_blocks, 0);
+        assert_eq!(config.head_source, HeadSource::BeaconEvents);
+        assert_eq!(config.head_event_timeout, Duration::from_secs(60));
+        assert_eq!(
+            config.metrics_listen,
+            Some("127.0.0.1:9464".parse().unwrap())
+        );
+        assert_eq!(
+            config.metrics_textfile,
+            Some(PathBuf::from(
+                "/var/lib/node_exporter/witness-collector.prom"
+            ))
+        );
+    }
+
+    #[test]
+    fn parses_endpoint_lists_with_headers() {
+        let config = CollectorConfig::from_toml_str(
+            r#"
+network = "glamsterdam-devnet-5"
+cl_url = ["http://127.0.0.1:3500", "https://cl.example"]
+el_url = [
+    "http://127.0.0.1:8545",
+    { url = "https://el.example", headers = { Authorization = "Bearer token" } },
+]
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            config.cl_endpoints,
+            vec![
+                Endpoint::new("http://127.0.0.1:3500"),
+                Endpoint::new("https://cl.example"),
+            ]
+        );
+        assert_eq!(
+            config.el_endpoints,
+            vec![
+                Endpoint::new("http://127.0.0.1:8545"),
+                Endpoint {
+                    url: "https://el.example".to_owned(),
+                    headers: vec![("Authorization".to_owned(), "Bearer token".to_owned())],
+                },
+            ]
+        );
+        assert!(
+            CollectorConfig::from_toml_str(
+                r#"
+network = "glamsterdam-devnet-5"
+cl_url = ["http://cl", ""]
+el_url = "http://el"
+"#,
+            )
+            .is_err()
+        );
     }
 
     #[test]
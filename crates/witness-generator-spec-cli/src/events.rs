@@ -0,0 +1,286 @@
+//! Beacon API server-sent event stream of new heads.
+
+use anyhow::{Context, bail};
+use reqwest::{Client, Response};
+use serde::Deserialize;
+
+use crate::{
+    Endpoint, NetworkWitnessConfig,
+    rpc::{apply_headers, trim_endpoint},
+    serde_helpers::de_u64,
+};
+
+/// Beacon API event topic announcing a new head.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HeadTopic {
+    /// A new beacon block became the head.
+    Head,
+    /// The execution payload of a beacon block was revealed.
+    ExecutionPayload,
+}
+
+/// New head announced on the Beacon API event stream.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HeadEvent {
+    /// Topic of the event.
+    pub topic: HeadTopic,
+    /// Slot of the announced block.
+    pub slot: u64,
+}
+
+/// Subscription to the `head` and `execution_payload` topics of `/eth/v1/events`.
+///
+/// The stream connects on the first call to [`Self::next`] and reconnects on the call after the
+/// connection failed or was closed, moving on to the next consensus-layer endpoint.
+#[derive(Debug)]
+pub struct HeadEventStream {
+    http: Client,
+    endpoints: Vec<Endpoint>,
+    current: usize,
+    response: Option<Response>,
+    parser: SseParser,
+}
+
+#[derive(Debug, Deserialize)]
+struct HeadEventData {
+    #[serde(deserialize_with = "de_u64")]
+    slot: u64,
+}
+
+/// Event dispatched by the server-sent events parser.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct SseEvent {
+    event: String,
+    data: String,
+}
+
+/// Incremental parser of a `text/event-stream` body.
+#[derive(Debug, Default)]
+struct SseParser {
+    buffer: Vec<u8>,
+    event: String,
+    data: Vec<String>,
+}
+
+impl HeadEventStream {
+    pub(crate) fn new(config: &NetworkWitnessConfig) -> anyhow::Result<Self> {
+        // The stream stays open indefinitely, so only connecting is bounded by the timeout.
+        let http = Client::builder()
+            .connect_timeout(config.timeout)
+            .build()
+            .context("failed to build HTTP client")?;
+        Ok(Self {
+            http,
+            endpoints: config.cl_endpoints.clone(),
+            current: 0,
+            response: None,
+            parser: SseParser::default(),
+        })
+    }
+
+    /// Waits for the next head event, connecting first if the stream is not connected.
+    ///
+    /// Returns an error when connecting fails or the connection drops. Dropping the future
+    /// before it completes loses no events.
+    pub async fn next(&mut self) -> anyhow::Result<HeadEvent> {
+        loop {
+            while let Some(event) = self.parser.next_event() {
+                if let Some(head) = HeadEvent::from_sse(&event)? {
+                    return Ok(head);
+                }
+            }
+
+            let response = match self.response.take() {
+                Some(response) => response,
+                None => self.connect().await?,
+            };
+            let response = self.response.insert(response);
+            match response.chunk().await {
+                Ok(Some(chunk)) => self.parser.push(&chunk),
+                Ok(None) => {
+                    let url = self.url();
+                    self.disconnect();
+                    bail!("head event stream `{url}` was closed");
+                }
+                Err(error) => {
+                    let url = self.url();
+                    self.disconnect();
+                    return Err(error)
+                        .with_context(|| format!("failed to read head event stream `{url}`"));
+                }
+            }
+        }
+    }
+
+    /// Returns whether the stream is connected.
+    pub const fn is_connected(&self) -> bool {
+        self.response.is_some()
+    }
+
+    /// Drops the connection, so the next call to [`Self::next`] reconnects to the next
+    /// endpoint.
+    pub fn disconnect(&mut self) {
+        self.response = None;
+        self.parser = SseParser::default();
+        self.current = (self.current + 1) % self.endpoints.len();
+    }
+
+    async fn connect(&mut self) -> anyhow::Result<Response> {
+        let url = self.url();
+        let builder = self
+            .http
+            .get(&url)
+            .header(reqwest::header::ACCEPT, "text/event-stream");
+        let response = apply_headers(builder, &self.endpoints[self.current].headers)?
+            .send()
+            .await
+            .and_then(Response::error_for_status)
+            .with_context(|| format!("failed to subscribe to head events `{url}`"));
+        if response.is_err() {
+            self.current = (self.current + 1) % self.endpoints.len();
+        }
+        response
+    }
+
+    fn url(&self) -> String {
+        head_events_url(&self.endpoints[self.current].url)
+    }
+}
+
+impl HeadEvent {
+    fn from_sse(event: &SseEvent) -> anyhow::Result<Option<Self>> {
+        let topic = match event.event.as_str() {
+            "head" => HeadTopic::Head,
+            "execution_payload" => HeadTopic::ExecutionPayload,
+            _ => return Ok(None),
+        };
+        let data: HeadEventData = serde_json::from_str(&event.data)
+            .with_context(|| format!("failed to decode `{}` event", event.event))?;
+        Ok(Some(Self {
+            topic,
+            slot: data.slot,
+        }))
+    }
+}
+
+impl SseParser {
+    fn push(&mut self, bytes: &[u8]) {
+        self.buffer.extend_from_slice(bytes);
+    }
+
+    /// Consumes buffered lines until an event is complete.
+    fn next_event(&mut self) -> Option<SseEvent> {
+        while let Some(end) = self.buffer.iter().position(|byte| *byte == b'\n') {
+            let line = self.buffer.drain(..=end).collect::<Vec<_>>();
+            let line = String::from_utf8_lossy(&line);
+            let line = line.trim_end_matches(['\n', '\r']);
+            if line.is_empty() {
+                let event = std::mem::take(&mut self.event);
+                let data = std::mem::take(&mut self.data);
+                if !data.is_empty() {
+                    return Some(SseEvent {
+                        event: if event.is_empty() {
+                            "message".to_owned()
+                        } else {
+                            event
+                        },
+                        data: data.join("\n"),
+                    });
+                }
+                continue;
+            }
+
+            let (field, value) = line.split_once(':').unwrap_or((line, ""));
+            let value = value.strip_prefix(' ').unwrap_or(value);
+            match field {
+                "event" => self.event = value.to_owned(),
+                "data" => self.data.push(value.to_owned()),
+                // Comments (empty field) keep the connection alive; `id` and `retry` are unused.
+                _ => {}
+            }
+        }
+        None
+    }
+}
+
+fn head_events_url(endpoint: &str) -> String {
+    format!(
+        "{}/eth/v1/events?topics=head,execution_payload",
+        trim_endpoint(endpoint)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_server::{self, StubResponse};
+
+    #[test]
+    fn parses_events_split_across_chunks() {
+        let mut parser = SseParser::default();
+        parser.push(b": keep-alive\n\nevent: head\r\ndata: {\"slot\":");
+        assert_eq!(parser.next_event(), None);
+
+        parser.push(b"\"10\"}\r\n\r\nevent: execution_payload\ndata: {\"slot\":\"10\"}\n\n");
+        let head = parser.next_event().unwrap();
+        assert_eq!(
+            HeadEvent::from_sse(&head).unwrap(),
+            Some(HeadEvent {
+                topic: HeadTopic::Head,
+                slot: 10,
+            })
+        );
+        let payload = parser.next_event().unwrap();
+        assert_eq!(
+            HeadEvent::from_sse(&payload).unwrap(),
+            Some(HeadEvent {
+                topic: HeadTopic::ExecutionPayload,
+                slot: 10,
+            })
+        );
+        assert_eq!(parser.next_event(), None);
+
+        parser.push(b"event: finalized_checkpoint\ndata: {}\n\n");
+        let other = parser.next_event().unwrap();
+        assert_eq!(HeadEvent::from_sse(&other).unwrap(), None);
+    }
+
+    #[test]
+    fn builds_head_events_url() {
+        assert_eq!(
+            head_events_url("http://127.0.0.1:3500/"),
+            "http://127.0.0.1:3500/eth/v1/events?topics=head,execution_payload"
+        );
+    }
+
+    #[tokio::test]
+    async fn reconnects_to_stub_event_server() -> anyhow::Result<()> {
+        let (endpoint, server) = test_server::serve(vec![
+            StubResponse::event_stream(
+                "event: head\ndata: {\"slot\":\"10\"}\n\n\
+                 event: execution_payload\ndata: {\"slot\":\"10\"}\n\n",
+            ),
+            StubResponse::event_stream("event: head\ndata: {\"slot\":\"11\"}\n\n"),
+        ])?;
+
+        let mut events = HeadEventStream::new(&NetworkWitnessConfig::new(endpoint, "http://el"))?;
+        assert_eq!(events.next().await?.slot, 10);
+        assert_eq!(events.next().await?.topic, HeadTopic::ExecutionPayload);
+        assert!(events.next().await.is_err());
+        assert!(!events.is_connected());
+        assert_eq!(
+            events.next().await?,
+            HeadEvent {
+                topic: HeadTopic::Head,
+                slot: 11,
+            }
+        );
+
+        let paths = server.join().unwrap();
+        assert_eq!(
+            paths,
+            vec!["/eth/v1/events?topics=head,execution_payload"; 2]
+        );
+        Ok(())
+    }
+}
@@ -12,7 +12,7 @@ use tracing::{info, warn};
 
 use crate::{
     artifact::{
-        self, ARTIFACT_SCHEMA_VERSION, ArtifactIndexEntry, BATCH_MANIFEST_PATH,
+        self, ARTIFACT_SCHEMA_VERSION, ArtifactIndexEntry, BATCH_MANIFEST_PATH, ChainStatus,
         StatelessInputArtifact, fixture_archive_path, path_to_slash_string,
         read_artifact_with_json, relative_artifact_path_from_parts, sha256_hex,
     },
@@ -60,6 +60,8 @@ struct BatchManifest {
     artifact_count: usize,
     created_at: String,
     artifacts: Vec<BatchManifestArtifact>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    orphaned_artifacts: Vec<OrphanedArtifact>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -76,11 +78,21 @@ struct BatchManifestArtifact {
     fixture_byte_length: usize,
 }
 
+/// Artifact of a block that was reorged out, listed in the batch manifest but not archived.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct OrphanedArtifact {
+    block_number: u64,
+    block_hash: String,
+    slot_number: u64,
+}
+
 pub(crate) fn export_batches(
     config: &CollectorConfig,
     force: bool,
 ) -> anyhow::Result<Vec<PathBuf>> {
     let (by_block, discovery) = artifacts_by_block(config)?;
+    let (by_block, orphaned) = split_orphans(by_block);
     let complete_batches = complete_batch_starts(&by_block, config.batch_size);
     let mut exported = Vec::new();
     let mut skipped = 0_usize;
@@ -94,6 +106,7 @@ pub(crate) fn export_batches(
         invalid = discovery.invalid,
         duplicates = discovery.duplicates,
         conflicts = discovery.conflicts,
+        orphaned = orphaned.values().map(Vec::len).sum::<usize>(),
         "discovered artifact metadata"
     );
 
@@ -113,7 +126,8 @@ pub(crate) fn export_batches(
         }
 
         let artifacts = batch_artifacts(&by_block, start, end);
-        write_batch_archive(config, start, end, &artifacts, &out_path, force)?;
+        let orphans = batch_artifacts(&orphaned, start, end);
+        write_batch_archive(config, start, end, &artifacts, &orphans, &out_path, force)?;
         exported.push(out_path);
     }
 
@@ -128,6 +142,28 @@ pub(crate) fn export_batches(
     Ok(exported)
 }
 
+/// Usable entries of `index.jsonl`, with the latest chain status of every artifact.
+pub(crate) fn index_entries(config: &CollectorConfig) -> anyhow::Result<Vec<ArtifactIndexEntry>> {
+    Ok(load_index(config)?.entries.into_values().collect())
+}
+
+/// Block numbers with a usable entry in `index.jsonl` that is not orphaned.
+pub(crate) fn indexed_block_numbers(config: &CollectorConfig) -> anyhow::Result<BTreeSet<u64>> {
+    Ok(load_index(config)?
+        .entries
+        .values()
+        .filter(|entry| entry.chain_status != Some(ChainStatus::Orphaned))
+        .map(|entry| entry.block_number)
+        .collect())
+}
+
+/// Block numbers with at least one exportable artifact, as seen by batch export.
+pub(crate) fn available_block_numbers(config: &CollectorConfig) -> anyhow::Result<BTreeSet<u64>> {
+    let (by_block, _) = artifacts_by_block(config)?;
+    let (by_block, _) = split_orphans(by_block);
+    Ok(by_block.into_keys().collect())
+}
+
 fn artifacts_by_block(
     config: &CollectorConfig,
 ) -> anyhow::Result<(BTreeMap<u64, Vec<ArtifactDescriptor>>, DiscoveryStats)> {
@@ -235,6 +271,12 @@ fn load_index(config: &CollectorConfig) -> anyhow::Result<IndexCache> {
             std::collections::btree_map::Entry::Vacant(slot) => {
                 slot.insert(entry);
             }
+            std::collections::btree_map::Entry::Occupied(mut slot)
+                if slot.get().same_artifact(&entry) && slot.get() != &entry =>
+            {
+                // A later line records a newer chain status of the same artifact.
+                slot.insert(entry);
+            }
             std::collections::btree_map::Entry::Occupied(slot) if slot.get() == &entry => {
                 warn!(
                     path = %relative_path.display(),
@@ -305,6 +347,37 @@ fn recover_artifact_metadata(
     Ok(metadata)
 }
 
+/// Separates orphaned artifacts from the exportable ones. A height with a canonical artifact
+/// keeps only canonical artifacts, heights whose artifacts were never checked keep every variant.
+fn split_orphans(
+    by_block: BTreeMap<u64, Vec<ArtifactDescriptor>>,
+) -> (
+    BTreeMap<u64, Vec<ArtifactDescriptor>>,
+    BTreeMap<u64, Vec<ArtifactDescriptor>>,
+) {
+    let mut exportable = BTreeMap::new();
+    let mut orphaned = BTreeMap::new();
+    for (block_number, descriptors) in by_block {
+        let has_canonical = descriptors
+            .iter()
+            .any(|descriptor| descriptor.metadata.chain_status == Some(ChainStatus::Canonical));
+        let (keep, orphans): (Vec<_>, Vec<_>) = descriptors.into_iter().partition(|descriptor| {
+            match descriptor.metadata.chain_status {
+                Some(ChainStatus::Canonical) => true,
+                Some(ChainStatus::Orphaned) => false,
+                None => !has_canonical,
+            }
+        });
+        if !keep.is_empty() {
+            exportable.insert(block_number, keep);
+        }
+        if !orphans.is_empty() {
+            orphaned.insert(block_number, orphans);
+        }
+    }
+    (exportable, orphaned)
+}
+
 fn complete_batch_starts(
     by_block: &BTreeMap<u64, Vec<ArtifactDescriptor>>,
     batch_size: u64,
@@ -338,6 +411,7 @@ fn write_batch_archive(
     start: u64,
     end: u64,
     artifacts: &[&ArtifactDescriptor],
+    orphans: &[&ArtifactDescriptor],
     out_path: &Path,
     force: bool,
 ) -> anyhow::Result<()> {
@@ -347,7 +421,7 @@ fn write_batch_archive(
             .with_context(|| format!("failed to create directory {}", parent.display()))?;
     }
 
-    let write_result = write_batch_archive_part(config, start, end, artifacts, &part_path);
+    let write_result = write_batch_archive_part(config, start, end, artifacts, orphans, &part_path);
     if let Err(error) = write_result {
         if part_path.exists() {
             fs::remove_file(&part_path).with_context(|| {
@@ -380,6 +454,7 @@ fn write_batch_archive_part(
     start: u64,
     end: u64,
     artifacts: &[&ArtifactDescriptor],
+    orphans: &[&ArtifactDescriptor],
     part_path: &Path,
 ) -> anyhow::Result<()> {
     let file = fs::File::create(part_path)
@@ -422,6 +497,14 @@ fn write_batch_archive_part(
         artifact_count: manifest_artifacts.len(),
         created_at: artifact::utc_now_rfc3339()?,
         artifacts: manifest_artifacts,
+        orphaned_artifacts: orphans
+            .iter()
+            .map(|descriptor| OrphanedArtifact {
+                block_number: descriptor.metadata.block_number,
+                block_hash: descriptor.metadata.block_hash.clone(),
+                slot_number: descriptor.metadata.slot_number,
+            })
+            .collect(),
     };
     let manifest_bytes =
         serde_json::to_vec_pretty(&manifest).context("failed to serialize batch manifest")?;
@@ -674,6 +757,57 @@ mod tests {
         );
     }
 
+    #[test]
+    fn exports_only_canonical_variants_and_lists_orphans() {
+        let config = test_config("canonical_variants", 2);
+        let canonical = write_generated_artifact(&config, 0, B256::repeat_byte(0xaa));
+        let orphan = write_generated_artifact(&config, 0, B256::repeat_byte(0xbb));
+        let unchecked = write_generated_artifact(&config, 1, B256::repeat_byte(0xcc));
+        let marked_canonical = ArtifactIndexEntry {
+            chain_status: Some(ChainStatus::Canonical),
+            ..canonical.clone()
+        };
+        let marked_orphan = ArtifactIndexEntry {
+            chain_status: Some(ChainStatus::Orphaned),
+            ..orphan.clone()
+        };
+        append_index_entries(
+            &config,
+            [
+                &canonical,
+                &orphan,
+                &unchecked,
+                &marked_canonical,
+                &marked_orphan,
+            ],
+        );
+
+        let entries = index_entries(&config).unwrap();
+        assert_eq!(entries.len(), 3);
+        assert!(entries.contains(&marked_canonical));
+        assert!(entries.contains(&marked_orphan));
+
+        let archive = export_batches(&config, false).unwrap().remove(0);
+        let manifest = read_manifest_from_archive(&archive);
+        assert_eq!(manifest.artifact_count, 2);
+        assert_eq!(
+            manifest
+                .artifacts
+                .iter()
+                .map(|artifact| artifact.block_hash.as_str())
+                .collect::<Vec<_>>(),
+            [canonical.block_hash.as_str(), unchecked.block_hash.as_str()]
+        );
+        assert_eq!(
+            manifest.orphaned_artifacts,
+            vec![OrphanedArtifact {
+                block_number: 0,
+                block_hash: orphan.block_hash.clone(),
+                slot_number: orphan.slot_number,
+            }]
+        );
+    }
+
     #[test]
     fn extracted_batch_is_directly_loadable_by_benchmark_runner() {
         let config = test_config("benchmark_ready", 2);
@@ -773,13 +907,20 @@ mod tests {
         let _ = fs::remove_dir_all(&out_root);
         CollectorConfig {
             network: "glamsterdam-devnet-5".to_owned(),
-            cl_url: "http://cl".to_owned(),
-            el_url: "http://el".to_owned(),
+            cl_endpoints: vec![witness_generator_spec_cli::Endpoint::new("http://cl")],
+            el_endpoints: vec![witness_generator_spec_cli::Endpoint::new("http://el")],
             out_root,
             poll_interval: std::time::Duration::from_secs(4),
             request_timeout: std::time::Duration::from_secs(30),
+            rpc_retry: witness_generator_spec_cli::RetryConfig::default(),
             batch_size,
             r2: None,
+            max_catch_up_blocks: 64,
+            reorg_check_depth: 32,
+            head_source: crate::config::HeadSource::Poll,
+            head_event_timeout: std::time::Duration::from_secs(30),
+            metrics_listen: None,
+            metrics_textfile: None,
         }
     }
 }
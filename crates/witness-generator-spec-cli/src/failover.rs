@@ -0,0 +1,291 @@
+//! Retry with backoff and health-based failover across RPC endpoints.
+
+use std::{
+    process,
+    sync::{
+        Arc, OnceLock,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use tokio::time;
+use tracing::warn;
+
+use crate::{Endpoint, RetryConfig};
+
+/// Endpoints of one layer with their error counters, shared by the clones of a client.
+#[derive(Debug, Clone)]
+pub(crate) struct EndpointPool {
+    layer: &'static str,
+    endpoints: Arc<[EndpointHealth]>,
+}
+
+#[derive(Debug)]
+struct EndpointHealth {
+    endpoint: Endpoint,
+    errors: AtomicU64,
+    consecutive_failures: AtomicU64,
+}
+
+/// Failure of a single attempt, classified by whether another attempt may succeed.
+#[derive(Debug)]
+pub(crate) struct AttemptError {
+    error: anyhow::Error,
+    retryable: bool,
+}
+
+/// Attempts of one request, moving to the healthiest remaining endpoint after each failure.
+#[derive(Debug)]
+pub(crate) struct Attempts<'a> {
+    pool: &'a EndpointPool,
+    retry: &'a RetryConfig,
+    order: Vec<usize>,
+    attempt: u32,
+}
+
+impl EndpointPool {
+    pub(crate) fn new(layer: &'static str, endpoints: &[Endpoint]) -> Self {
+        Self {
+            layer,
+            endpoints: endpoints
+                .iter()
+                .cloned()
+                .map(|endpoint| EndpointHealth {
+                    endpoint,
+                    errors: AtomicU64::new(0),
+                    consecutive_failures: AtomicU64::new(0),
+                })
+                .collect(),
+        }
+    }
+
+    /// Starts a request. Endpoints are tried by fewest consecutive failures, then in
+    /// configuration order.
+    pub(crate) fn attempts<'a>(&'a self, retry: &'a RetryConfig) -> Attempts<'a> {
+        let mut order = (0..self.endpoints.len()).collect::<Vec<_>>();
+        order.sort_by_key(|&index| {
+            self.endpoints[index]
+                .consecutive_failures
+                .load(Ordering::Relaxed)
+        });
+        Attempts {
+            pool: self,
+            retry,
+            order,
+            attempt: 0,
+        }
+    }
+}
+
+impl Attempts<'_> {
+    /// Endpoint of the current attempt.
+    pub(crate) fn endpoint(&self) -> &Endpoint {
+        &self.health().endpoint
+    }
+
+    /// Records the outcome of the current attempt.
+    ///
+    /// Every failure counts against the endpoint's health, retryable or not. Returns the result
+    /// of the request, or `None` after sleeping the backoff when the failure is retryable and
+    /// retries are left.
+    pub(crate) async fn settle<T>(
+        &mut self,
+        result: Result<T, AttemptError>,
+    ) -> Option<anyhow::Result<T>> {
+        let health = self.health();
+        let AttemptError { error, retryable } = match result {
+            Ok(value) => {
+                health.consecutive_failures.store(0, Ordering::Relaxed);
+                return Some(Ok(value));
+            }
+            Err(error) => error,
+        };
+
+        let errors = health.errors.fetch_add(1, Ordering::Relaxed) + 1;
+        let consecutive_failures = health.consecutive_failures.fetch_add(1, Ordering::Relaxed) + 1;
+        let retrying = retryable && self.attempt < self.retry.max_retries;
+        warn!(
+            layer = self.pool.layer,
+            endpoint = %health.endpoint.url,
+            errors,
+            consecutive_failures,
+            attempt = self.attempt + 1,
+            retryable,
+            retrying,
+            ?error,
+            "RPC request failed",
+        );
+        if !retrying {
+            return Some(Err(error));
+        }
+
+        time::sleep(backoff(self.retry, self.attempt, jitter())).await;
+        self.attempt += 1;
+        None
+    }
+
+    fn health(&self) -> &EndpointHealth {
+        let index = self.order[self.attempt as usize % self.order.len()];
+        &self.pool.endpoints[index]
+    }
+}
+
+impl AttemptError {
+    pub(crate) const fn fatal(error: anyhow::Error) -> Self {
+        Self {
+            error,
+            retryable: false,
+        }
+    }
+
+    /// Classifies an HTTP failure: timeouts, connection and body errors, HTTP 429 and 5xx
+    /// responses are retryable.
+    pub(crate) fn http(error: reqwest::Error, context: String) -> Self {
+        let retryable = error.is_timeout()
+            || error.is_connect()
+            || error.is_request()
+            || error.is_body()
+            || error.status().is_some_and(|status| {
+                status.is_server_error() || status == reqwest::StatusCode::TOO_MANY_REQUESTS
+            });
+        Self {
+            error: anyhow::Error::new(error).context(context),
+            retryable,
+        }
+    }
+
+    /// Classifies a JSON-RPC error: server errors `-32000` to `-32099`, such as `-32000` for
+    /// missing state or `-32005` for rate limits, and internal errors `-32603` are retryable.
+    pub(crate) fn json_rpc(code: i64, error: anyhow::Error) -> Self {
+        Self {
+            error,
+            retryable: (-32099..=-32000).contains(&code) || code == -32603,
+        }
+    }
+}
+
+/// Exponential backoff before retry `attempt + 1`, with `jitter` in `[0, 1]` spreading it over
+/// the upper half so that clients retrying together do not stay in lockstep.
+fn backoff(retry: &RetryConfig, attempt: u32, jitter: f64) -> Duration {
+    let backoff = retry
+        .initial_backoff
+        .saturating_mul(2_u32.saturating_pow(attempt))
+        .min(retry.max_backoff);
+    let half = backoff / 2;
+    half + half.mul_f64(jitter)
+}
+
+/// Returns a jitter in `[0, 1)` from a process-wide SplitMix64 sequence.
+///
+/// Jitter only has to keep clients from retrying in lockstep, so a cheap non-cryptographic
+/// generator is enough. The sequence is seeded from the clock and process id on first use.
+fn jitter() -> f64 {
+    const GAMMA: u64 = 0x9e37_79b9_7f4a_7c15;
+    static STATE: OnceLock<AtomicU64> = OnceLock::new();
+    let state = STATE.get_or_init(|| {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_nanos() as u64);
+        AtomicU64::new(nanos ^ u64::from(process::id()).rotate_left(32))
+    });
+
+    let mut z = state
+        .fetch_add(GAMMA, Ordering::Relaxed)
+        .wrapping_add(GAMMA);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^= z >> 31;
+    // The top 53 bits fill the mantissa of an `f64` in `[0, 1)`.
+    (z >> 11) as f64 / (1_u64 << 53) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::anyhow;
+
+    use super::*;
+
+    #[test]
+    fn backoff_doubles_up_to_the_maximum() {
+        let retry = RetryConfig {
+            max_retries: 5,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_millis(350),
+        };
+
+        assert_eq!(backoff(&retry, 0, 1.0), Duration::from_millis(100));
+        assert_eq!(backoff(&retry, 0, 0.0), Duration::from_millis(50));
+        assert_eq!(backoff(&retry, 1, 1.0), Duration::from_millis(200));
+        assert_eq!(backoff(&retry, 2, 1.0), Duration::from_millis(350));
+        assert_eq!(backoff(&retry, 40, 0.0), Duration::from_millis(175));
+        assert!((0.0..=1.0).contains(&jitter()));
+    }
+
+    #[test]
+    fn classifies_json_rpc_errors() {
+        for code in [-32000, -32005, -32099, -32603] {
+            assert!(AttemptError::json_rpc(code, anyhow!("transient")).retryable);
+        }
+        for code in [-32601, -32602, -32700, 3] {
+            assert!(!AttemptError::json_rpc(code, anyhow!("permanent")).retryable);
+        }
+    }
+
+    #[tokio::test]
+    async fn fails_over_to_the_healthiest_endpoint() {
+        let pool = EndpointPool::new(
+            "EL",
+            &[Endpoint::new("http://a"), Endpoint::new("http://b")],
+        );
+        let retry = RetryConfig {
+            max_retries: 2,
+            initial_backoff: Duration::from_millis(1),
+            max_backoff: Duration::from_millis(1),
+        };
+
+        let mut attempts = pool.attempts(&retry);
+        assert_eq!(attempts.endpoint().url, "http://a");
+        let failed = Err::<(), _>(AttemptError::json_rpc(-32000, anyhow!("header not found")));
+        assert!(attempts.settle(failed).await.is_none());
+        assert_eq!(attempts.endpoint().url, "http://b");
+        assert!(matches!(attempts.settle(Ok(())).await, Some(Ok(()))));
+
+        // The failing endpoint is tried last until it succeeds again.
+        let mut attempts = pool.attempts(&retry);
+        assert_eq!(attempts.endpoint().url, "http://b");
+        assert!(matches!(attempts.settle(Ok(())).await, Some(Ok(()))));
+        assert_eq!(pool.endpoints[0].errors.load(Ordering::Relaxed), 1);
+        assert_eq!(pool.endpoints[1].errors.load(Ordering::Relaxed), 0);
+    }
+
+    #[tokio::test]
+    async fn non_retryable_failures_count_against_the_endpoint() {
+        let pool = EndpointPool::new(
+            "EL",
+            &[Endpoint::new("http://a"), Endpoint::new("http://b")],
+        );
+        let retry = RetryConfig {
+            max_retries: 2,
+            initial_backoff: Duration::from_millis(1),
+            max_backoff: Duration::from_millis(1),
+        };
+
+        let mut attempts = pool.attempts(&retry);
+        let failed = Err::<(), _>(AttemptError::fatal(anyhow!("invalid params")));
+        assert!(matches!(attempts.settle(failed).await, Some(Err(_))));
+        let health = &pool.endpoints[0];
+        assert_eq!(health.errors.load(Ordering::Relaxed), 1);
+        assert_eq!(health.consecutive_failures.load(Ordering::Relaxed), 1);
+
+        // Without a retry, the failing endpoint still moves behind the healthy one.
+        assert_eq!(pool.attempts(&retry).endpoint().url, "http://b");
+    }
+
+    #[test]
+    fn jitter_varies_between_calls() {
+        let jitters: Vec<f64> = (0..8).map(|_| jitter()).collect();
+        assert!(jitters.iter().all(|value| (0.0..1.0).contains(value)));
+        assert!(jitters.windows(2).any(|pair| pair[0] != pair[1]));
+    }
+}
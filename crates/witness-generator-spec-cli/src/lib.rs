@@ -2,14 +2,19 @@
 
 mod builder;
 mod chain_config;
+mod events;
+mod failover;
 mod rpc;
 mod serde_helpers;
+#[cfg(test)]
+mod test_server;
 
 use std::time::Duration;
 
 use alloy_primitives::B256;
 use anyhow::{Context, ensure};
 pub use builder::GeneratedInput;
+pub use events::{HeadEvent, HeadEventStream, HeadTopic};
 use reqwest::Client;
 
 // These dependencies are used by this package's CLI target.
@@ -20,36 +25,76 @@ use humantime as _;
 use sha2 as _;
 use tar as _;
 use time as _;
-use tokio as _;
 use toml as _;
-use tracing as _;
 use tracing_subscriber as _;
+use zkevm_metrics as _;
 use zstd as _;
 
 /// Configuration for consensus-layer and execution-layer RPC access.
 #[derive(Debug, Clone)]
 pub struct NetworkWitnessConfig {
-    /// Consensus-layer Beacon API endpoint.
-    pub cl_endpoint: String,
-    /// Execution-layer JSON-RPC endpoint.
-    pub el_endpoint: String,
-    /// Request timeout.
+    /// Consensus-layer Beacon API endpoints, in order of preference.
+    pub cl_endpoints: Vec<Endpoint>,
+    /// Execution-layer JSON-RPC endpoints, in order of preference.
+    pub el_endpoints: Vec<Endpoint>,
+    /// Request timeout of a single attempt.
     pub timeout: Duration,
-    /// Extra HTTP headers sent to the consensus-layer endpoint.
-    pub cl_headers: Vec<(String, String)>,
-    /// Extra HTTP headers sent to the execution-layer endpoint.
-    pub el_headers: Vec<(String, String)>,
+    /// Retry policy shared by every request.
+    pub retry: RetryConfig,
+}
+
+/// RPC endpoint with the extra HTTP headers sent to it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Endpoint {
+    /// Endpoint URL.
+    pub url: String,
+    /// Extra HTTP headers.
+    pub headers: Vec<(String, String)>,
+}
+
+/// Retry policy for transient RPC failures.
+///
+/// Timeouts, connection failures, HTTP 429 and 5xx responses, and JSON-RPC server errors are
+/// retried with exponential backoff and jitter, each time on the healthiest endpoint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryConfig {
+    /// Retries after the first attempt of a request.
+    pub max_retries: u32,
+    /// Backoff before the first retry, doubled for every further retry.
+    pub initial_backoff: Duration,
+    /// Upper bound of the backoff.
+    pub max_backoff: Duration,
 }
 
 impl NetworkWitnessConfig {
-    /// Creates a config with a conservative default timeout and no custom headers.
+    /// Creates a config for one endpoint per layer with a conservative default timeout, the
+    /// default retry policy and no custom headers.
     pub fn new(cl_endpoint: impl Into<String>, el_endpoint: impl Into<String>) -> Self {
         Self {
-            cl_endpoint: cl_endpoint.into(),
-            el_endpoint: el_endpoint.into(),
+            cl_endpoints: vec![Endpoint::new(cl_endpoint)],
+            el_endpoints: vec![Endpoint::new(el_endpoint)],
             timeout: Duration::from_secs(30),
-            cl_headers: Vec::new(),
-            el_headers: Vec::new(),
+            retry: RetryConfig::default(),
+        }
+    }
+}
+
+impl Endpoint {
+    /// Creates an endpoint without custom headers.
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            headers: Vec::new(),
+        }
+    }
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(250),
+            max_backoff: Duration::from_secs(5),
         }
     }
 }
@@ -75,6 +120,14 @@ pub struct NetworkWitnessClient {
 impl NetworkWitnessClient {
     /// Creates a new network witness client.
     pub fn new(config: NetworkWitnessConfig) -> anyhow::Result<Self> {
+        ensure!(
+            !config.cl_endpoints.is_empty(),
+            "at least one consensus-layer endpoint is required"
+        );
+        ensure!(
+            !config.el_endpoints.is_empty(),
+            "at least one execution-layer endpoint is required"
+        );
         let http = Client::builder()
             .timeout(config.timeout)
             .build()
@@ -85,6 +138,14 @@ impl NetworkWitnessClient {
         })
     }
 
+    /// Subscribes to new heads on the Beacon API event stream of the consensus-layer endpoints.
+    ///
+    /// The returned stream connects lazily and reconnects to the next endpoint after the
+    /// connection drops.
+    pub fn head_events(&self) -> anyhow::Result<HeadEventStream> {
+        HeadEventStream::new(self.rpc.config())
+    }
+
     /// Fetches network data and returns canonical spec guest input and expected output bytes.
     pub async fn stateless_input_bytes(
         &self,
@@ -103,6 +164,22 @@ impl NetworkWitnessClient {
         builder::build_generated_input(envelope, witness, chain_id)
     }
 
+    /// Returns the hash of the execution block at the consensus head, fetching only its payload
+    /// envelope.
+    pub async fn head_block_hash(&self) -> anyhow::Result<B256> {
+        Ok(self
+            .rpc
+            .execution_payload_envelope("head")
+            .await?
+            .payload
+            .block_hash)
+    }
+
+    /// Returns the hash of the canonical execution block at `number`.
+    pub async fn block_hash_by_number(&self, number: u64) -> anyhow::Result<B256> {
+        Ok(self.rpc.eth_block_by_number(number).await?.hash)
+    }
+
     async fn fetch_payload_envelope(
         &self,
         selector: BlockSelector,
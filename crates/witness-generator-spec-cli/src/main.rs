@@ -6,6 +6,7 @@
 )]
 
 mod artifact;
+mod backfill;
 mod catalog;
 mod collector;
 mod config;
@@ -15,10 +16,11 @@ mod publish;
 use std::{
     fs,
     io::{self, Write},
+    num::NonZeroUsize,
     path::PathBuf,
 };
 
-use anyhow::Context;
+use anyhow::{Context, ensure};
 use clap::{Args, Parser, Subcommand};
 use config::CollectorConfig;
 use tracing::info;
@@ -58,6 +60,8 @@ enum Command {
     Generate(GenerateArgs),
     /// Poll the live network head and store one artifact per observed block.
     Collect(CollectArgs),
+    /// Collect a historical block range by execution block number, or report its gaps.
+    Backfill(BackfillArgs),
     /// Package complete local block ranges into downloadable batch archives.
     Export(ExportArgs),
     /// Publish exported batches and indexes to Cloudflare R2.
@@ -93,6 +97,26 @@ struct CollectArgs {
     once: bool,
 }
 
+#[derive(Debug, Clone, Args)]
+struct BackfillArgs {
+    /// TOML config path.
+    #[arg(long)]
+    config: PathBuf,
+    /// First execution block number of the range.
+    #[arg(long)]
+    from: u64,
+    /// Last execution block number of the range, inclusive.
+    #[arg(long)]
+    to: u64,
+    /// Maximum number of blocks fetched at the same time.
+    #[arg(long, default_value = "4")]
+    concurrency: NonZeroUsize,
+    /// Print the missing block numbers of every incomplete batch overlapping the range instead
+    /// of collecting them.
+    #[arg(long)]
+    gap_report: bool,
+}
+
 #[derive(Debug, Clone, Args)]
 struct ExportArgs {
     /// TOML config path.
@@ -124,6 +148,7 @@ async fn main() -> anyhow::Result<()> {
             let config = CollectorConfig::from_path(args.config)?;
             collector::collect(config, args.once).await
         }
+        Some(Command::Backfill(args)) => run_backfill(args).await,
         Some(Command::Export(args)) => {
             let config = CollectorConfig::from_path(args.config)?;
             let exported = export::export_batches(&config, args.force)?;
@@ -160,6 +185,38 @@ impl Cli {
     }
 }
 
+async fn run_backfill(args: BackfillArgs) -> anyhow::Result<()> {
+    ensure!(
+        args.from <= args.to,
+        "--from {} must not be greater than --to {}",
+        args.from,
+        args.to
+    );
+    let config = CollectorConfig::from_path(args.config)?;
+    let blocks = args.from..=args.to;
+
+    if args.gap_report {
+        let gaps = backfill::gap_report(&config, &blocks)?;
+        info!(incomplete_batches = gaps.len(), "reported missing blocks");
+        io::stdout().write_all(backfill::render_gap_report(&gaps).as_bytes())?;
+        return Ok(());
+    }
+
+    let summary = backfill::backfill(&config, blocks, args.concurrency).await?;
+    info!(
+        collected = summary.collected,
+        skipped = summary.skipped,
+        failed = summary.failed.len(),
+        "backfilled block range"
+    );
+    ensure!(
+        summary.failed.is_empty(),
+        "failed to backfill blocks {}",
+        backfill::format_block_ranges(&summary.failed)
+    );
+    Ok(())
+}
+
 async fn run_generate(args: GenerateArgs) -> anyhow::Result<()> {
     let selector = block_selector(args.block_id.as_deref(), args.execution_block_number);
     let client = NetworkWitnessClient::new(NetworkWitnessConfig::new(args.cl_url, args.el_url))?;
@@ -240,6 +297,29 @@ mod tests {
         }
     }
 
+    #[test]
+    fn parses_backfill_subcommand() {
+        let cli = Cli::try_parse_from([
+            "witness-generator-spec-cli",
+            "backfill",
+            "--config",
+            "/etc/witness-generator-spec-cli/glamsterdam-devnet-5.toml",
+            "--from",
+            "32500",
+            "--to",
+            "32999",
+            "--gap-report",
+        ])
+        .unwrap();
+
+        let Some(Command::Backfill(args)) = cli.command else {
+            panic!("expected backfill subcommand");
+        };
+        assert_eq!(args.from..=args.to, 32500..=32999);
+        assert_eq!(args.concurrency.get(), 4);
+        assert!(args.gap_report);
+    }
+
     #[test]
     fn clap_definition_is_valid() {
         Cli::command().debug_assert();
@@ -1,16 +1,17 @@
 //! Minimal Beacon API and JSON-RPC client/types for network witness generation.
 
 use alloy_primitives::{Address, B256, Bytes, FixedBytes};
-use anyhow::{Context, bail};
+use anyhow::{Context, anyhow};
 use reqwest::{
-    Client, RequestBuilder,
+    Client, RequestBuilder, Response,
     header::{HeaderName, HeaderValue},
 };
 use serde::{Deserialize, Serialize, de::DeserializeOwned};
 use serde_json::{Value, json};
 
 use crate::{
-    NetworkWitnessConfig,
+    Endpoint, NetworkWitnessConfig,
+    failover::{AttemptError, EndpointPool},
     serde_helpers::{de_u64, hex_quantity, parse_u64},
 };
 
@@ -18,6 +19,8 @@ use crate::{
 pub(crate) struct RpcClient {
     config: NetworkWitnessConfig,
     http: Client,
+    cl: EndpointPool,
+    el: EndpointPool,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -202,7 +205,7 @@ struct JsonRpcRequest<'a> {
     jsonrpc: &'static str,
     id: u64,
     method: &'a str,
-    params: Value,
+    params: &'a Value,
 }
 
 #[derive(Debug, Deserialize)]
@@ -218,29 +221,33 @@ struct JsonRpcError {
 }
 
 impl RpcClient {
-    pub(crate) const fn new(config: NetworkWitnessConfig, http: Client) -> Self {
-        Self { config, http }
+    pub(crate) fn new(config: NetworkWitnessConfig, http: Client) -> Self {
+        Self {
+            cl: EndpointPool::new("CL", &config.cl_endpoints),
+            el: EndpointPool::new("EL", &config.el_endpoints),
+            config,
+            http,
+        }
+    }
+
+    pub(crate) const fn config(&self) -> &NetworkWitnessConfig {
+        &self.config
     }
 
     pub(crate) async fn execution_payload_envelope(
         &self,
         block_id: &str,
     ) -> anyhow::Result<ExecutionPayloadEnvelope> {
-        let url = execution_payload_envelope_url(&self.config.cl_endpoint, block_id);
         let response: ExecutionPayloadEnvelopeResponse = self
-            .send_get_with_headers(&url, &self.config.cl_headers)
+            .cl_get(|endpoint| execution_payload_envelope_url(endpoint, block_id))
             .await
             .with_context(|| format!("failed to fetch execution payload envelope `{block_id}`"))?;
         Ok(response.data.message)
     }
 
     pub(crate) async fn beacon_genesis(&self) -> anyhow::Result<BeaconGenesis> {
-        let url = format!(
-            "{}/eth/v1/beacon/genesis",
-            trim_endpoint(&self.config.cl_endpoint)
-        );
         let response: BeaconGenesisResponse = self
-            .send_get_with_headers(&url, &self.config.cl_headers)
+            .cl_get(|endpoint| format!("{}/eth/v1/beacon/genesis", trim_endpoint(endpoint)))
             .await
             .context("failed to fetch CL genesis")?;
         Ok(BeaconGenesis {
@@ -249,12 +256,8 @@ impl RpcClient {
     }
 
     pub(crate) async fn beacon_spec(&self) -> anyhow::Result<BeaconSpec> {
-        let url = format!(
-            "{}/eth/v1/config/spec",
-            trim_endpoint(&self.config.cl_endpoint)
-        );
         let response: BeaconSpecResponse = self
-            .send_get_with_headers(&url, &self.config.cl_headers)
+            .cl_get(|endpoint| format!("{}/eth/v1/config/spec", trim_endpoint(endpoint)))
             .await
             .context("failed to fetch CL spec")?;
         Ok(BeaconSpec {
@@ -291,6 +294,24 @@ impl RpcClient {
     }
 
     async fn el_rpc<T>(&self, method: &str, params: Value) -> anyhow::Result<T>
+    where
+        T: DeserializeOwned,
+    {
+        let mut attempts = self.el.attempts(&self.config.retry);
+        loop {
+            let result = self.el_rpc_once(attempts.endpoint(), method, &params).await;
+            if let Some(result) = attempts.settle(result).await {
+                return result;
+            }
+        }
+    }
+
+    async fn el_rpc_once<T>(
+        &self,
+        endpoint: &Endpoint,
+        method: &str,
+        params: &Value,
+    ) -> Result<T, AttemptError>
     where
         T: DeserializeOwned,
     {
@@ -300,50 +321,73 @@ impl RpcClient {
             method,
             params,
         };
-        let url = trim_endpoint(&self.config.el_endpoint).to_owned();
+        let url = trim_endpoint(&endpoint.url).to_owned();
         let builder = self.http.post(url).json(&request);
-        let response: JsonRpcResponse<T> = apply_headers(builder, &self.config.el_headers)?
+        let response: JsonRpcResponse<T> = apply_headers(builder, &endpoint.headers)
+            .map_err(AttemptError::fatal)?
             .send()
             .await
-            .with_context(|| format!("JSON-RPC method `{method}` failed"))?
-            .error_for_status()
-            .with_context(|| format!("JSON-RPC method `{method}` returned HTTP error"))?
+            .and_then(Response::error_for_status)
+            .map_err(|error| {
+                AttemptError::http(error, format!("JSON-RPC method `{method}` failed"))
+            })?
             .json()
             .await
-            .with_context(|| format!("failed to decode JSON-RPC response for `{method}`"))?;
+            .map_err(|error| {
+                AttemptError::http(
+                    error,
+                    format!("failed to decode JSON-RPC response for `{method}`"),
+                )
+            })?;
 
         if let Some(error) = response.error {
-            bail!(
-                "JSON-RPC method `{method}` returned error {}: {}",
+            return Err(AttemptError::json_rpc(
                 error.code,
-                error.message
-            );
+                anyhow!(
+                    "JSON-RPC method `{method}` returned error {}: {}",
+                    error.code,
+                    error.message
+                ),
+            ));
         }
-        response
-            .result
-            .with_context(|| format!("JSON-RPC method `{method}` returned no result"))
+        response.result.ok_or_else(|| {
+            AttemptError::fatal(anyhow!("JSON-RPC method `{method}` returned no result"))
+        })
     }
 
-    async fn send_get_with_headers<T>(
-        &self,
-        url: &str,
-        headers: &[(String, String)],
-    ) -> anyhow::Result<T>
+    /// Sends a GET request to the URL `url` builds from a consensus-layer endpoint.
+    async fn cl_get<T>(&self, url: impl Fn(&str) -> String) -> anyhow::Result<T>
+    where
+        T: DeserializeOwned,
+    {
+        let mut attempts = self.cl.attempts(&self.config.retry);
+        loop {
+            let endpoint = attempts.endpoint();
+            let result = self.get_once(&url(&endpoint.url), &endpoint.headers).await;
+            if let Some(result) = attempts.settle(result).await {
+                return result;
+            }
+        }
+    }
+
+    async fn get_once<T>(&self, url: &str, headers: &[(String, String)]) -> Result<T, AttemptError>
     where
         T: DeserializeOwned,
     {
         let builder = self.http.get(url);
-        apply_headers(builder, headers)?
+        apply_headers(builder, headers)
+            .map_err(AttemptError::fatal)?
             .send()
-            .await?
-            .error_for_status()?
+            .await
+            .and_then(Response::error_for_status)
+            .map_err(|error| AttemptError::http(error, format!("GET `{url}` failed")))?
             .json()
             .await
-            .context("failed to decode JSON response")
+            .map_err(|error| AttemptError::http(error, "failed to decode JSON response".to_owned()))
     }
 }
 
-fn apply_headers(
+pub(crate) fn apply_headers(
     mut builder: RequestBuilder,
     headers: &[(String, String)],
 ) -> anyhow::Result<RequestBuilder> {
@@ -357,7 +401,7 @@ fn apply_headers(
     Ok(builder)
 }
 
-fn trim_endpoint(endpoint: &str) -> &str {
+pub(crate) fn trim_endpoint(endpoint: &str) -> &str {
     endpoint.trim_end_matches('/')
 }
 
@@ -371,6 +415,13 @@ fn execution_payload_envelope_url(endpoint: &str, block_id: &str) -> String {
 
 #[cfg(test)]
 mod tests {
+    use std::time::Duration;
+
+    use crate::{
+        Endpoint, RetryConfig,
+        test_server::{self, StubResponse},
+    };
+
     use super::*;
 
     #[test]
@@ -469,4 +520,40 @@ mod tests {
             B256::repeat_byte(0xaa)
         );
     }
+
+    #[tokio::test]
+    async fn retries_transient_errors_on_the_next_endpoint() -> anyhow::Result<()> {
+        let failing = test_server::serve(vec![
+            StubResponse::json(502, "bad gateway"),
+            StubResponse::json(503, "unavailable"),
+        ])?;
+        let healthy = test_server::serve(vec![
+            StubResponse::json(200, json_rpc_error(-32000, "header not found")),
+            StubResponse::json(200, r#"{"jsonrpc":"2.0","id":1,"result":"0x2a"}"#),
+            StubResponse::json(200, json_rpc_error(-32601, "method not found")),
+        ])?;
+        let mut config = NetworkWitnessConfig::new("http://cl", failing.0.clone());
+        config.el_endpoints.push(Endpoint::new(healthy.0.clone()));
+        config.retry = RetryConfig {
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(1),
+            max_backoff: Duration::from_millis(1),
+        };
+        let client = RpcClient::new(config, Client::new());
+
+        // Attempts alternate between the endpoints until the second one answers.
+        assert_eq!(client.eth_chain_id().await?, 42);
+        // The endpoint with consecutive failures is tried last, and a missing method is not
+        // retried.
+        let error = client.eth_chain_id().await.unwrap_err();
+        assert!(format!("{error:#}").contains("-32601"));
+
+        assert_eq!(failing.1.join().unwrap().len(), 2);
+        assert_eq!(healthy.1.join().unwrap().len(), 3);
+        Ok(())
+    }
+
+    fn json_rpc_error(code: i64, message: &str) -> String {
+        format!(r#"{{"jsonrpc":"2.0","id":1,"error":{{"code":{code},"message":"{message}"}}}}"#)
+    }
 }
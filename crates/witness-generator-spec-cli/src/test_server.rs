@@ -0,0 +1,75 @@
+//! Minimal HTTP server answering tests with canned responses.
+
+use std::{
+    io::{BufRead, BufReader, Read, Write},
+    net::TcpListener,
+    thread::{self, JoinHandle},
+};
+
+/// Canned HTTP response, sent with `connection: close`.
+#[derive(Debug, Clone)]
+pub(crate) struct StubResponse {
+    pub(crate) status: u16,
+    pub(crate) content_type: &'static str,
+    pub(crate) body: String,
+}
+
+impl StubResponse {
+    pub(crate) fn json(status: u16, body: impl Into<String>) -> Self {
+        Self {
+            status,
+            content_type: "application/json",
+            body: body.into(),
+        }
+    }
+
+    pub(crate) fn event_stream(body: impl Into<String>) -> Self {
+        Self {
+            status: 200,
+            content_type: "text/event-stream",
+            body: body.into(),
+        }
+    }
+}
+
+/// Serves `responses` in order, one per connection, on a local port.
+///
+/// Returns the endpoint URL and a handle yielding the request target of every served request.
+pub(crate) fn serve(
+    responses: Vec<StubResponse>,
+) -> anyhow::Result<(String, JoinHandle<Vec<String>>)> {
+    let listener = TcpListener::bind("127.0.0.1:0")?;
+    let endpoint = format!("http://{}", listener.local_addr()?);
+    let server = thread::spawn(move || {
+        let mut targets = Vec::new();
+        for response in responses {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut request_line = String::new();
+            reader.read_line(&mut request_line).unwrap();
+            targets.push(request_line.split(' ').nth(1).unwrap().to_owned());
+
+            let mut content_length = 0;
+            let mut line = String::new();
+            while reader.read_line(&mut line).unwrap() > 2 {
+                if let Some(length) = line.to_ascii_lowercase().strip_prefix("content-length:") {
+                    content_length = length.trim().parse().unwrap();
+                }
+                line.clear();
+            }
+            reader.read_exact(&mut vec![0; content_length]).unwrap();
+
+            write!(
+                stream,
+                "HTTP/1.1 {} STUB\r\ncontent-type: {}\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                response.status,
+                response.content_type,
+                response.body.len(),
+                response.body
+            )
+            .unwrap();
+        }
+        targets
+    });
+    Ok((endpoint, server))
+}